@@ -3,11 +3,14 @@
 //! This module provides functionality for analyzing dependencies between modules,
 //! including circular dependency detection and topological sorting.
 
+use anyhow::{Result, anyhow};
+
 use crate::{
     analyzers::types::{
         CircularDependencyAnalysis, CircularDependencyGroup, CircularDependencyType,
         ResolutionStrategy,
     },
+    config::{Config, CycleStrategy},
     dependency_graph::{DependencyGraph, ItemType},
 };
 
@@ -54,6 +57,89 @@ pub(crate) fn analyze_circular_dependencies(graph: &DependencyGraph) -> Circular
     }
 }
 
+/// Resolve every circular dependency with a fixed strategy, skipping cycle classification
+/// (`--ignore-circular-deps`)
+///
+/// Only `lazy` and `function-scope` can be applied without analysis. The cycles are not
+/// inspected, so they are recorded as function-level cycles, the only type whose imports the
+/// function-scope rewriting moves.
+pub(crate) fn assume_cycles_resolvable(
+    graph: &DependencyGraph,
+    strategy: CycleStrategy,
+) -> Result<CircularDependencyAnalysis> {
+    let suggested_resolution = match strategy {
+        CycleStrategy::Lazy => ResolutionStrategy::Lazy,
+        CycleStrategy::FunctionScope => ResolutionStrategy::Resolvable,
+        CycleStrategy::Auto | CycleStrategy::Error => {
+            return Err(anyhow!(
+                "Circular dependencies can only be ignored with the lazy or function-scope \
+                 strategy, got {strategy:?}"
+            ));
+        }
+    };
+
+    let resolvable_cycles = graph
+        .find_strongly_connected_components()
+        .into_iter()
+        .filter(|scc| scc.len() > 1)
+        .map(|modules| CircularDependencyGroup {
+            modules,
+            cycle_type: CircularDependencyType::FunctionLevel,
+            suggested_resolution: suggested_resolution.clone(),
+        })
+        .collect();
+
+    Ok(CircularDependencyAnalysis {
+        resolvable_cycles,
+        unresolvable_cycles: Vec::new(),
+    })
+}
+
+/// Apply the configured cycle strategies on top of the automatic classification
+///
+/// Returns the cycles forbidden by the `error` strategy; all other cycles are moved between
+/// `resolvable_cycles` and `unresolvable_cycles` according to their effective resolution.
+pub(crate) fn apply_cycle_strategies(
+    analysis: &mut CircularDependencyAnalysis,
+    config: &Config,
+    module_name: impl Fn(crate::resolver::ModuleId) -> Option<String>,
+) -> Vec<CircularDependencyGroup> {
+    let cycles = std::mem::take(&mut analysis.resolvable_cycles)
+        .into_iter()
+        .chain(std::mem::take(&mut analysis.unresolvable_cycles));
+    let mut forbidden = Vec::new();
+
+    for mut cycle in cycles {
+        let module_names: Vec<String> = cycle
+            .modules
+            .iter()
+            .filter_map(|&module_id| module_name(module_id))
+            .collect();
+        match config.cycle_strategy_for(&module_names) {
+            CycleStrategy::Auto => {}
+            CycleStrategy::FunctionScope => {
+                cycle.suggested_resolution = ResolutionStrategy::Resolvable;
+            }
+            CycleStrategy::Lazy => cycle.suggested_resolution = ResolutionStrategy::Lazy,
+            CycleStrategy::Error => {
+                forbidden.push(cycle);
+                continue;
+            }
+        }
+
+        if matches!(
+            cycle.suggested_resolution,
+            ResolutionStrategy::Unresolvable { .. }
+        ) {
+            analysis.unresolvable_cycles.push(cycle);
+        } else {
+            analysis.resolvable_cycles.push(cycle);
+        }
+    }
+
+    forbidden
+}
+
 /// Classify the type of circular dependency
 fn classify_cycle_type(
     graph: &DependencyGraph,
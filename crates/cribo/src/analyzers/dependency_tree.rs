@@ -0,0 +1,293 @@
+//! Import dependency trees (`cribo deps` and `--emit-dependency-tree`).
+//!
+//! Explains why a module ends up in the bundle: the tree of a single module lists the modules
+//! it imports (upstream) and the modules importing it (downstream), following the dependency
+//! graph up to a maximum depth. The import tree of the whole bundle starts at the entry module.
+
+use std::{fmt::Write, path::Path};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    dependency_graph::DependencyGraph,
+    resolver::{ModuleId, ModuleResolver},
+    types::FxIndexSet,
+};
+
+/// Which side of the dependency graph to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TreeDirection {
+    /// Modules imported by the module
+    Upstream,
+    /// Modules importing the module
+    Downstream,
+    Both,
+}
+
+impl std::str::FromStr for TreeDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "upstream" => Ok(Self::Upstream),
+            "downstream" => Ok(Self::Downstream),
+            "both" => Ok(Self::Both),
+            _ => Err(anyhow!(
+                "Invalid direction '{value}'. Supported directions: upstream, downstream, both"
+            )),
+        }
+    }
+}
+
+/// Render the dependency tree of `module_name` as text
+pub(crate) fn render(
+    graph: &DependencyGraph,
+    module_name: &str,
+    direction: TreeDirection,
+    max_depth: usize,
+) -> Result<String> {
+    let root = graph
+        .get_module_by_name(module_name)
+        .map(|module| module.module_id)
+        .ok_or_else(|| anyhow!("Module '{module_name}' is not part of the bundle"))?;
+
+    let mut out = format!("{module_name}\n");
+    if matches!(direction, TreeDirection::Upstream | TreeDirection::Both) {
+        out.push_str("imports:\n");
+        render_children(
+            &mut out,
+            graph,
+            &mut vec![root],
+            DependencyGraph::get_dependencies,
+            "",
+            max_depth,
+        );
+    }
+    if matches!(direction, TreeDirection::Downstream | TreeDirection::Both) {
+        out.push_str("imported by:\n");
+        render_children(
+            &mut out,
+            graph,
+            &mut vec![root],
+            DependencyGraph::get_dependents,
+            "",
+            max_depth,
+        );
+    }
+    Ok(out)
+}
+
+/// Render the neighbors of the last module in `path`, recursing until `remaining_depth` is used
+/// up. Modules already on the path are marked as cycles instead of being expanded again.
+fn render_children(
+    out: &mut String,
+    graph: &DependencyGraph,
+    path: &mut Vec<ModuleId>,
+    neighbors: fn(&DependencyGraph, ModuleId) -> Vec<ModuleId>,
+    prefix: &str,
+    remaining_depth: usize,
+) {
+    let Some(&current) = path.last() else {
+        return;
+    };
+    if remaining_depth == 0 {
+        return;
+    }
+
+    let mut children: Vec<(ModuleId, &str)> = neighbors(graph, current)
+        .into_iter()
+        .filter_map(|id| Some((id, graph.get_module(id)?.module_name.as_str())))
+        .collect();
+    children.sort_by(|a, b| a.1.cmp(b.1));
+
+    for (i, &(child, name)) in children.iter().enumerate() {
+        let is_last = i + 1 == children.len();
+        let branch = if is_last { "└── " } else { "├── " };
+        if path.contains(&child) {
+            writeln!(out, "{prefix}{branch}{name} (cycle)").expect("Writing to String never fails");
+            continue;
+        }
+        writeln!(out, "{prefix}{branch}{name}").expect("Writing to String never fails");
+
+        let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+        path.push(child);
+        render_children(
+            out,
+            graph,
+            path,
+            neighbors,
+            &child_prefix,
+            remaining_depth - 1,
+        );
+        path.pop();
+    }
+}
+
+/// Render the import tree of the whole bundle as file paths indented by depth, starting at
+/// the entry module
+///
+/// Paths are relative to the entry module's directory. An import of a module that is still
+/// being expanded is marked `(circular)`; a module whose imports were already listed elsewhere
+/// in the tree is marked `(see above)` instead of being expanded again.
+pub(crate) fn render_import_tree(graph: &DependencyGraph, resolver: &ModuleResolver) -> String {
+    let root_dir = resolver
+        .get_module_path(ModuleId::ENTRY)
+        .and_then(|path| path.parent().map(Path::to_path_buf));
+    let label = |id: ModuleId| match resolver.get_module_path(id) {
+        Some(path) => root_dir
+            .as_deref()
+            .and_then(|root_dir| path.strip_prefix(root_dir).ok())
+            .unwrap_or(&path)
+            .display()
+            .to_string(),
+        None => graph
+            .get_module(id)
+            .map(|module| module.module_name.clone())
+            .unwrap_or_default(),
+    };
+
+    let mut out = String::new();
+    writeln!(out, "{}", label(ModuleId::ENTRY)).expect("Writing to String never fails");
+    render_imports(
+        &mut out,
+        graph,
+        &label,
+        &mut vec![ModuleId::ENTRY],
+        &mut FxIndexSet::from_iter([ModuleId::ENTRY]),
+    );
+    out
+}
+
+/// Render the imports of the last module in `path` two spaces deeper than it, expanding each
+/// module's own imports only the first time it appears
+fn render_imports(
+    out: &mut String,
+    graph: &DependencyGraph,
+    label: &dyn Fn(ModuleId) -> String,
+    path: &mut Vec<ModuleId>,
+    expanded: &mut FxIndexSet<ModuleId>,
+) {
+    let Some(&current) = path.last() else {
+        return;
+    };
+    let indent = "  ".repeat(path.len());
+
+    let mut children: Vec<(ModuleId, String)> = graph
+        .get_dependencies(current)
+        .into_iter()
+        .map(|id| (id, label(id)))
+        .collect();
+    children.sort_by(|a, b| a.1.cmp(&b.1));
+
+    for (child, name) in children {
+        if path.contains(&child) {
+            writeln!(out, "{indent}{name} (circular)").expect("Writing to String never fails");
+            continue;
+        }
+        if !expanded.insert(child) {
+            let marker = if graph.get_dependencies(child).is_empty() {
+                ""
+            } else {
+                " (see above)"
+            };
+            writeln!(out, "{indent}{name}{marker}").expect("Writing to String never fails");
+            continue;
+        }
+        writeln!(out, "{indent}{name}").expect("Writing to String never fails");
+
+        path.push(child);
+        render_imports(out, graph, label, path, expanded);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_render_shows_imports_and_importers() {
+        let mut graph = DependencyGraph::new();
+        let main = graph.add_module(ModuleId::ENTRY, "main".to_owned(), Path::new("main.py"));
+        let utils = graph.add_module(
+            ModuleId::new(1),
+            "pkg.utils".to_owned(),
+            Path::new("pkg/utils.py"),
+        );
+        let helpers = graph.add_module(
+            ModuleId::new(2),
+            "pkg.helpers".to_owned(),
+            Path::new("pkg/helpers.py"),
+        );
+        let base = graph.add_module(
+            ModuleId::new(3),
+            "pkg.base".to_owned(),
+            Path::new("pkg/base.py"),
+        );
+        graph.add_module_dependency(main, utils);
+        graph.add_module_dependency(utils, helpers);
+        graph.add_module_dependency(utils, base);
+        graph.add_module_dependency(helpers, utils);
+
+        let tree =
+            render(&graph, "pkg.utils", TreeDirection::Both, 3).expect("pkg.utils is in the graph");
+        assert_eq!(
+            tree,
+            "pkg.utils\nimports:\n├── pkg.base\n└── pkg.helpers\n    └── pkg.utils \
+             (cycle)\nimported by:\n├── main\n└── pkg.helpers\n    └── pkg.utils (cycle)\n"
+        );
+
+        let shallow = render(&graph, "pkg.utils", TreeDirection::Upstream, 1)
+            .expect("pkg.utils is in the graph");
+        assert_eq!(
+            shallow,
+            "pkg.utils\nimports:\n├── pkg.base\n└── pkg.helpers\n"
+        );
+
+        assert!(render(&graph, "missing", TreeDirection::Both, 3).is_err());
+    }
+
+    #[test]
+    fn test_render_import_tree_indents_file_paths() {
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+        let files = [
+            ("main", "app/main.py"),
+            ("pkg", "app/pkg/__init__.py"),
+            ("pkg.utils", "app/pkg/utils.py"),
+            ("pkg.models", "app/pkg/models.py"),
+            ("pkg.db", "app/pkg/db.py"),
+        ];
+        let mut graph = DependencyGraph::new();
+        let ids: Vec<ModuleId> = files
+            .iter()
+            .map(|(name, path)| {
+                let id = resolver.register_module(name, Path::new(path));
+                graph.add_module(id, (*name).to_owned(), Path::new(path))
+            })
+            .collect();
+        let [main, pkg, utils, models, db] = ids[..] else {
+            panic!("expected five modules");
+        };
+        graph.add_module_dependency(main, pkg);
+        graph.add_module_dependency(main, models);
+        graph.add_module_dependency(pkg, utils);
+        graph.add_module_dependency(pkg, models);
+        graph.add_module_dependency(models, db);
+        graph.add_module_dependency(db, pkg);
+
+        assert_eq!(
+            render_import_tree(&graph, &resolver),
+            "\
+main.py
+  pkg/__init__.py
+    pkg/models.py
+      pkg/db.py
+        pkg/__init__.py (circular)
+    pkg/utils.py
+  pkg/models.py (see above)
+"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Code duplicated across bundles (`cribo report-duplication`).
+//!
+//! Compares the functions and classes defined by several bundles to find code worth factoring
+//! into a shared module. Definitions match when their name, signature and docstring are the
+//! same; definitions inside the module init functions of wrapped modules are compared as if
+//! they were at the top level.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::Ranged;
+
+use crate::{
+    code_generator::module_registry::is_init_function,
+    types::{FxIndexMap, FxIndexSet},
+};
+
+/// How a function or class is recognized in every bundle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Fingerprint {
+    /// `def` or `class`
+    keyword: &'static str,
+    name: String,
+    /// Parameters and return annotation of a function, or the bases of a class
+    signature: String,
+    docstring: Option<String>,
+}
+
+/// A definition found in one or more bundles
+struct Occurrences {
+    /// Bytes of the largest copy
+    size: usize,
+    /// Indices of the bundles defining it
+    bundles: FxIndexSet<usize>,
+}
+
+/// Render the functions and classes defined by more than one of `bundles`, largest savings
+/// first
+pub(crate) fn render(bundles: &[PathBuf]) -> Result<String> {
+    if bundles.len() < 2 {
+        return Err(anyhow!(
+            "Comparing for duplication needs at least two bundles"
+        ));
+    }
+
+    let mut occurrences: FxIndexMap<Fingerprint, Occurrences> = FxIndexMap::default();
+    for (index, bundle) in bundles.iter().enumerate() {
+        for (fingerprint, size) in collect_definitions(bundle)? {
+            let entry = occurrences
+                .entry(fingerprint)
+                .or_insert_with(|| Occurrences {
+                    size: 0,
+                    bundles: FxIndexSet::default(),
+                });
+            entry.size = entry.size.max(size);
+            entry.bundles.insert(index);
+        }
+    }
+
+    let mut duplicated: Vec<(String, &Occurrences)> = occurrences
+        .iter()
+        .filter(|(_, occurrences)| occurrences.bundles.len() > 1)
+        .map(|(fingerprint, occurrences)| {
+            (
+                format!(
+                    "{} {}{}",
+                    fingerprint.keyword, fingerprint.name, fingerprint.signature
+                ),
+                occurrences,
+            )
+        })
+        .collect();
+    if duplicated.is_empty() {
+        return Ok("No function or class appears in more than one bundle\n".to_owned());
+    }
+    duplicated.sort_by(|a, b| savings(b.1).cmp(&savings(a.1)).then_with(|| a.0.cmp(&b.0)));
+
+    let bundle_names: Vec<String> = bundles
+        .iter()
+        .map(|bundle| {
+            bundle.file_name().map_or_else(
+                || bundle.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    let name_width = duplicated
+        .iter()
+        .map(|(definition, _)| definition.len())
+        .chain(std::iter::once("Definition".len()))
+        .max()
+        .unwrap_or_default();
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "{:<name_width$}  {:>9}  Bundles",
+        "Definition", "Size (B)"
+    )
+    .expect("Writing to String never fails");
+    for (definition, occurrences) in &duplicated {
+        let names: Vec<&str> = occurrences
+            .bundles
+            .iter()
+            .map(|&index| bundle_names[index].as_str())
+            .collect();
+        writeln!(
+            output,
+            "{definition:<name_width$}  {:>9}  {}",
+            occurrences.size,
+            names.join(", ")
+        )
+        .expect("Writing to String never fails");
+    }
+    let total: usize = duplicated
+        .iter()
+        .map(|(_, occurrences)| savings(occurrences))
+        .sum();
+    writeln!(
+        output,
+        "{} definitions appear in more than one bundle; sharing them would save {total} B",
+        duplicated.len()
+    )
+    .expect("Writing to String never fails");
+    Ok(output)
+}
+
+/// Bytes saved by keeping a single copy of a definition
+fn savings(occurrences: &Occurrences) -> usize {
+    occurrences.size * (occurrences.bundles.len() - 1)
+}
+
+/// Parse a bundle and fingerprint its top-level functions and classes with their size
+fn collect_definitions(bundle: &Path) -> Result<Vec<(Fingerprint, usize)>> {
+    let source = fs::read_to_string(bundle)
+        .with_context(|| format!("Failed to read bundle {}", bundle.display()))?;
+    let module = ruff_python_parser::parse_module(&source)
+        .map_err(|e| anyhow!("Failed to parse bundle {}: {e}", bundle.display()))?
+        .into_syntax();
+
+    let mut definitions = Vec::new();
+    collect_from_body(&module.body, &source, &mut definitions);
+    Ok(definitions)
+}
+
+fn collect_from_body(body: &[Stmt], source: &str, definitions: &mut Vec<(Fingerprint, usize)>) {
+    for stmt in body {
+        let (fingerprint, range) = match stmt {
+            // The bundler's init functions only wrap the body of a module
+            Stmt::FunctionDef(function) if is_init_function(function.name.as_str()) => {
+                collect_from_body(&function.body, source, definitions);
+                continue;
+            }
+            Stmt::FunctionDef(function) => {
+                let mut signature = source_text(source, function.parameters.range());
+                if let Some(returns) = &function.returns {
+                    signature = format!("{signature} -> {}", source_text(source, returns.range()));
+                }
+                (
+                    Fingerprint {
+                        keyword: "def",
+                        name: function.name.to_string(),
+                        signature,
+                        docstring: docstring(&function.body),
+                    },
+                    function.range(),
+                )
+            }
+            Stmt::ClassDef(class) => (
+                Fingerprint {
+                    keyword: "class",
+                    name: class.name.to_string(),
+                    signature: class
+                        .arguments
+                        .as_ref()
+                        .map(|arguments| source_text(source, arguments.range()))
+                        .unwrap_or_default(),
+                    docstring: docstring(&class.body),
+                },
+                class.range(),
+            ),
+            _ => continue,
+        };
+        definitions.push((fingerprint, range.len().to_usize()));
+    }
+}
+
+/// Source text of `range` with runs of whitespace collapsed, so line wrapping does not matter
+fn source_text(source: &str, range: ruff_text_size::TextRange) -> String {
+    source[range]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn docstring(body: &[Stmt]) -> Option<String> {
+    let Some(Stmt::Expr(expr)) = body.first() else {
+        return None;
+    };
+    let Expr::StringLiteral(literal) = expr.value.as_ref() else {
+        return None;
+    };
+    Some(literal.value.to_str().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_render_lists_definitions_shared_by_bundles() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let first = temp_dir.path().join("first.py");
+        let second = temp_dir.path().join("second.py");
+        fs::write(
+            &first,
+            "\
+def helper(x, y):
+    \"\"\"Add two numbers.\"\"\"
+    return x + y
+def _cribo_init_pkg_models(self):
+    class Model(Base):
+        pass
+    return self
+def main():
+    return helper(1, 2)
+",
+        )?;
+        fs::write(
+            &second,
+            "\
+class Model(Base):
+    pass
+def helper(x, y):
+    \"\"\"Add two numbers.\"\"\"
+    return x + y
+def main(argv):
+    return helper(*argv)
+",
+        )?;
+
+        assert_eq!(
+            render(&[first, second])?,
+            "\
+Definition          Size (B)  Bundles
+def helper(x, y)          61  first.py, second.py
+class Model(Base)         31  first.py, second.py
+2 definitions appear in more than one bundle; sharing them would save 92 B
+"
+        );
+        Ok(())
+    }
+}
@@ -406,4 +406,41 @@ def f():
         assert!(info.global_declarations.contains_key("m"));
         assert!(info.functions_using_globals.contains("f"));
     }
+
+    #[test]
+    fn test_imported_global_is_lifted_per_module() {
+        let settings = parse_module(
+            "MY_SETTING = 1\n\ndef configure(value):\n    global MY_SETTING\n    MY_SETTING = \
+             value\n",
+        )
+        .expect("Test code should parse successfully");
+        let app = parse_module(
+            "from settings import MY_SETTING\n\ndef override():\n    global MY_SETTING\n    \
+             MY_SETTING = 2\n",
+        )
+        .expect("Test code should parse successfully");
+
+        let settings_info = GlobalAnalyzer::analyze("settings", settings.syntax())
+            .expect("Expected global info due to global in function");
+        let app_info = GlobalAnalyzer::analyze("app", app.syntax())
+            .expect("Expected global info due to global in function");
+
+        // `global` in `app` rebinds app's own import binding, never `settings.MY_SETTING`
+        let settings_lifter = crate::code_generator::globals::GlobalsLifter::new(&settings_info);
+        let app_lifter = crate::code_generator::globals::GlobalsLifter::new(&app_info);
+        assert_eq!(
+            settings_lifter
+                .lifted_names
+                .get("MY_SETTING")
+                .map(String::as_str),
+            Some("_cribo_settings_MY_SETTING")
+        );
+        assert_eq!(
+            app_lifter
+                .lifted_names
+                .get("MY_SETTING")
+                .map(String::as_str),
+            Some("_cribo_app_MY_SETTING")
+        );
+    }
 }
@@ -0,0 +1,525 @@
+//! Bundling anti-pattern checks (`cribo lint`).
+//!
+//! Flags code that is known to misbehave once bundled: `sys.path` manipulation (imports after it
+//! resolve differently at runtime than at bundle time), circular imports no strategy can
+//! resolve, `__import__` calls with a computed module name and `exec` of computed code (neither
+//! can be followed by the bundler), and `__del__` finalizers reading module-level variables
+//! (bundled modules may keep those in namespace objects that are torn down before the
+//! finalizer runs at interpreter exit). A diagnostic is suppressed by a `# cribo: ignore` comment
+//! on the line it is reported on.
+
+use std::{
+    fmt::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use ruff_python_ast::{
+    Expr, ExprContext, Stmt,
+    visitor::source_order::{SourceOrderVisitor, walk_expr, walk_stmt},
+};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::{
+    analyzers::types::{CircularDependencyGroup, ResolutionStrategy},
+    artifacts::{
+        attribution::{line_number, line_starts},
+        symbol_table::ModuleSource,
+    },
+    resolver::{ModuleId, ModuleResolver},
+    types::FxIndexSet,
+    visitors::ImportDiscoveryVisitor,
+};
+
+/// Comment suppressing the diagnostics reported on its line
+const IGNORE_COMMENT: &str = "# cribo: ignore";
+
+/// `sys.path` methods that change the module search path
+const SYS_PATH_MUTATORS: &[&str] = &["append", "clear", "extend", "insert", "pop", "remove"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiagnosticKind {
+    SysPathModification,
+    UnresolvableCycle,
+    DynamicImport,
+    DynamicExec,
+    FinalizerModuleState,
+}
+
+impl DiagnosticKind {
+    /// Short name shown in front of the message
+    pub(crate) const fn code(self) -> &'static str {
+        match self {
+            Self::SysPathModification => "sys-path-modification",
+            Self::UnresolvableCycle => "unresolvable-cycle",
+            Self::DynamicImport => "dynamic-import",
+            Self::DynamicExec => "dynamic-exec",
+            Self::FinalizerModuleState => "finalizer-module-state",
+        }
+    }
+}
+
+/// A bundling anti-pattern found at a source location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub path: PathBuf,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in bytes
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.kind.code(),
+            self.message
+        )
+    }
+}
+
+/// Check `modules` and the unresolvable `cycles` among them, in module order
+pub(crate) fn check(
+    modules: &[ModuleSource<'_>],
+    cycles: &[CircularDependencyGroup],
+    resolver: &ModuleResolver,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for module in modules {
+        let path = resolver
+            .get_module_path(module.module_id)
+            .unwrap_or_default();
+        let starts = line_starts(module.source);
+        let mut visitor = AntiPatternVisitor {
+            starts: &starts,
+            path: &path,
+            module_names: module_level_names(&module.ast.body),
+            strings: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        visitor.visit_body(&module.ast.body);
+
+        for cycle in cycles
+            .iter()
+            .filter(|cycle| cycle.modules.first() == Some(&module.module_id))
+        {
+            let range = cycle_import_range(module, cycle, resolver).unwrap_or_default();
+            visitor.report(
+                DiagnosticKind::UnresolvableCycle,
+                range,
+                cycle_message(cycle, resolver),
+            );
+        }
+
+        let ignored = ignored_lines(module.source, &starts, &visitor.strings);
+        diagnostics.extend(
+            visitor
+                .diagnostics
+                .into_iter()
+                .filter(|diagnostic| !ignored.contains(&diagnostic.line)),
+        );
+    }
+    diagnostics
+}
+
+/// Lines with a `# cribo: ignore` comment (occurrences inside string literals do not count)
+fn ignored_lines(source: &str, starts: &[usize], strings: &[TextRange]) -> FxIndexSet<usize> {
+    source
+        .match_indices(IGNORE_COMMENT)
+        .map(|(offset, _)| TextSize::try_from(offset).unwrap_or_default())
+        .filter(|offset| !strings.iter().any(|range| range.contains(*offset)))
+        .map(|offset| line_number(starts, offset))
+        .collect()
+}
+
+/// Range of the import in `module` that pulls in another module of `cycle`
+fn cycle_import_range(
+    module: &ModuleSource<'_>,
+    cycle: &CircularDependencyGroup,
+    resolver: &ModuleResolver,
+) -> Option<TextRange> {
+    let module_name = resolver.get_module_name(module.module_id)?;
+    let mut visitor = ImportDiscoveryVisitor::new();
+    for stmt in &module.ast.body {
+        visitor.visit_stmt(stmt);
+    }
+    visitor.into_imports().into_iter().find_map(|import| {
+        let written = import.module_name.as_deref().unwrap_or_default();
+        let base = if import.level == 0 {
+            written.to_owned()
+        } else {
+            resolver.resolve_relative_import_from_package_name(
+                import.level,
+                Some(written.trim_start_matches('.')),
+                &module_name,
+            )
+        };
+        // `from pkg import submodule` imports the submodule itself
+        let imports_cycle_member = std::iter::once(base.clone())
+            .chain(
+                import
+                    .names
+                    .iter()
+                    .map(|(name, _)| format!("{base}.{name}")),
+            )
+            .filter_map(|name| resolver.get_module_id_by_name(&name))
+            .any(|id| id != module.module_id && cycle.modules.contains(&id));
+        imports_cycle_member.then_some(import.range)
+    })
+}
+
+fn cycle_message(cycle: &CircularDependencyGroup, resolver: &ModuleResolver) -> String {
+    let names: Vec<String> = cycle
+        .modules
+        .iter()
+        .chain(cycle.modules.first())
+        .map(|&id| module_name(resolver, id))
+        .collect();
+    let mut message = format!(
+        "circular import that no strategy can resolve: {}",
+        names.join(" → ")
+    );
+    if let ResolutionStrategy::Unresolvable { reason } = &cycle.suggested_resolution {
+        let _ = write!(message, " ({reason})");
+    }
+    message
+}
+
+fn module_name(resolver: &ModuleResolver, id: ModuleId) -> String {
+    resolver
+        .get_module_name(id)
+        .unwrap_or_else(|| format!("module_{}", id.as_u32()))
+}
+
+fn is_sys_path(expr: &Expr) -> bool {
+    matches!(expr, Expr::Attribute(attr)
+        if attr.attr.as_str() == "path"
+            && matches!(&*attr.value, Expr::Name(name) if name.id.as_str() == "sys"))
+}
+
+/// Whether the first argument of a call is anything but a string literal
+fn has_computed_first_argument(call: &ruff_python_ast::ExprCall) -> bool {
+    !matches!(call.arguments.args.first(), Some(Expr::StringLiteral(_)))
+}
+
+/// Names bound by the top-level statements of a module
+fn module_level_names(body: &[Stmt]) -> FxIndexSet<String> {
+    let mut names = FxIndexSet::default();
+    for stmt in body {
+        match stmt {
+            Stmt::FunctionDef(function) => {
+                names.insert(function.name.to_string());
+            }
+            Stmt::ClassDef(class) => {
+                names.insert(class.name.to_string());
+            }
+            Stmt::Import(import) => names.extend(import.names.iter().map(|alias| {
+                alias.asname.as_ref().map_or_else(
+                    || alias.name.split('.').next().unwrap_or_default().to_owned(),
+                    ToString::to_string,
+                )
+            })),
+            Stmt::ImportFrom(import_from) => names.extend(
+                import_from
+                    .names
+                    .iter()
+                    .filter(|alias| alias.name.as_str() != "*")
+                    .map(|alias| alias.asname.as_ref().unwrap_or(&alias.name).to_string()),
+            ),
+            Stmt::Assign(assign) => {
+                for target in &assign.targets {
+                    collect_target_names(target, &mut names);
+                }
+            }
+            Stmt::AnnAssign(assign) => collect_target_names(&assign.target, &mut names),
+            Stmt::AugAssign(assign) => collect_target_names(&assign.target, &mut names),
+            _ => {}
+        }
+    }
+    names
+}
+
+fn collect_target_names(target: &Expr, names: &mut FxIndexSet<String>) {
+    match target {
+        Expr::Name(name) => {
+            names.insert(name.id.to_string());
+        }
+        Expr::Tuple(tuple) => {
+            for elt in &tuple.elts {
+                collect_target_names(elt, names);
+            }
+        }
+        Expr::List(list) => {
+            for elt in &list.elts {
+                collect_target_names(elt, names);
+            }
+        }
+        Expr::Starred(starred) => collect_target_names(&starred.value, names),
+        _ => {}
+    }
+}
+
+/// Names a function body reads and the names it binds itself
+#[derive(Default)]
+struct FunctionNames {
+    read: FxIndexSet<String>,
+    bound: FxIndexSet<String>,
+}
+
+impl<'a> SourceOrderVisitor<'a> for FunctionNames {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Name(name) = expr {
+            if matches!(name.ctx, ExprContext::Load) {
+                self.read.insert(name.id.to_string());
+            } else {
+                self.bound.insert(name.id.to_string());
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct AntiPatternVisitor<'s> {
+    starts: &'s [usize],
+    path: &'s Path,
+    /// Module-level names of the module being checked
+    module_names: FxIndexSet<String>,
+    /// Ranges of string literals, where an ignore comment is just text
+    strings: Vec<TextRange>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl AntiPatternVisitor<'_> {
+    fn report(&mut self, kind: DiagnosticKind, range: TextRange, message: String) {
+        let line = line_number(self.starts, range.start());
+        self.diagnostics.push(Diagnostic {
+            kind,
+            path: self.path.to_path_buf(),
+            line,
+            column: usize::from(range.start()) - self.starts[line - 1] + 1,
+            message,
+        });
+    }
+
+    fn report_sys_path(&mut self, range: TextRange) {
+        self.report(
+            DiagnosticKind::SysPathModification,
+            range,
+            "`sys.path` is modified; imports after it may resolve differently at runtime than \
+             when bundling"
+                .to_owned(),
+        );
+    }
+
+    /// Report a `__del__` method of a class reading module-level variables
+    fn check_finalizer(&mut self, class: &ruff_python_ast::StmtClassDef) {
+        for stmt in &class.body {
+            let Stmt::FunctionDef(function) = stmt else {
+                continue;
+            };
+            if function.name.as_str() != "__del__" {
+                continue;
+            }
+            let mut names = FunctionNames::default();
+            names.visit_body(&function.body);
+            let parameters: FxIndexSet<&str> = function
+                .parameters
+                .iter()
+                .map(|parameter| parameter.name().as_str())
+                .collect();
+            let referenced: Vec<&str> = names
+                .read
+                .iter()
+                .map(String::as_str)
+                .filter(|name| {
+                    self.module_names.contains(*name)
+                        && !names.bound.contains(*name)
+                        && !parameters.contains(name)
+                })
+                .collect();
+            if referenced.is_empty() {
+                continue;
+            }
+            self.report(
+                DiagnosticKind::FinalizerModuleState,
+                function.range,
+                format!(
+                    "`{}.__del__` reads module-level {}; once bundled they may live in a \
+                     namespace object that is already cleared when the finalizer runs",
+                    class.name,
+                    referenced
+                        .iter()
+                        .map(|name| format!("`{name}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+        }
+    }
+}
+
+impl<'a> SourceOrderVisitor<'a> for AntiPatternVisitor<'_> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        let modifies_sys_path = match stmt {
+            Stmt::Assign(assign) => assign.targets.iter().any(|target| {
+                is_sys_path(target)
+                    || matches!(target, Expr::Subscript(subscript) if is_sys_path(&subscript.value))
+            }),
+            Stmt::AugAssign(aug_assign) => is_sys_path(&aug_assign.target),
+            Stmt::Delete(delete) => delete.targets.iter().any(|target| {
+                matches!(target, Expr::Subscript(subscript) if is_sys_path(&subscript.value))
+            }),
+            Stmt::ClassDef(class) => {
+                self.check_finalizer(class);
+                false
+            }
+            _ => false,
+        };
+        if modifies_sys_path {
+            self.report_sys_path(stmt.range());
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::StringLiteral(_)
+            | Expr::BytesLiteral(_)
+            | Expr::FString(_)
+            | Expr::TString(_) => {
+                self.strings.push(expr.range());
+            }
+            Expr::Call(call) => match &*call.func {
+                Expr::Attribute(attr)
+                    if is_sys_path(&attr.value)
+                        && SYS_PATH_MUTATORS.contains(&attr.attr.as_str()) =>
+                {
+                    self.report_sys_path(call.range);
+                }
+                Expr::Name(name)
+                    if name.id.as_str() == "__import__" && has_computed_first_argument(call) =>
+                {
+                    self.report(
+                        DiagnosticKind::DynamicImport,
+                        call.range,
+                        "`__import__` with a computed module name cannot be followed by the \
+                         bundler"
+                            .to_owned(),
+                    );
+                }
+                Expr::Name(name)
+                    if name.id.as_str() == "exec" && has_computed_first_argument(call) =>
+                {
+                    self.report(
+                        DiagnosticKind::DynamicExec,
+                        call.range,
+                        "`exec` of dynamically constructed code; imports in it are not bundled"
+                            .to_owned(),
+                    );
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    fn lint(source: &str) -> Vec<(DiagnosticKind, usize)> {
+        let ast = parse_module(source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        let starts = line_starts(source);
+        let path = PathBuf::from("main.py");
+        let mut visitor = AntiPatternVisitor {
+            starts: &starts,
+            path: &path,
+            module_names: module_level_names(&ast.body),
+            strings: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        visitor.visit_body(&ast.body);
+        let ignored = ignored_lines(source, &starts, &visitor.strings);
+        visitor
+            .diagnostics
+            .into_iter()
+            .filter(|diagnostic| !ignored.contains(&diagnostic.line))
+            .map(|diagnostic| (diagnostic.kind, diagnostic.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_anti_patterns_reported_with_their_line() {
+        let source = "\
+import sys
+sys.path.insert(0, 'vendor')
+sys.path += ['lib']
+mod = __import__('json')
+plugin = __import__(name)
+exec('x = 1')
+
+def run(code):
+    exec(code)
+";
+        assert_eq!(
+            lint(source),
+            [
+                (DiagnosticKind::SysPathModification, 2),
+                (DiagnosticKind::SysPathModification, 3),
+                (DiagnosticKind::DynamicImport, 5),
+                (DiagnosticKind::DynamicExec, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_comment_suppresses_its_line_only() {
+        let source = "\
+import sys
+sys.path.append('a')  # cribo: ignore
+sys.path.append('b')
+message = '# cribo: ignore'; exec(message)
+";
+        assert_eq!(
+            lint(source),
+            [
+                (DiagnosticKind::SysPathModification, 3),
+                (DiagnosticKind::DynamicExec, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finalizer_reading_module_state_reported_at_its_def() {
+        let source = "\
+import os
+REGISTRY = {}
+
+class Handle:
+    def __del__(self):
+        REGISTRY.pop(id(self), None)
+
+class TempFile:
+    def __del__(self):  # cribo: ignore
+        os.remove(self.path)
+
+class Local:
+    def __del__(self, REGISTRY=None):
+        os = self.os
+        os.close(REGISTRY)
+";
+        assert_eq!(lint(source), [(DiagnosticKind::FinalizerModuleState, 5)]);
+    }
+}
@@ -5,10 +5,15 @@
 //! module dependencies, symbol relationships, and import requirements.
 
 pub(crate) mod dependency_analyzer;
+pub(crate) mod dependency_tree;
+pub(crate) mod duplication;
 pub(crate) mod global_analyzer;
 pub(crate) mod import_analyzer;
+pub(crate) mod lint;
 pub(crate) mod module_classifier;
+pub(crate) mod module_resolution;
 pub(crate) mod symbol_analyzer;
+pub(crate) mod symbol_trace;
 pub(crate) mod types;
 
 pub(crate) use global_analyzer::GlobalAnalyzer;
@@ -282,68 +282,69 @@ impl<'a> ModuleClassifier<'a> {
             }
         }
 
-        // Now expand wildcard imports in module_exports_map
-        for (module_id, wildcard_sources) in wildcard_imports {
-            // module_id is already a ModuleId from the wildcard_imports map
+        // Now expand wildcard imports in module_exports_map. Star imports can be chained
+        // (`pkg` star-imports `pkg.api`, which star-imports `pkg.core`), so keep expanding until
+        // no module gains new exports.
+        loop {
+            let mut changed = false;
+            for (module_id, wildcard_sources) in &wildcard_imports {
+                // Respect explicit __all__: don't auto-expand wildcard imports
+                if self.modules_with_explicit_all.contains(module_id) {
+                    let module_name = self
+                        .resolver
+                        .get_module_name(*module_id)
+                        .expect("Module name must exist for ModuleId");
+                    debug!(
+                        "Skipping wildcard expansion for module '{module_name}' due to explicit \
+                         __all__"
+                    );
+                    continue;
+                }
 
-            // Respect explicit __all__: don't auto-expand wildcard imports
-            if self.modules_with_explicit_all.contains(&module_id) {
                 let module_name = self
                     .resolver
-                    .get_module_name(module_id)
+                    .get_module_name(*module_id)
                     .expect("Module name must exist for ModuleId");
-                debug!(
-                    "Skipping wildcard expansion for module '{module_name}' due to explicit \
-                     __all__"
-                );
-                continue;
-            }
+                debug!("Module '{module_name}' has wildcard imports from: {wildcard_sources:?}");
 
-            let module_name = self
-                .resolver
-                .get_module_name(module_id)
-                .expect("Module name must exist for ModuleId");
-            debug!("Module '{module_name}' has wildcard imports from: {wildcard_sources:?}");
-
-            // Collect exports from all source modules first to avoid double borrow
-            let mut exports_to_add = Vec::new();
-            for source_module in &wildcard_sources {
-                let Some(source_id) = self.resolver.get_module_id_by_name(source_module) else {
-                    continue;
-                };
-                if let Some(source_exports) = module_exports_map.get(&source_id)
-                    && let Some(source_exports) = source_exports
-                {
-                    debug!(
-                        "  Expanding wildcard import from '{}' with {} exports",
-                        source_module,
-                        source_exports.len()
-                    );
-                    for export in source_exports {
-                        if export != "*" {
-                            exports_to_add.push(export.clone());
+                // Collect exports from all source modules first to avoid double borrow
+                let mut exports_to_add = Vec::new();
+                for source_module in wildcard_sources {
+                    let Some(source_id) = self.resolver.get_module_id_by_name(source_module) else {
+                        continue;
+                    };
+                    if let Some(source_exports) = module_exports_map.get(&source_id)
+                        && let Some(source_exports) = source_exports
+                    {
+                        debug!(
+                            "  Expanding wildcard import from '{}' with {} exports",
+                            source_module,
+                            source_exports.len()
+                        );
+                        for export in source_exports {
+                            if export != "*" {
+                                exports_to_add.push(export.clone());
+                            }
                         }
                     }
                 }
-            }
 
-            // Now add the collected exports to the module
-            if !exports_to_add.is_empty()
-                && let Some(exports) = module_exports_map.get_mut(&module_id)
-            {
-                if let Some(export_list) = exports {
+                // Now add the collected exports to the module
+                if !exports_to_add.is_empty()
+                    && let Some(exports) = module_exports_map.get_mut(module_id)
+                {
+                    let export_list = exports.get_or_insert_with(Vec::new);
+                    let previous_len = export_list.len();
                     // Merge, then sort + dedup for deterministic output
                     export_list.extend(exports_to_add);
                     export_list.sort();
                     export_list.dedup();
-                } else {
-                    // Module has no exports yet, create sorted, deduped list
-                    let mut list = exports_to_add;
-                    list.sort();
-                    list.dedup();
-                    *exports = Some(list);
+                    changed |= export_list.len() != previous_len;
                 }
             }
+            if !changed {
+                break;
+            }
         }
 
         ClassificationResult {
@@ -354,3 +355,55 @@ impl<'a> ModuleClassifier<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_chained_star_imports_expand_exports() {
+        let resolver = ModuleResolver::new(Config::default());
+        let sources = [
+            ("main", "import pkg\n"),
+            ("pkg", "from pkg.api import *\n"),
+            (
+                "pkg.api",
+                "from pkg.core import *\n\ndef request():\n    pass\n",
+            ),
+            (
+                "pkg.core",
+                "def helper():\n    pass\n\ndef _private():\n    pass\n",
+            ),
+        ];
+        let mut modules = FxIndexMap::default();
+        for (name, source) in sources {
+            let path = PathBuf::from(format!("{}.py", name.replace('.', "/")));
+            let module_id = resolver.register_module(name, Path::new(&path));
+            let ast = parse_module(source)
+                .expect("Test code should parse successfully")
+                .into_syntax();
+            modules.insert(module_id, (Arc::new(ast), path, String::new()));
+        }
+
+        let result = ModuleClassifier::new(
+            &resolver,
+            false,
+            FxIndexMap::default(),
+            FxIndexSet::default(),
+        )
+        .classify_modules(&modules, 10);
+
+        let pkg_id = resolver
+            .get_module_id_by_name("pkg")
+            .expect("pkg should be registered");
+        assert_eq!(
+            result.module_exports_map.get(&pkg_id),
+            Some(&Some(vec!["helper".to_owned(), "request".to_owned()]))
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Resolution of a single module name (`cribo resolve-path`).
+//!
+//! Shows how the resolver sees a module without bundling anything: the file it resolves to,
+//! the search path entry it is found in and how imports of it are classified.
+
+use std::fmt::Write;
+
+use anyhow::{Result, anyhow};
+
+use crate::resolver::{ImportType, ModuleResolver};
+
+/// Render how `module_name` resolves as text
+pub(crate) fn render(resolver: &ModuleResolver, module_name: &str) -> Result<String> {
+    if module_name.starts_with('.') {
+        return Err(anyhow!(
+            "Cannot resolve relative module name '{module_name}': use its absolute name"
+        ));
+    }
+
+    let mut output = String::new();
+    writeln!(output, "module: {module_name}").expect("Writing to String never fails");
+    match resolver.resolve_module_path(module_name)? {
+        Some(path) => {
+            writeln!(output, "path: {}", path.display()).expect("Writing to String never fails");
+            if let Some(search_dir) = resolver.find_search_directory(module_name) {
+                writeln!(output, "search path entry: {}", search_dir.display())
+                    .expect("Writing to String never fails");
+            }
+        }
+        None => {
+            output.push_str("path: not found\nsearched:\n");
+            for search_dir in resolver.get_search_directories() {
+                writeln!(output, "  {}", search_dir.display())
+                    .expect("Writing to String never fails");
+            }
+        }
+    }
+    writeln!(
+        output,
+        "classification: {}",
+        classification(&resolver.classify_import(module_name))
+    )
+    .expect("Writing to String never fails");
+    Ok(output)
+}
+
+const fn classification(import_type: &ImportType) -> &'static str {
+    match import_type {
+        ImportType::FirstParty => "first-party",
+        ImportType::ThirdParty => "third-party",
+        ImportType::StandardLibrary => "standard library",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_render_reports_path_search_entry_and_classification() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path().canonicalize()?;
+        let lib = root.join("lib");
+        fs::create_dir_all(lib.join("pkg/utils"))?;
+        fs::write(lib.join("pkg/__init__.py"), "")?;
+        fs::write(lib.join("pkg/utils/__init__.py"), "")?;
+        fs::write(lib.join("pkg/utils/helpers.py"), "")?;
+
+        let config = Config {
+            src: vec![root.clone(), lib.clone()],
+            ..Default::default()
+        };
+        let resolver = ModuleResolver::new_with_overrides(config, Some(""), None);
+
+        assert_eq!(
+            render(&resolver, "pkg.utils.helpers")?,
+            format!(
+                "module: pkg.utils.helpers\npath: {}\nsearch path entry: {}\nclassification: \
+                 first-party\n",
+                lib.join("pkg/utils/helpers.py").display(),
+                lib.display()
+            )
+        );
+        assert_eq!(
+            render(&resolver, "json")?,
+            format!(
+                "module: json\npath: not found\nsearched:\n  {}\n  {}\nclassification: standard \
+                 library\n",
+                root.display(),
+                lib.display()
+            )
+        );
+        assert!(render(&resolver, ".helpers").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+//! What happens to a single symbol while bundling (`--trace-symbol`).
+//!
+//! Follows one module-level symbol through the pipeline: the statement defining it, whether
+//! tree-shaking keeps it, how its module is bundled, the name it is renamed to, the imports
+//! referencing it and the bundle statements binding it. Each step is logged under
+//! [`LOG_TARGET`], which `--trace-symbol` enables regardless of the verbosity level.
+
+use anyhow::{Result, anyhow};
+use ruff_python_ast::{Expr, ModModule, Stmt};
+use ruff_python_codegen::{Generator, Stylist};
+
+use crate::{
+    code_generator::module_registry::is_init_function,
+    dependency_graph::{DependencyGraph, ItemType},
+    resolver::{ModuleId, ModuleResolver},
+    symbol_conflict_resolver::SymbolConflictResolver,
+    tree_shaking::TreeShaker,
+    types::FxIndexSet,
+};
+
+/// Log target of the trace
+pub(crate) const LOG_TARGET: &str = "cribo::trace";
+
+/// A module-level symbol named by its qualified name (e.g. `pkg.utils.helper`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TracedSymbol {
+    pub module: String,
+    pub symbol: String,
+}
+
+impl std::str::FromStr for TracedSymbol {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.rsplit_once('.') {
+            Some((module, symbol)) if !module.is_empty() && !symbol.is_empty() => Ok(Self {
+                module: module.to_owned(),
+                symbol: symbol.to_owned(),
+            }),
+            _ => Err(anyhow!(
+                "Invalid symbol '{value}': expected the module and the symbol name, e.g. \
+                 `pkg.utils.helper`"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TracedSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.module, self.symbol)
+    }
+}
+
+/// Results of the bundling pipeline the trace is read from
+pub(crate) struct TraceInputs<'a> {
+    pub graph: &'a DependencyGraph,
+    pub resolver: &'a ModuleResolver,
+    /// `None` when tree-shaking is disabled
+    pub tree_shaker: Option<&'a TreeShaker<'a>>,
+    pub conflict_resolver: &'a SymbolConflictResolver,
+    pub inlined_modules: &'a FxIndexSet<ModuleId>,
+    pub wrapper_modules: &'a FxIndexSet<ModuleId>,
+    pub bundled_ast: &'a ModModule,
+    pub stylist: &'a Stylist<'a>,
+}
+
+/// Describe every step of the pipeline that involves `traced`, one line per step
+pub(crate) fn trace(traced: &TracedSymbol, inputs: &TraceInputs<'_>) -> Vec<String> {
+    let Some(module) = inputs.graph.get_module_by_name(&traced.module) else {
+        return vec![format!(
+            "module {} is not part of the bundle",
+            traced.module
+        )];
+    };
+    let module_id = module.module_id;
+    let mut steps = Vec::new();
+
+    let path = inputs
+        .resolver
+        .get_module_path(module_id)
+        .map_or_else(|| traced.module.clone(), |path| path.display().to_string());
+    match module
+        .items
+        .values()
+        .find(|item| item.defined_symbols.contains(&traced.symbol))
+    {
+        Some(item) => {
+            let kind = match item.item_type {
+                ItemType::FunctionDef { .. } => "a function",
+                ItemType::ClassDef { .. } => "a class",
+                ItemType::Assignment { .. } => "an assignment",
+                ItemType::Import { .. } | ItemType::FromImport { .. } => "an import",
+                _ => "a statement",
+            };
+            steps.push(format!("defined in {path} by {kind}"));
+        }
+        None => {
+            steps.push(format!("not defined at the top level of {path}"));
+            return steps;
+        }
+    }
+
+    steps.push(match inputs.tree_shaker {
+        None => "tree-shaking is disabled".to_owned(),
+        Some(tree_shaker) if tree_shaker.is_symbol_used(&traced.module, &traced.symbol) => {
+            "kept by tree-shaking".to_owned()
+        }
+        Some(_) => "removed by tree-shaking".to_owned(),
+    });
+
+    if inputs.inlined_modules.contains(&module_id) {
+        steps.push(format!("module {} is inlined", traced.module));
+    } else if inputs.wrapper_modules.contains(&module_id) {
+        steps.push(format!(
+            "module {} is wrapped in an init function",
+            traced.module
+        ));
+    }
+
+    let bundled_name = match inputs
+        .conflict_resolver
+        .symbol_registry()
+        .get_rename(module_id, &traced.symbol)
+    {
+        Some(rename) => {
+            steps.push(format!("renamed to {rename} to resolve a name conflict"));
+            rename
+        }
+        None => traced.symbol.as_str(),
+    };
+
+    for importer in inputs.graph.modules.values() {
+        for item in importer.items.values() {
+            let ItemType::FromImport {
+                module: imported,
+                names,
+                level,
+                is_star,
+            } = &item.item_type
+            else {
+                continue;
+            };
+            let imported = if *level > 0 {
+                inputs.resolver.resolve_relative_import_from_package_name(
+                    *level,
+                    Some(imported.trim_start_matches('.')).filter(|name| !name.is_empty()),
+                    &importer.module_name,
+                )
+            } else {
+                imported.clone()
+            };
+            if imported != traced.module {
+                continue;
+            }
+            if *is_star {
+                steps.push(format!("star-imported by {}", importer.module_name));
+            }
+            for (name, alias) in names {
+                if *name == traced.symbol {
+                    let local = alias.as_deref().unwrap_or(name);
+                    steps.push(format!("imported by {} as {local}", importer.module_name));
+                }
+            }
+        }
+    }
+
+    let mut bindings = Vec::new();
+    collect_bindings(
+        &inputs.bundled_ast.body,
+        &traced.symbol,
+        bundled_name,
+        None,
+        &mut bindings,
+    );
+    if bindings.is_empty() {
+        steps.push(format!("not bound in the bundle as {bundled_name}"));
+    }
+    for (index, init_function, stmt) in bindings {
+        let code = Generator::from(inputs.stylist).stmt(stmt);
+        let first_line = code.lines().next().unwrap_or_default();
+        steps.push(match init_function {
+            Some(init_function) => format!("bound in {init_function} of the bundle: {first_line}"),
+            None => format!("bound by bundle statement {index}: {first_line}"),
+        });
+    }
+
+    steps
+}
+
+/// Collect the statements binding `bundled_name`, or assigning it to an attribute named
+/// `symbol` (namespace and module attributes), descending into module init functions
+fn collect_bindings<'a>(
+    body: &'a [Stmt],
+    symbol: &str,
+    bundled_name: &str,
+    init_function: Option<&'a str>,
+    bindings: &mut Vec<(usize, Option<&'a str>, &'a Stmt)>,
+) {
+    let binds =
+        |target: &Expr, value: Option<&Expr>| match target {
+            Expr::Name(name) => name.id.as_str() == bundled_name,
+            Expr::Attribute(attribute) => attribute.attr.as_str() == symbol
+                && value.is_some_and(
+                    |value| matches!(value, Expr::Name(name) if name.id.as_str() == bundled_name),
+                ),
+            _ => false,
+        };
+
+    for (index, stmt) in body.iter().enumerate() {
+        let is_binding = match stmt {
+            Stmt::FunctionDef(function) if is_init_function(function.name.as_str()) => {
+                collect_bindings(
+                    &function.body,
+                    symbol,
+                    bundled_name,
+                    Some(function.name.as_str()),
+                    bindings,
+                );
+                false
+            }
+            Stmt::FunctionDef(function) => function.name.as_str() == bundled_name,
+            Stmt::ClassDef(class) => class.name.as_str() == bundled_name,
+            Stmt::Assign(assign) => assign
+                .targets
+                .iter()
+                .any(|target| binds(target, Some(&assign.value))),
+            Stmt::AnnAssign(assign) => binds(&assign.target, assign.value.as_deref()),
+            _ => false,
+        };
+        if is_binding {
+            bindings.push((index, init_function, stmt));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{config::Config, dependency_graph::ItemData, types::FxIndexMap};
+
+    fn item(item_type: ItemType, defined_symbols: &[&str]) -> ItemData {
+        ItemData {
+            item_type,
+            defined_symbols: defined_symbols
+                .iter()
+                .map(|&name| name.to_owned())
+                .collect(),
+            read_vars: FxIndexSet::default(),
+            eventual_read_vars: FxIndexSet::default(),
+            var_decls: FxIndexSet::default(),
+            write_vars: FxIndexSet::default(),
+            eventual_write_vars: FxIndexSet::default(),
+            has_side_effects: false,
+            imported_names: FxIndexSet::default(),
+            reexported_names: FxIndexSet::default(),
+            symbol_dependencies: FxIndexMap::default(),
+            attribute_accesses: FxIndexMap::default(),
+            containing_scope: None,
+        }
+    }
+
+    #[test]
+    fn test_trace_follows_symbol_from_definition_to_bundle() {
+        let resolver = ModuleResolver::new(Config::default());
+        let main = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("pkg.utils", Path::new("pkg/utils.py"));
+        let mut graph = DependencyGraph::new();
+        graph.add_module(main, "main".to_owned(), Path::new("main.py"));
+        graph.add_module(utils, "pkg.utils".to_owned(), Path::new("pkg/utils.py"));
+        graph
+            .get_module_by_name_mut("pkg.utils")
+            .expect("pkg.utils is in the graph")
+            .add_item(item(
+                ItemType::FunctionDef {
+                    name: "helper".to_owned(),
+                },
+                &["helper"],
+            ));
+        graph
+            .get_module_by_name_mut("main")
+            .expect("main is in the graph")
+            .add_item(item(
+                ItemType::FromImport {
+                    module: "pkg.utils".to_owned(),
+                    names: vec![("helper".to_owned(), Some("assist".to_owned()))],
+                    level: 0,
+                    is_star: false,
+                },
+                &["assist"],
+            ));
+
+        let source = "def helper(x):\n    return x\npkg.utils.helper = helper\nname = 'main'\n";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let inlined_modules = std::iter::once(utils).collect();
+        let inputs = TraceInputs {
+            graph: &graph,
+            resolver: &resolver,
+            tree_shaker: None,
+            conflict_resolver: &SymbolConflictResolver::new(),
+            inlined_modules: &inlined_modules,
+            wrapper_modules: &FxIndexSet::default(),
+            bundled_ast: parsed.syntax(),
+            stylist: &stylist,
+        };
+
+        let traced: TracedSymbol = "pkg.utils.helper".parse().expect("valid symbol");
+        assert_eq!(
+            trace(&traced, &inputs),
+            [
+                "defined in pkg/utils.py by a function",
+                "tree-shaking is disabled",
+                "module pkg.utils is inlined",
+                "imported by main as assist",
+                "bound by bundle statement 0: def helper(x):",
+                "bound by bundle statement 1: pkg.utils.helper = helper",
+            ]
+        );
+
+        let missing: TracedSymbol = "pkg.utils.other".parse().expect("valid symbol");
+        assert_eq!(
+            trace(&missing, &inputs),
+            ["not defined at the top level of pkg/utils.py"]
+        );
+        assert!("helper".parse::<TracedSymbol>().is_err());
+    }
+}
@@ -139,6 +139,9 @@ pub(crate) enum ResolutionStrategy {
     /// Cycle can be resolved through code transformations (lazy import, function-scoped import,
     /// etc.)
     Resolvable,
+    /// Cycle is resolved only by the lazy initialization of wrapper modules, without moving
+    /// imports into functions (`--cycle-strategy lazy`)
+    Lazy,
     /// Cycle cannot be resolved
     Unresolvable { reason: String },
 }
@@ -0,0 +1,220 @@
+//! Source attribution for statements in the bundled AST.
+//!
+//! `ast_indexer` gives every node of a module an index inside the range reserved for the
+//! module's position (`position * MODULE_INDEX_RANGE`). Statements copied into the bundle keep
+//! their original index, so the owning module can be recovered from the index alone. Nodes
+//! synthesized by the bundler either have no index or one past the last module range.
+
+use std::path::PathBuf;
+
+use ruff_python_ast::{AtomicNodeIndex, ExceptHandler, Stmt};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::{ast_indexer::MODULE_INDEX_RANGE, resolver::ModuleId};
+
+/// Where a top-level bundled statement came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StatementOrigin {
+    /// Copied from a module's source (possibly with rewritten names)
+    Source {
+        module_id: ModuleId,
+        offset: TextSize,
+    },
+    /// Generated by the bundler around code of a module (e.g. a wrapper init function)
+    Wrapped {
+        module_id: ModuleId,
+        offset: TextSize,
+    },
+    /// Bundling infrastructure with no counterpart in any source file
+    Generated,
+}
+
+impl StatementOrigin {
+    /// The module the statement belongs to, if any
+    pub(crate) const fn module_id(self) -> Option<ModuleId> {
+        match self {
+            Self::Source { module_id, .. } | Self::Wrapped { module_id, .. } => Some(module_id),
+            Self::Generated => None,
+        }
+    }
+}
+
+/// Resolved source location of an attributed statement
+#[derive(Debug, Clone)]
+pub(crate) struct SourceLocation {
+    pub module_name: String,
+    pub path: PathBuf,
+    /// 1-based line number in the original file
+    pub line: usize,
+}
+
+/// A top-level bundle statement rendered to code, together with its origin
+#[derive(Debug, Clone)]
+pub(crate) struct AttributedStatement {
+    pub code: String,
+    pub origin: StatementOrigin,
+    pub location: Option<SourceLocation>,
+}
+
+/// Maps node indices assigned by `ast_indexer` back to their modules
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceAttribution {
+    module_order: Vec<ModuleId>,
+}
+
+impl SourceAttribution {
+    /// Create an attribution from the order in which module ASTs were indexed
+    pub(crate) const fn new(module_order: Vec<ModuleId>) -> Self {
+        Self { module_order }
+    }
+
+    /// Get the module owning a node index, or `None` for synthesized nodes
+    fn module_for_index(&self, node_index: &AtomicNodeIndex) -> Option<ModuleId> {
+        let index = node_index.load().as_u32()?;
+        self.module_order
+            .get((index / MODULE_INDEX_RANGE) as usize)
+            .copied()
+    }
+
+    /// Determine the origin of a top-level statement.
+    ///
+    /// Synthesized compound statements (init functions, guards) are attributed to the first
+    /// original statement found in their bodies.
+    pub(crate) fn attribute(&self, stmt: &Stmt) -> StatementOrigin {
+        if let Some(module_id) = self.module_for_index(stmt_node_index(stmt)) {
+            return StatementOrigin::Source {
+                module_id,
+                offset: stmt.range().start(),
+            };
+        }
+
+        nested_bodies(stmt)
+            .into_iter()
+            .flatten()
+            .find_map(|nested| match self.attribute(nested) {
+                StatementOrigin::Source { module_id, offset }
+                | StatementOrigin::Wrapped { module_id, offset } => {
+                    Some(StatementOrigin::Wrapped { module_id, offset })
+                }
+                StatementOrigin::Generated => None,
+            })
+            .unwrap_or(StatementOrigin::Generated)
+    }
+}
+
+/// Byte offsets at which each line of `source` starts
+pub(crate) fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(pos, _)| pos + 1))
+        .collect()
+}
+
+/// Convert a byte offset to a 1-based line number using precomputed line starts
+pub(crate) fn line_number(line_starts: &[usize], offset: TextSize) -> usize {
+    line_starts
+        .partition_point(|&start| start <= usize::from(offset))
+        .max(1)
+}
+
+/// Get the node index of a statement
+fn stmt_node_index(stmt: &Stmt) -> &AtomicNodeIndex {
+    match stmt {
+        Stmt::FunctionDef(s) => &s.node_index,
+        Stmt::ClassDef(s) => &s.node_index,
+        Stmt::Import(s) => &s.node_index,
+        Stmt::ImportFrom(s) => &s.node_index,
+        Stmt::Assign(s) => &s.node_index,
+        Stmt::Return(s) => &s.node_index,
+        Stmt::Delete(s) => &s.node_index,
+        Stmt::AugAssign(s) => &s.node_index,
+        Stmt::AnnAssign(s) => &s.node_index,
+        Stmt::TypeAlias(s) => &s.node_index,
+        Stmt::For(s) => &s.node_index,
+        Stmt::While(s) => &s.node_index,
+        Stmt::If(s) => &s.node_index,
+        Stmt::With(s) => &s.node_index,
+        Stmt::Match(s) => &s.node_index,
+        Stmt::Raise(s) => &s.node_index,
+        Stmt::Try(s) => &s.node_index,
+        Stmt::Assert(s) => &s.node_index,
+        Stmt::Global(s) => &s.node_index,
+        Stmt::Nonlocal(s) => &s.node_index,
+        Stmt::Expr(s) => &s.node_index,
+        Stmt::Pass(s) => &s.node_index,
+        Stmt::Break(s) => &s.node_index,
+        Stmt::Continue(s) => &s.node_index,
+        Stmt::IpyEscapeCommand(s) => &s.node_index,
+    }
+}
+
+/// Collect the statement bodies nested directly inside a compound statement
+fn nested_bodies(stmt: &Stmt) -> Vec<&[Stmt]> {
+    match stmt {
+        Stmt::FunctionDef(func) => vec![func.body.as_slice()],
+        Stmt::ClassDef(class_def) => vec![class_def.body.as_slice()],
+        Stmt::If(if_stmt) => std::iter::once(if_stmt.body.as_slice())
+            .chain(
+                if_stmt
+                    .elif_else_clauses
+                    .iter()
+                    .map(|clause| clause.body.as_slice()),
+            )
+            .collect(),
+        Stmt::For(for_stmt) => vec![for_stmt.body.as_slice(), for_stmt.orelse.as_slice()],
+        Stmt::While(while_stmt) => vec![while_stmt.body.as_slice(), while_stmt.orelse.as_slice()],
+        Stmt::With(with_stmt) => vec![with_stmt.body.as_slice()],
+        Stmt::Try(try_stmt) => std::iter::once(try_stmt.body.as_slice())
+            .chain(try_stmt.handlers.iter().map(|handler| {
+                let ExceptHandler::ExceptHandler(handler) = handler;
+                handler.body.as_slice()
+            }))
+            .chain([try_stmt.orelse.as_slice(), try_stmt.finalbody.as_slice()])
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::ast_indexer::index_module_with_id;
+
+    #[test]
+    fn test_attribute_indexed_and_synthesized_statements() {
+        let source = "x = 1\n\ndef f():\n    return x\n";
+        let mut module = parse_module(source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        index_module_with_id(&mut module, 1);
+
+        let attribution = SourceAttribution::new(vec![ModuleId::ENTRY, ModuleId::new(7)]);
+        let starts = line_starts(source);
+
+        let origin = attribution.attribute(&module.body[1]);
+        assert_eq!(origin.module_id(), Some(ModuleId::new(7)));
+        let StatementOrigin::Source { offset, .. } = origin else {
+            panic!("indexed statement should be attributed to its source");
+        };
+        assert_eq!(line_number(&starts, offset), 3);
+
+        // A synthesized wrapper around original statements is attributed to their module
+        let wrapper = crate::ast_builder::statements::if_stmt(
+            crate::ast_builder::expressions::bool_literal(true),
+            module.body.clone(),
+            Vec::new(),
+        );
+        assert!(matches!(
+            attribution.attribute(&wrapper),
+            StatementOrigin::Wrapped { module_id, .. } if module_id == ModuleId::new(7)
+        ));
+
+        // Purely generated statements have no origin
+        let generated = crate::ast_builder::statements::pass();
+        assert_eq!(
+            attribution.attribute(&generated),
+            StatementOrigin::Generated
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Function call graph of the bundle (`--emit-call-graph`).
+//!
+//! Every module-level function kept in the bundle becomes a node, and a link points from a
+//! function to each bundled function it references, within its module or across modules. Calls
+//! are resolved with the same import resolution tree-shaking uses, so `helper()` after
+//! `from .utils import helper` and `utils.helper()` after `import utils` both link to
+//! `utils.helper`; method calls on objects are not tracked.
+//!
+//! The JSON uses the node-link layout (`directed`, `multigraph`, `nodes`, `links`) read by
+//! networkx's `node_link_graph` and d3-style graph visualizers.
+
+use serde::Serialize;
+
+use crate::{
+    artifacts::report::AnalysisResults,
+    dependency_graph::{DependencyGraph, ItemData, ItemType},
+    resolver::ModuleId,
+    tree_shaking::TreeShaker,
+    types::{FxIndexMap, FxIndexSet},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CallGraph {
+    pub directed: bool,
+    pub multigraph: bool,
+    pub nodes: Vec<CallGraphNode>,
+    pub links: Vec<CallGraphLink>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CallGraphNode {
+    /// Qualified name, `module.function`
+    pub id: String,
+    pub module: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct CallGraphLink {
+    /// Qualified name of the calling function
+    pub source: String,
+    /// Qualified name of the called function
+    pub target: String,
+}
+
+/// Build the call graph of the module-level functions in the bundle, in bundle order
+///
+/// With tree-shaking, functions removed from the bundle are left out.
+pub(crate) fn build(
+    results: &AnalysisResults<'_>,
+    graph: &DependencyGraph,
+    tree_shaker: Option<&TreeShaker<'_>>,
+) -> CallGraph {
+    let module_name = |module_id: ModuleId| {
+        graph
+            .modules
+            .get(&module_id)
+            .map_or_else(String::new, |module| module.module_name.clone())
+    };
+
+    let mut functions: FxIndexMap<(ModuleId, String), &ItemData> = FxIndexMap::default();
+    for &module_id in results.module_ids {
+        let Some(module) = graph.modules.get(&module_id) else {
+            continue;
+        };
+        for item in module.items.values() {
+            if let ItemType::FunctionDef { name } = &item.item_type
+                && item.containing_scope.is_none()
+                && tree_shaker.is_none_or(|shaker| shaker.is_symbol_used(&module.module_name, name))
+            {
+                functions.insert((module_id, name.clone()), item);
+            }
+        }
+    }
+
+    let qualified_name =
+        |(module_id, name): &(ModuleId, String)| format!("{}.{name}", module_name(*module_id));
+
+    let resolver = TreeShaker::from_graph(graph, results.resolver);
+    let mut links = Vec::new();
+    for (caller, item) in &functions {
+        let module_id = caller.0;
+        let mut callees: FxIndexSet<(ModuleId, String)> = FxIndexSet::default();
+        for var in item.read_vars.iter().chain(&item.eventual_read_vars) {
+            callees.extend(resolver.resolve_symbol_reference(module_id, var));
+        }
+        for (base, attrs) in &item.attribute_accesses {
+            for attr in attrs {
+                callees.extend(resolver.resolve_module_attribute_reference(module_id, base, attr));
+            }
+        }
+
+        let source = qualified_name(caller);
+        links.extend(
+            callees
+                .iter()
+                .filter(|callee| functions.contains_key(*callee))
+                .map(|callee| CallGraphLink {
+                    source: source.clone(),
+                    target: qualified_name(callee),
+                }),
+        );
+    }
+
+    CallGraph {
+        directed: true,
+        multigraph: false,
+        nodes: functions
+            .keys()
+            .map(|function| CallGraphNode {
+                id: qualified_name(function),
+                module: module_name(function.0),
+                name: function.1.clone(),
+            })
+            .collect(),
+        links,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        config::Config, resolver::ModuleResolver, symbol_conflict_resolver::SymbolConflictResolver,
+    };
+
+    fn item(item_type: ItemType, defined: &str, reads: &[&str]) -> ItemData {
+        ItemData {
+            item_type,
+            var_decls: std::iter::once(defined.to_owned()).collect(),
+            read_vars: FxIndexSet::default(),
+            eventual_read_vars: reads.iter().map(|read| (*read).to_owned()).collect(),
+            write_vars: FxIndexSet::default(),
+            eventual_write_vars: FxIndexSet::default(),
+            has_side_effects: false,
+            imported_names: FxIndexSet::default(),
+            reexported_names: FxIndexSet::default(),
+            defined_symbols: std::iter::once(defined.to_owned()).collect(),
+            symbol_dependencies: FxIndexMap::default(),
+            attribute_accesses: FxIndexMap::default(),
+            containing_scope: None,
+        }
+    }
+
+    fn function(name: &str, reads: &[&str]) -> ItemData {
+        item(
+            ItemType::FunctionDef {
+                name: name.to_owned(),
+            },
+            name,
+            reads,
+        )
+    }
+
+    #[test]
+    fn test_build_links_calls_across_modules() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+
+        let mut graph = DependencyGraph::new();
+        graph.add_module(entry, "main".to_owned(), Path::new("main.py"));
+        graph.add_module(utils, "utils".to_owned(), Path::new("utils.py"));
+
+        let utils_module = graph.modules.get_mut(&utils).expect("module should exist");
+        utils_module.add_item(function("helper", &["format_value"]));
+        utils_module.add_item(function("format_value", &["str"]));
+
+        let main_module = graph.modules.get_mut(&entry).expect("module should exist");
+        let mut import = item(
+            ItemType::FromImport {
+                module: "utils".to_owned(),
+                names: vec![("helper".to_owned(), None)],
+                level: 0,
+                is_star: false,
+            },
+            "helper",
+            &[],
+        );
+        import.defined_symbols.clear();
+        main_module.add_item(import);
+        let mut run = function("run", &["helper", "print"]);
+        run.attribute_accesses.insert(
+            "utils".to_owned(),
+            std::iter::once("format_value".to_owned()).collect(),
+        );
+        main_module.add_item(run);
+
+        let conflict_resolver = SymbolConflictResolver::new();
+        let module_ids = [utils, entry];
+        let empty = FxIndexSet::default();
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &empty,
+            wrapper_modules: &empty,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: None,
+        };
+        let call_graph = build(&results, &graph, None);
+
+        let ids: Vec<&str> = call_graph
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect();
+        assert_eq!(ids, ["utils.helper", "utils.format_value", "main.run"]);
+        let link = |source: &str, target: &str| CallGraphLink {
+            source: source.to_owned(),
+            target: target.to_owned(),
+        };
+        assert_eq!(
+            call_graph.links,
+            [
+                link("utils.helper", "utils.format_value"),
+                link("main.run", "utils.helper"),
+                link("main.run", "utils.format_value"),
+            ]
+        );
+    }
+}
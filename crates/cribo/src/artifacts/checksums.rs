@@ -0,0 +1,125 @@
+//! Per-module content hashes (`--emit-checksums`).
+//!
+//! Each bundled module's path maps to the SHA-256 of its source, the same content hash the
+//! bundler derives module names from, together with the hashes of the modules it directly
+//! depends on. A build system can compare these against a previous run to decide whether the
+//! bundle has to be regenerated.
+
+use serde::Serialize;
+
+use crate::{
+    artifacts::report::AnalysisResults, dependency_graph::DependencyGraph, resolver::ModuleId,
+    types::FxIndexMap,
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ModuleChecksum {
+    pub module: String,
+    pub sha256: String,
+    /// Path to content hash of each direct first-party dependency
+    pub dependencies: FxIndexMap<String, String>,
+}
+
+/// Build the checksums of the modules with a content hash, keyed by module path, in bundle order
+pub(crate) fn build(
+    results: &AnalysisResults<'_>,
+    graph: &DependencyGraph,
+    content_hashes: &FxIndexMap<ModuleId, String>,
+) -> FxIndexMap<String, ModuleChecksum> {
+    let module_path = |module_id: ModuleId| {
+        results
+            .resolver
+            .get_module_path(module_id)
+            .map(|path| path.display().to_string())
+    };
+
+    results
+        .module_ids
+        .iter()
+        .filter_map(|&module_id| {
+            let sha256 = content_hashes.get(&module_id)?;
+            let dependencies = graph
+                .get_dependencies(module_id)
+                .into_iter()
+                .filter_map(|dependency| {
+                    Some((
+                        module_path(dependency)?,
+                        content_hashes.get(&dependency)?.clone(),
+                    ))
+                })
+                .collect();
+            let checksum = ModuleChecksum {
+                module: results
+                    .resolver
+                    .get_module_name(module_id)
+                    .unwrap_or_else(|| format!("module_{}", module_id.as_u32())),
+                sha256: sha256.clone(),
+                dependencies,
+            };
+            Some((module_path(module_id)?, checksum))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        config::Config, resolver::ModuleResolver, symbol_conflict_resolver::SymbolConflictResolver,
+        types::FxIndexSet,
+    };
+
+    #[test]
+    fn test_build_includes_direct_dependency_hashes() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+        let helpers = resolver.register_module("helpers", Path::new("helpers.py"));
+
+        let mut graph = DependencyGraph::new();
+        graph.add_module(entry, "main".to_owned(), Path::new("main.py"));
+        graph.add_module(utils, "utils".to_owned(), Path::new("utils.py"));
+        graph.add_module(helpers, "helpers".to_owned(), Path::new("helpers.py"));
+        graph.add_module_dependency(entry, utils);
+        graph.add_module_dependency(utils, helpers);
+
+        let content_hashes: FxIndexMap<ModuleId, String> = [
+            (entry, "aa".to_owned()),
+            (utils, "bb".to_owned()),
+            (helpers, "cc".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+
+        let conflict_resolver = SymbolConflictResolver::new();
+        let module_ids = [helpers, utils, entry];
+        let empty = FxIndexSet::default();
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &empty,
+            wrapper_modules: &empty,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: None,
+        };
+        let checksums = build(&results, &graph, &content_hashes);
+
+        let path = |name: &str| Path::new(name).display().to_string();
+        assert_eq!(
+            checksums.keys().cloned().collect::<Vec<_>>(),
+            [path("helpers.py"), path("utils.py"), path("main.py")]
+        );
+        let main = &checksums[&path("main.py")];
+        assert_eq!(main.module, "main");
+        assert_eq!(main.sha256, "aa");
+        // Only direct dependencies are listed
+        assert_eq!(
+            main.dependencies.iter().collect::<Vec<_>>(),
+            [(&path("utils.py"), &"bb".to_owned())]
+        );
+        assert!(checksums[&path("helpers.py")].dependencies.is_empty());
+    }
+}
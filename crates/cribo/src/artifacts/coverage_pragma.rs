@@ -0,0 +1,87 @@
+//! Coverage exclusion of bundling infrastructure (`--emit-coverage-pragma`).
+//!
+//! Statements the bundler generates without a counterpart in any source file get a trailing
+//! `# pragma: no cover` on their first line. coverage.py excludes the whole statement, including
+//! the body of compound statements, so glue code no longer lowers the coverage of the bundle.
+
+use crate::artifacts::attribution::{AttributedStatement, StatementOrigin};
+
+const PRAGMA: &str = "# pragma: no cover";
+
+/// Append the pragma to every generated statement
+pub(crate) fn mark_generated(statements: &mut [AttributedStatement]) {
+    for statement in statements {
+        if statement.origin == StatementOrigin::Generated {
+            statement.code = with_pragma(&statement.code);
+        }
+    }
+}
+
+/// Add the pragma to the end of the first line, unless that line continues into the next one
+/// (where a trailing comment would end up inside a string or break the continuation)
+fn with_pragma(code: &str) -> String {
+    let (first_line, rest) = code
+        .split_once('\n')
+        .map_or((code, None), |(first, rest)| (first, Some(rest)));
+    let first_line = first_line.trim_end();
+    if first_line.is_empty()
+        || first_line.ends_with('\\')
+        || first_line.contains("\"\"\"")
+        || first_line.contains("'''")
+    {
+        return code.to_owned();
+    }
+    match rest {
+        Some(rest) => format!("{first_line}  {PRAGMA}\n{rest}"),
+        None => format!("{first_line}  {PRAGMA}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::resolver::ModuleId;
+
+    #[test]
+    fn test_mark_generated_skips_source_statements() {
+        let mut statements = vec![
+            AttributedStatement {
+                code: "import types".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "def _cribo_init():\n    pass".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "x = \"\"\"a\nb\"\"\"".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "def helper():\n    return 1".to_owned(),
+                origin: StatementOrigin::Source {
+                    module_id: ModuleId::new(1),
+                    offset: TextSize::from(0),
+                },
+                location: None,
+            },
+        ];
+
+        mark_generated(&mut statements);
+        let code: Vec<&str> = statements.iter().map(|s| s.code.as_str()).collect();
+        assert_eq!(
+            code,
+            [
+                "import types  # pragma: no cover",
+                "def _cribo_init():  # pragma: no cover\n    pass",
+                "x = \"\"\"a\nb\"\"\"",
+                "def helper():\n    return 1",
+            ]
+        );
+    }
+}
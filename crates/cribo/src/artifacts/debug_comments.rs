@@ -0,0 +1,104 @@
+//! Transformation comments for `--debug-output`.
+//!
+//! Ruff's AST does not carry comments, so the explanations are injected as a text-level pass
+//! over the generated statements: each top-level statement is preceded by a `# [cribo]` line
+//! naming the statement and the original file and line it was produced from.
+
+use crate::{
+    artifacts::attribution::{AttributedStatement, StatementOrigin},
+    resolver::ModuleId,
+};
+
+/// Maximum number of characters of a statement repeated in its comment
+const MAX_SUMMARY_LEN: usize = 60;
+
+/// Render the bundle statements, each preceded by a comment explaining where it came from
+pub(crate) fn annotate(statements: &[AttributedStatement]) -> Vec<String> {
+    statements
+        .iter()
+        .map(|statement| format!("{}\n{}", comment(statement), statement.code))
+        .collect()
+}
+
+/// Build the `# [cribo]` comment for one statement
+fn comment(statement: &AttributedStatement) -> String {
+    let summary = summarize(&statement.code);
+    let Some(location) = &statement.location else {
+        return format!("# [cribo] {summary} -> generated by the bundler");
+    };
+    let decision = match statement.origin {
+        StatementOrigin::Source { module_id, .. } if module_id == ModuleId::ENTRY => "kept",
+        StatementOrigin::Source { .. } => "inlined",
+        StatementOrigin::Wrapped { .. } => "wrapped in the module init",
+        StatementOrigin::Generated => "generated",
+    };
+    format!(
+        "# [cribo] {summary} -> {decision} from {}:{}",
+        location.path.display(),
+        location.line
+    )
+}
+
+/// First line of a statement, shortened to keep the comment on one line
+fn summarize(code: &str) -> String {
+    let first_line = code.lines().next().unwrap_or_default().trim_end();
+    if first_line.chars().count() > MAX_SUMMARY_LEN {
+        let truncated: String = first_line.chars().take(MAX_SUMMARY_LEN).collect();
+        format!("{truncated}...")
+    } else {
+        first_line.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::artifacts::attribution::SourceLocation;
+
+    #[test]
+    fn test_annotate_explains_each_statement() {
+        let location = SourceLocation {
+            module_name: "pkg.utils".to_owned(),
+            path: PathBuf::from("pkg/utils.py"),
+            line: 42,
+        };
+        let statements = vec![
+            AttributedStatement {
+                code: "import types".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "def helper():\n    return 1".to_owned(),
+                origin: StatementOrigin::Source {
+                    module_id: ModuleId::new(1),
+                    offset: TextSize::from(0),
+                },
+                location: Some(location.clone()),
+            },
+            AttributedStatement {
+                code: "def _cribo_init_pkg_utils(self):\n    pass".to_owned(),
+                origin: StatementOrigin::Wrapped {
+                    module_id: ModuleId::new(1),
+                    offset: TextSize::from(0),
+                },
+                location: Some(location),
+            },
+        ];
+
+        assert_eq!(
+            annotate(&statements),
+            [
+                "# [cribo] import types -> generated by the bundler\nimport types",
+                "# [cribo] def helper(): -> inlined from pkg/utils.py:42\ndef helper():\n    \
+                 return 1",
+                "# [cribo] def _cribo_init_pkg_utils(self): -> wrapped in the module init from \
+                 pkg/utils.py:42\ndef _cribo_init_pkg_utils(self):\n    pass",
+            ]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Annotated HTML view of the bundle (`--emit-html`).
+//!
+//! The page is assembled with plain string formatting. Every top-level statement becomes a
+//! block whose tooltip names the original file and line it came from, and consecutive
+//! statements of the same module are grouped into a collapsible `<details>` section.
+
+use std::fmt::Write;
+
+use crate::artifacts::attribution::{AttributedStatement, StatementOrigin};
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; background: #fafafa; color: #222; }
+details { margin: 0.5em 0; border: 1px solid #ddd; border-radius: 4px; background: #fff; }
+summary { cursor: pointer; padding: 0.4em 0.8em; background: #f0f0f0; font-weight: bold; }
+summary .path { font-weight: normal; color: #666; margin-left: 1em; }
+details.generated summary { color: #777; font-style: italic; }
+pre { margin: 0; padding: 0.5em 0.8em; overflow-x: auto; }
+.stmt { display: block; padding: 0.1em 0; border-left: 3px solid transparent; }
+.stmt:hover { background: #fffbe6; border-left-color: #e0b000; }
+.stmt.wrapped { border-left-color: #cde; }
+.stmt.generated { color: #666; }
+.kw { color: #0033b3; font-weight: bold; }
+.str { color: #067d17; }
+.num { color: #1750eb; }
+.com { color: #8c8c8c; font-style: italic; }
+";
+
+/// Render the annotated HTML page for a bundle
+pub(crate) fn render(title: &str, statements: &[AttributedStatement]) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    writeln!(html, "<title>Cribo bundle: {}</title>", escape_html(title))
+        .expect("Writing to String never fails");
+    writeln!(html, "<style>\n{STYLE}</style>\n</head>\n<body>")
+        .expect("Writing to String never fails");
+    writeln!(html, "<h1>Cribo bundle: {}</h1>", escape_html(title))
+        .expect("Writing to String never fails");
+
+    for group in statements.chunk_by(|a, b| a.origin.module_id() == b.origin.module_id()) {
+        render_section(&mut html, group);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Render one collapsible section of consecutive statements sharing a module
+fn render_section(html: &mut String, group: &[AttributedStatement]) {
+    let first = &group[0];
+    match &first.location {
+        Some(location) => writeln!(
+            html,
+            "<details open class=\"module\">\n<summary>{}<span class=\"path\">{}</span></summary>",
+            escape_html(&location.module_name),
+            escape_html(&location.path.display().to_string())
+        ),
+        None => writeln!(
+            html,
+            "<details open class=\"generated\">\n<summary>generated by cribo</summary>"
+        ),
+    }
+    .expect("Writing to String never fails");
+
+    html.push_str("<pre><code>");
+    for statement in group {
+        let (class, tooltip) = match (&statement.origin, &statement.location) {
+            (StatementOrigin::Source { .. }, Some(location)) => (
+                "source",
+                format!("{}:{}", location.path.display(), location.line),
+            ),
+            (StatementOrigin::Wrapped { .. }, Some(location)) => (
+                "wrapped",
+                format!(
+                    "{}:{} (wrapped by cribo)",
+                    location.path.display(),
+                    location.line
+                ),
+            ),
+            _ => ("generated", "generated by cribo".to_owned()),
+        };
+        write!(
+            html,
+            "<span class=\"stmt {class}\" title=\"{}\">",
+            escape_html(&tooltip)
+        )
+        .expect("Writing to String never fails");
+        highlight_python(&statement.code, html);
+        html.push_str("</span>");
+    }
+    html.push_str("</code></pre>\n</details>\n");
+}
+
+/// Escape text for use in HTML content and attribute values
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        push_escaped(&mut escaped, ch);
+    }
+    escaped
+}
+
+fn push_escaped(out: &mut String, ch: char) {
+    match ch {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        '\'' => out.push_str("&#39;"),
+        _ => out.push(ch),
+    }
+}
+
+fn push_span(out: &mut String, class: &str, chars: &[char]) {
+    write!(out, "<span class=\"{class}\">").expect("Writing to String never fails");
+    for &ch in chars {
+        push_escaped(out, ch);
+    }
+    out.push_str("</span>");
+}
+
+/// Check whether an identifier is a valid string prefix (`r`, `b`, `f`, `rb`, ...)
+fn is_string_prefix(word: &[char]) -> bool {
+    word.len() <= 2
+        && word
+            .iter()
+            .all(|ch| matches!(ch.to_ascii_lowercase(), 'r' | 'b' | 'u' | 'f'))
+}
+
+/// Find the end (exclusive) of a string literal starting with the quote at `start`
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let triple = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if triple { start + 3 } else { start + 1 };
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            ch if ch == quote => {
+                if !triple {
+                    return i + 1;
+                }
+                if chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote) {
+                    return i + 3;
+                }
+                i += 1;
+            }
+            '\n' if !triple => return i,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Minimal lexical highlighter for generated Python code
+///
+/// Only keywords, string literals, numbers and comments are highlighted; everything else is
+/// emitted escaped but otherwise untouched.
+pub(crate) fn highlight_python(code: &str, out: &mut String) {
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        if ch == '#' {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |offset| i + offset);
+            push_span(out, "com", &chars[i..end]);
+            i = end;
+        } else if ch == '"' || ch == '\'' {
+            let end = string_end(&chars, i);
+            push_span(out, "str", &chars[i..end]);
+            i = end;
+        } else if ch.is_alphabetic() || ch == '_' {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| !(c.is_alphanumeric() || c == '_'))
+                .map_or(chars.len(), |offset| i + offset);
+            let word = &chars[i..end];
+            if end < chars.len() && matches!(chars[end], '"' | '\'') && is_string_prefix(word) {
+                let literal_end = string_end(&chars, end);
+                push_span(out, "str", &chars[i..literal_end]);
+                i = literal_end;
+                continue;
+            }
+            let word_str: String = word.iter().collect();
+            if ruff_python_stdlib::keyword::is_keyword(&word_str) {
+                push_span(out, "kw", word);
+            } else {
+                out.push_str(&word_str);
+            }
+            i = end;
+        } else if ch.is_ascii_digit() {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                .map_or(chars.len(), |offset| i + offset);
+            push_span(out, "num", &chars[i..end]);
+            i = end;
+        } else {
+            push_escaped(out, ch);
+            i += 1;
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::{artifacts::attribution::SourceLocation, resolver::ModuleId};
+
+    #[test]
+    fn test_highlight_python() {
+        let mut out = String::new();
+        highlight_python(
+            "def f(x):\n    return x < 1  # done\ns = rb'a\\'b'",
+            &mut out,
+        );
+        assert_eq!(
+            out,
+            "<span class=\"kw\">def</span> f(x):\n    <span class=\"kw\">return</span> x &lt; \
+             <span class=\"num\">1</span>  <span class=\"com\"># done</span>\ns = <span \
+             class=\"str\">rb&#39;a\\&#39;b&#39;</span>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_groups_statements_by_module() {
+        let location = SourceLocation {
+            module_name: "pkg.utils".to_owned(),
+            path: PathBuf::from("pkg/utils.py"),
+            line: 3,
+        };
+        let origin = StatementOrigin::Source {
+            module_id: ModuleId::new(1),
+            offset: TextSize::from(0),
+        };
+        let statements = vec![
+            AttributedStatement {
+                code: "import types".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "x = 1".to_owned(),
+                origin,
+                location: Some(location.clone()),
+            },
+            AttributedStatement {
+                code: "y = 2".to_owned(),
+                origin,
+                location: Some(SourceLocation {
+                    line: 4,
+                    ..location
+                }),
+            },
+        ];
+
+        let html = render("main.py", &statements);
+        assert_eq!(html.matches("<details").count(), 2);
+        assert!(html.contains("<summary>generated by cribo</summary>"));
+        assert!(html.contains("<summary>pkg.utils<span class=\"path\">pkg/utils.py</span>"));
+        assert!(html.contains("title=\"pkg/utils.py:3\""));
+        assert!(html.contains("title=\"pkg/utils.py:4\""));
+    }
+}
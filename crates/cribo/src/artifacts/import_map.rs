@@ -0,0 +1,302 @@
+//! Import resolution map (`--emit-import-map`).
+//!
+//! Every import of every parsed module (including imports nested in functions and static
+//! `importlib.import_module` calls) is listed with the file and line it appears on, the module
+//! it resolved to, whether that module is first-party, stdlib or third-party, and what the
+//! bundler did with it.
+
+use ruff_python_ast::visitor::source_order::SourceOrderVisitor;
+use serde::Serialize;
+
+use crate::{
+    artifacts::{
+        attribution::{line_number, line_starts},
+        report::AnalysisResults,
+        symbol_table::ModuleSource,
+    },
+    resolver::{ImportType, ModuleId},
+    visitors::{self, DiscoveredImport, ImportDiscoveryVisitor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ModuleKind {
+    FirstParty,
+    Stdlib,
+    ThirdParty,
+}
+
+/// What the bundler did with an imported module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BundlingDecision {
+    /// First-party module inlined into the bundle's global scope
+    Inline,
+    /// First-party module wrapped in an init function
+    Wrap,
+    /// `__future__` import moved to the top of the bundle
+    Hoist,
+    /// Stdlib module accessed through the `_cribo` proxy
+    Proxy,
+    /// Import kept as written (third-party, or a first-party module that could not be found)
+    Preserve,
+    /// First-party module left out of the bundle (e.g. by `--prune-unused-modules`)
+    Drop,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ImportMapEntry {
+    pub file: String,
+    /// 1-based line of the import statement
+    pub line: usize,
+    /// Module as written, with leading dots for relative imports
+    pub import: String,
+    /// Names imported by a `from` import
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+    /// Absolute name of the module the import resolved to
+    pub target: String,
+    /// File of the resolved module (first-party modules only)
+    pub path: Option<String>,
+    pub module_type: ModuleKind,
+    pub decision: BundlingDecision,
+}
+
+/// Build the import map of the parsed modules, in bundle order
+pub(crate) fn build(
+    results: &AnalysisResults<'_>,
+    modules: &[ModuleSource<'_>],
+) -> Vec<ImportMapEntry> {
+    let mut entries = Vec::new();
+    for &module_id in results.module_ids {
+        let Some(module) = modules.iter().find(|module| module.module_id == module_id) else {
+            continue;
+        };
+        let importing_module = results
+            .resolver
+            .get_module_name(module_id)
+            .unwrap_or_else(|| format!("module_{}", module_id.as_u32()));
+        let file = results
+            .resolver
+            .get_module_path(module_id)
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let starts = line_starts(module.source);
+
+        let mut visitor = ImportDiscoveryVisitor::new();
+        for stmt in &module.ast.body {
+            visitor.visit_stmt(stmt);
+        }
+        for import in visitor.into_imports() {
+            let line = line_number(&starts, import.range.start());
+            let written = written_module(&import);
+            let base = resolve_base(results, &import, &importing_module);
+            let is_from_import = matches!(
+                import.import_type,
+                visitors::ImportType::From | visitors::ImportType::Relative { .. }
+            );
+
+            // `from pkg import submodule` imports the submodule itself
+            let (submodules, names): (Vec<_>, Vec<_>) = import
+                .names
+                .iter()
+                .filter(|_| is_from_import)
+                .map(|(name, _)| name.clone())
+                .partition(|name| {
+                    results
+                        .resolver
+                        .get_module_id_by_name(&format!("{base}.{name}"))
+                        .is_some()
+                });
+            for submodule in submodules {
+                entries.push(entry(
+                    results,
+                    &file,
+                    line,
+                    &written,
+                    vec![submodule.clone()],
+                    format!("{base}.{submodule}"),
+                ));
+            }
+            if !names.is_empty() || !is_from_import {
+                entries.push(entry(results, &file, line, &written, names, base));
+            }
+        }
+    }
+    entries
+}
+
+/// The imported module as written in the source, e.g. `..utils` or `os.path`
+fn written_module(import: &DiscoveredImport) -> String {
+    let module = import.module_name.as_deref().unwrap_or_default();
+    if module.starts_with('.') {
+        module.to_owned()
+    } else {
+        format!("{}{module}", ".".repeat(import.level as usize))
+    }
+}
+
+/// Absolute name of the module an import refers to
+fn resolve_base(
+    results: &AnalysisResults<'_>,
+    import: &DiscoveredImport,
+    importing_module: &str,
+) -> String {
+    let module = import.module_name.as_deref().unwrap_or_default();
+    if import.level == 0 {
+        return module.to_owned();
+    }
+    // `importlib.import_module(".mod", "pkg")` is relative to the given package
+    let anchor = import
+        .package_context
+        .as_deref()
+        .unwrap_or(importing_module);
+    results.resolver.resolve_relative_import_from_package_name(
+        import.level,
+        Some(module.trim_start_matches('.')),
+        anchor,
+    )
+}
+
+fn entry(
+    results: &AnalysisResults<'_>,
+    file: &str,
+    line: usize,
+    written: &str,
+    names: Vec<String>,
+    target: String,
+) -> ImportMapEntry {
+    let module_id = results.resolver.get_module_id_by_name(&target);
+    // Modules registered with the resolver were found on disk and are first-party
+    let import_type = if module_id.is_some() {
+        ImportType::FirstParty
+    } else {
+        results.resolver.classify_import(&target)
+    };
+    let (module_type, decision) = match import_type {
+        ImportType::FirstParty => (
+            ModuleKind::FirstParty,
+            first_party_decision(results, module_id),
+        ),
+        ImportType::StandardLibrary if target == "__future__" => {
+            (ModuleKind::Stdlib, BundlingDecision::Hoist)
+        }
+        ImportType::StandardLibrary => (ModuleKind::Stdlib, BundlingDecision::Proxy),
+        ImportType::ThirdParty => (ModuleKind::ThirdParty, BundlingDecision::Preserve),
+    };
+    ImportMapEntry {
+        file: file.to_owned(),
+        line,
+        import: written.to_owned(),
+        names,
+        path: module_id
+            .and_then(|id| results.resolver.get_module_path(id))
+            .map(|path| path.display().to_string()),
+        target,
+        module_type,
+        decision,
+    }
+}
+
+fn first_party_decision(
+    results: &AnalysisResults<'_>,
+    module_id: Option<ModuleId>,
+) -> BundlingDecision {
+    match module_id {
+        Some(id) if id.is_entry() || results.inlined_modules.contains(&id) => {
+            BundlingDecision::Inline
+        }
+        Some(id) if results.wrapper_modules.contains(&id) => BundlingDecision::Wrap,
+        Some(_) => BundlingDecision::Drop,
+        None => BundlingDecision::Preserve,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::{
+        config::Config,
+        resolver::ModuleResolver,
+        symbol_conflict_resolver::SymbolConflictResolver,
+        types::{FxIndexMap, FxIndexSet},
+    };
+
+    #[test]
+    fn test_build_maps_imports_to_targets_and_decisions() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let pkg = resolver.register_module("pkg", Path::new("pkg/__init__.py"));
+        let helpers = resolver.register_module("pkg.helpers", Path::new("pkg/helpers.py"));
+
+        let entry_source = "from __future__ import annotations\nimport os\nfrom pkg import \
+                            helpers, VERSION\n\ndef run():\n    import requests\n";
+        let entry_ast = parse_module(entry_source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+
+        let conflict_resolver = SymbolConflictResolver::new();
+        let module_ids = [helpers, pkg, entry];
+        let inlined: FxIndexSet<ModuleId> = [pkg].into_iter().collect();
+        let wrapper: FxIndexSet<ModuleId> = [helpers].into_iter().collect();
+        let keep_symbols: FxIndexMap<ModuleId, FxIndexSet<String>> = FxIndexMap::default();
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &inlined,
+            wrapper_modules: &wrapper,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: Some(&keep_symbols),
+        };
+        let import_map = build(
+            &results,
+            &[ModuleSource {
+                module_id: entry,
+                ast: &entry_ast,
+                source: entry_source,
+            }],
+        );
+
+        let summary: Vec<(usize, &str, ModuleKind, BundlingDecision)> = import_map
+            .iter()
+            .map(|entry| {
+                (
+                    entry.line,
+                    entry.target.as_str(),
+                    entry.module_type,
+                    entry.decision,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, "__future__", ModuleKind::Stdlib, BundlingDecision::Hoist),
+                (2, "os", ModuleKind::Stdlib, BundlingDecision::Proxy),
+                (
+                    3,
+                    "pkg.helpers",
+                    ModuleKind::FirstParty,
+                    BundlingDecision::Wrap
+                ),
+                (3, "pkg", ModuleKind::FirstParty, BundlingDecision::Inline),
+                (
+                    6,
+                    "requests",
+                    ModuleKind::ThirdParty,
+                    BundlingDecision::Preserve
+                ),
+            ]
+        );
+        assert_eq!(import_map[3].names, ["VERSION"]);
+        assert_eq!(
+            import_map[2].path.as_deref(),
+            Some(Path::new("pkg/helpers.py").display().to_string().as_str())
+        );
+    }
+}
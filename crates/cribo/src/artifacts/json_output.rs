@@ -0,0 +1,162 @@
+//! Machine-readable bundle output (`--json-output`).
+//!
+//! Instead of the bare bundle, a single JSON object is printed to stdout holding the bundle
+//! code together with the bundled modules, the warnings logged during the run and size
+//! statistics, so tools driving cribo do not have to scrape its logs.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::artifacts::report::{AnalysisResults, ModuleReport};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BundleStatistics {
+    pub module_count: usize,
+    /// Module-level symbols of the bundled modules that survived tree-shaking
+    pub symbol_count: usize,
+    /// Total size in bytes of the first-party sources read for the bundle
+    pub input_size: usize,
+    /// Size in bytes of the generated bundle
+    pub output_size: usize,
+}
+
+/// Everything reported next to the bundle code except the warnings, which are collected by
+/// the logger
+#[derive(Debug, Serialize)]
+pub(crate) struct BundleMetadata {
+    pub modules: Vec<ModuleReport>,
+    pub statistics: BundleStatistics,
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    bundle: &'a str,
+    modules: &'a [ModuleReport],
+    warnings: &'a [String],
+    statistics: &'a BundleStatistics,
+}
+
+/// Collect the metadata of a bundling run; `output_size` is filled in once the code exists
+pub(crate) fn metadata(results: &AnalysisResults<'_>, input_size: usize) -> BundleMetadata {
+    let modules = results.module_reports();
+    let symbol_count = results
+        .module_ids
+        .iter()
+        .filter(|module_id| {
+            module_id.is_entry()
+                || results.inlined_modules.contains(*module_id)
+                || results.wrapper_modules.contains(*module_id)
+        })
+        .filter_map(|&module_id| {
+            let info = results.conflict_resolver.get_module_info(module_id)?;
+            // The entry module is never tree-shaken
+            let kept_symbols = results
+                .tree_shaking_keep_symbols
+                .filter(|_| !module_id.is_entry())
+                .map(|keep_symbols| keep_symbols.get(&module_id));
+            Some(match kept_symbols {
+                None => info.module_scope_symbols.len(),
+                Some(kept) => info
+                    .module_scope_symbols
+                    .iter()
+                    .filter(|symbol| kept.is_some_and(|kept| kept.contains(*symbol)))
+                    .count(),
+            })
+        })
+        .sum();
+
+    BundleMetadata {
+        statistics: BundleStatistics {
+            module_count: modules.len(),
+            symbol_count,
+            input_size,
+            output_size: 0,
+        },
+        modules,
+    }
+}
+
+/// Render the JSON object printed by `--json-output`
+pub(crate) fn render(
+    bundle: &str,
+    metadata: &BundleMetadata,
+    warnings: &[String],
+) -> Result<String> {
+    serde_json::to_string_pretty(&JsonOutput {
+        bundle,
+        modules: &metadata.modules,
+        warnings,
+        statistics: &metadata.statistics,
+    })
+    .context("Failed to serialize JSON output")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::{
+        config::Config,
+        resolver::{ModuleId, ModuleResolver},
+        symbol_conflict_resolver::SymbolConflictResolver,
+        types::{FxIndexMap, FxIndexSet},
+    };
+
+    #[test]
+    fn test_render_reports_modules_warnings_and_statistics() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+
+        let entry_ast = parse_module("from utils import run\n\nrun()\n")
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        let utils_ast = parse_module("def run():\n    pass\n\ndef unused():\n    pass\n")
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        let mut conflict_resolver = SymbolConflictResolver::new();
+        conflict_resolver.analyze_module(entry, &entry_ast, Path::new("main.py"));
+        conflict_resolver.analyze_module(utils, &utils_ast, Path::new("utils.py"));
+
+        let module_ids = [utils, entry];
+        let inlined: FxIndexSet<ModuleId> = [utils].into_iter().collect();
+        let wrapper = FxIndexSet::default();
+        let mut keep_symbols: FxIndexMap<ModuleId, FxIndexSet<String>> = FxIndexMap::default();
+        keep_symbols.insert(utils, std::iter::once("run".to_owned()).collect());
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &inlined,
+            wrapper_modules: &wrapper,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: Some(&keep_symbols),
+        };
+
+        let mut metadata = metadata(&results, 64);
+        metadata.statistics.output_size = 12;
+        let json = render("print('hi')\n", &metadata, &["careful".to_owned()])
+            .expect("JSON output should serialize");
+        let value: serde_json::Value =
+            serde_json::from_str(&json).expect("JSON output should parse");
+
+        assert_eq!(value["bundle"], "print('hi')\n");
+        assert_eq!(value["modules"][0]["name"], "utils");
+        assert_eq!(value["modules"][0]["kind"], "inline");
+        assert_eq!(value["modules"][1]["kind"], "entry");
+        assert_eq!(value["warnings"][0], "careful");
+        assert_eq!(value["statistics"]["module_count"], 2);
+        // `run` survives tree-shaking; the entry module's symbols are all kept
+        assert_eq!(
+            value["statistics"]["symbol_count"],
+            1 + conflict_resolver
+                .get_module_info(entry)
+                .map_or(0, |info| info.module_scope_symbols.len())
+        );
+        assert_eq!(value["statistics"]["input_size"], 64);
+        assert_eq!(value["statistics"]["output_size"], 12);
+    }
+}
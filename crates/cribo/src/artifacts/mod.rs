@@ -0,0 +1,22 @@
+//! Auxiliary artifacts emitted alongside the bundle
+//!
+//! Artifacts are developer-facing views of a bundling run (annotated HTML, reports, ...).
+//! They are derived from the final bundled AST and never influence the generated code.
+
+pub(crate) mod attribution;
+pub(crate) mod call_graph;
+pub(crate) mod checksums;
+pub(crate) mod coverage_pragma;
+pub(crate) mod debug_comments;
+pub(crate) mod html;
+pub(crate) mod import_map;
+pub(crate) mod json_output;
+pub(crate) mod noqa;
+pub(crate) mod report;
+pub(crate) mod size_breakdown;
+pub(crate) mod size_stats;
+pub(crate) mod sourcemap_html;
+pub(crate) mod stub;
+pub(crate) mod symbol_table;
+pub(crate) mod third_party;
+pub(crate) mod unused_imports;
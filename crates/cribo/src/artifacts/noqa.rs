@@ -0,0 +1,169 @@
+//! Linter suppression of bundling infrastructure (`--emit-noqa`).
+//!
+//! Glue code generated by the bundler (namespace objects, symbol assignments, init function
+//! calls) is not written by the user and often trips linters, e.g. unused imports or long
+//! namespace assignments. Every line of a generated statement gets a trailing `# noqa: <codes>`
+//! comment; statements wrapping the code of a module (init functions) only get it on their
+//! header line, and code copied from the sources is left alone.
+//!
+//! Which lines can carry a comment is decided by a pass over the statement's AST: lines that
+//! open or continue a multi-line string literal would swallow the comment into the string.
+
+use ruff_python_ast::{
+    Expr,
+    visitor::source_order::{SourceOrderVisitor, walk_expr},
+};
+use ruff_text_size::Ranged;
+
+use crate::artifacts::attribution::{
+    AttributedStatement, StatementOrigin, line_number, line_starts,
+};
+
+/// Append `# noqa: <codes>` to the lines of every generated statement
+pub(crate) fn mark_generated(statements: &mut [AttributedStatement], codes: &str) {
+    let comment = noqa_comment(codes);
+    for statement in statements {
+        match statement.origin {
+            StatementOrigin::Generated => {
+                statement.code = with_noqa(&statement.code, &comment, usize::MAX);
+            }
+            StatementOrigin::Wrapped { .. } => {
+                statement.code = with_noqa(&statement.code, &comment, 1);
+            }
+            StatementOrigin::Source { .. } => {}
+        }
+    }
+}
+
+/// Build the comment from a comma separated list of codes, or a blanket `# noqa` if it is empty
+fn noqa_comment(codes: &str) -> String {
+    let codes: Vec<&str> = codes
+        .split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .collect();
+    if codes.is_empty() {
+        "# noqa".to_owned()
+    } else {
+        format!("# noqa: {}", codes.join(","))
+    }
+}
+
+/// Add the comment to the end of the first `max_lines` lines of `code` that can carry one
+fn with_noqa(code: &str, comment: &str, max_lines: usize) -> String {
+    let Some(in_string) = string_continuation_lines(code) else {
+        return code.to_owned();
+    };
+
+    let mut output = String::with_capacity(code.len());
+    for (index, line) in code.split('\n').enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        let trimmed = line.trim_end();
+        let can_comment = index < max_lines
+            && !trimmed.trim_start().is_empty()
+            && !trimmed.ends_with('\\')
+            && !in_string.get(index).copied().unwrap_or_default();
+        if can_comment {
+            output.push_str(trimmed);
+            output.push_str("  ");
+            output.push_str(comment);
+        } else {
+            // Lines inside strings are kept byte for byte
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+/// Line metadata of a statement: for each (0-based) line, whether a string literal continues
+/// past its end. Returns `None` if the code cannot be parsed.
+fn string_continuation_lines(code: &str) -> Option<Vec<bool>> {
+    let module = ruff_python_parser::parse_module(code).ok()?.into_syntax();
+    let starts = line_starts(code);
+    let mut collector = MultilineStrings {
+        starts: &starts,
+        in_string: vec![false; starts.len()],
+    };
+    collector.visit_body(&module.body);
+    Some(collector.in_string)
+}
+
+struct MultilineStrings<'s> {
+    starts: &'s [usize],
+    in_string: Vec<bool>,
+}
+
+impl<'a> SourceOrderVisitor<'a> for MultilineStrings<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if matches!(
+            expr,
+            Expr::StringLiteral(_) | Expr::BytesLiteral(_) | Expr::FString(_) | Expr::TString(_)
+        ) {
+            let first = line_number(self.starts, expr.start());
+            let last = line_number(self.starts, expr.end());
+            for line in first..last {
+                self.in_string[line - 1] = true;
+            }
+            return;
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::resolver::ModuleId;
+
+    fn statement(code: &str, origin: StatementOrigin) -> AttributedStatement {
+        AttributedStatement {
+            code: code.to_owned(),
+            origin,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_mark_generated_comments_every_generated_line() {
+        let wrapped = StatementOrigin::Wrapped {
+            module_id: ModuleId::new(1),
+            offset: TextSize::from(0),
+        };
+        let source = StatementOrigin::Source {
+            module_id: ModuleId::new(1),
+            offset: TextSize::from(0),
+        };
+        let mut statements = vec![
+            statement("import types", StatementOrigin::Generated),
+            statement(
+                "if True:\n    x = \"\"\"a\nb\"\"\"\n    y = 1",
+                StatementOrigin::Generated,
+            ),
+            statement("def _cribo_init():\n    return helper()", wrapped),
+            statement("def helper():\n    return 1", source),
+        ];
+
+        mark_generated(&mut statements, "E501,F401");
+        let code: Vec<&str> = statements.iter().map(|s| s.code.as_str()).collect();
+        assert_eq!(
+            code,
+            [
+                "import types  # noqa: E501,F401",
+                "if True:  # noqa: E501,F401\n    x = \"\"\"a\nb\"\"\"  # noqa: E501,F401\n    y \
+                 = 1  # noqa: E501,F401",
+                "def _cribo_init():  # noqa: E501,F401\n    return helper()",
+                "def helper():\n    return 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_noqa_comment_normalizes_codes() {
+        assert_eq!(noqa_comment(" E501, F811 ,"), "# noqa: E501,F811");
+        assert_eq!(noqa_comment(""), "# noqa");
+    }
+}
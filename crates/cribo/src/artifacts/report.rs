@@ -0,0 +1,301 @@
+//! Analysis report (`--emit-analysis-report`).
+//!
+//! Summarizes the decisions taken between analysis and code generation: how each module is
+//! bundled, which symbols were renamed to resolve conflicts, which circular dependencies were
+//! found and how they are handled, and how many symbols survived tree-shaking.
+
+use serde::Serialize;
+
+use crate::{
+    analyzers::types::{CircularDependencyAnalysis, CircularDependencyGroup, ResolutionStrategy},
+    resolver::{ModuleId, ModuleResolver},
+    symbol_conflict_resolver::SymbolConflictResolver,
+    types::{FxIndexMap, FxIndexSet},
+};
+
+/// Borrowed view of everything the analysis phases decided for a bundling run
+pub(crate) struct AnalysisResults<'a> {
+    pub resolver: &'a ModuleResolver,
+    /// Modules in bundle order
+    pub module_ids: &'a [ModuleId],
+    pub inlined_modules: &'a FxIndexSet<ModuleId>,
+    pub wrapper_modules: &'a FxIndexSet<ModuleId>,
+    pub conflict_resolver: &'a SymbolConflictResolver,
+    pub circular_dep_analysis: Option<&'a CircularDependencyAnalysis>,
+    /// Symbols kept per module, `None` when tree-shaking is disabled
+    pub tree_shaking_keep_symbols: Option<&'a FxIndexMap<ModuleId, FxIndexSet<String>>>,
+}
+
+/// How a module is emitted into the bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ModuleKind {
+    Entry,
+    Inline,
+    Wrapper,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ModuleReport {
+    pub name: String,
+    pub path: String,
+    pub kind: ModuleKind,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SymbolRename {
+    pub module: String,
+    pub renamed_to: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ConflictReport {
+    pub symbol: String,
+    pub resolutions: Vec<SymbolRename>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CycleReport {
+    pub modules: Vec<String>,
+    pub cycle_type: String,
+    pub resolution: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ModuleTreeShaking {
+    pub module: String,
+    pub kept: usize,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct TreeShakingReport {
+    pub symbols_kept: usize,
+    pub symbols_removed: usize,
+    /// Modules that lost at least one symbol
+    pub modules: Vec<ModuleTreeShaking>,
+}
+
+/// Serializable summary of the analysis decisions of a bundling run
+#[derive(Debug, Serialize)]
+pub(crate) struct AnalysisReport {
+    pub modules: Vec<ModuleReport>,
+    pub symbol_conflicts: Vec<ConflictReport>,
+    pub circular_dependencies: Vec<CycleReport>,
+    /// `None` when tree-shaking is disabled
+    pub tree_shaking: Option<TreeShakingReport>,
+}
+
+impl AnalysisResults<'_> {
+    /// Build the serializable report for these results
+    pub(crate) fn into_report(self) -> AnalysisReport {
+        AnalysisReport {
+            modules: self.module_reports(),
+            symbol_conflicts: self.conflict_reports(),
+            circular_dependencies: self.cycle_reports(),
+            tree_shaking: self.tree_shaking_report(),
+        }
+    }
+
+    fn module_name(&self, module_id: ModuleId) -> String {
+        self.resolver
+            .get_module_name(module_id)
+            .unwrap_or_else(|| format!("module_{}", module_id.as_u32()))
+    }
+
+    /// Bundled modules in bundle order with how each one is emitted
+    pub(crate) fn module_reports(&self) -> Vec<ModuleReport> {
+        self.module_ids
+            .iter()
+            .filter_map(|&module_id| {
+                let kind = if module_id.is_entry() {
+                    ModuleKind::Entry
+                } else if self.wrapper_modules.contains(&module_id) {
+                    ModuleKind::Wrapper
+                } else if self.inlined_modules.contains(&module_id) {
+                    ModuleKind::Inline
+                } else {
+                    // Not bundled (e.g. a namespace package without code)
+                    return None;
+                };
+                Some(ModuleReport {
+                    name: self.module_name(module_id),
+                    path: self
+                        .resolver
+                        .get_module_path(module_id)
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default(),
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    fn conflict_reports(&self) -> Vec<ConflictReport> {
+        let registry = self.conflict_resolver.symbol_registry();
+        registry
+            .detect_conflicts()
+            .into_iter()
+            .map(|conflict| ConflictReport {
+                resolutions: conflict
+                    .modules
+                    .iter()
+                    .filter_map(|&module_id| {
+                        registry
+                            .get_rename(module_id, &conflict.symbol)
+                            .map(|renamed| SymbolRename {
+                                module: self.module_name(module_id),
+                                renamed_to: renamed.to_owned(),
+                            })
+                    })
+                    .collect(),
+                symbol: conflict.symbol,
+            })
+            .collect()
+    }
+
+    fn cycle_reports(&self) -> Vec<CycleReport> {
+        let Some(analysis) = self.circular_dep_analysis else {
+            return Vec::new();
+        };
+        analysis
+            .resolvable_cycles
+            .iter()
+            .chain(&analysis.unresolvable_cycles)
+            .map(|group| self.cycle_report(group))
+            .collect()
+    }
+
+    fn cycle_report(&self, group: &CircularDependencyGroup) -> CycleReport {
+        let resolution = match &group.suggested_resolution {
+            ResolutionStrategy::Resolvable if self.wrapper_modules.is_empty() => {
+                "function-scoped imports".to_owned()
+            }
+            ResolutionStrategy::Resolvable => {
+                "function-scoped imports and wrapper modules".to_owned()
+            }
+            ResolutionStrategy::Lazy => "lazy wrapper module initialization".to_owned(),
+            ResolutionStrategy::Unresolvable { reason } => format!("unresolvable: {reason}"),
+        };
+        CycleReport {
+            modules: group
+                .modules
+                .iter()
+                .map(|&module_id| self.module_name(module_id))
+                .collect(),
+            cycle_type: format!("{:?}", group.cycle_type),
+            resolution,
+        }
+    }
+
+    fn tree_shaking_report(&self) -> Option<TreeShakingReport> {
+        let keep_symbols = self.tree_shaking_keep_symbols?;
+        let mut report = TreeShakingReport::default();
+
+        for &module_id in self.module_ids {
+            // The entry module is never tree-shaken
+            if module_id.is_entry() {
+                continue;
+            }
+            let Some(info) = self.conflict_resolver.get_module_info(module_id) else {
+                continue;
+            };
+            let kept_symbols = keep_symbols.get(&module_id);
+            let (kept, removed): (Vec<&String>, Vec<&String>) = info
+                .module_scope_symbols
+                .iter()
+                .partition(|symbol| kept_symbols.is_some_and(|kept| kept.contains(*symbol)));
+
+            report.symbols_kept += kept.len();
+            report.symbols_removed += removed.len();
+            if !removed.is_empty() {
+                report.modules.push(ModuleTreeShaking {
+                    module: self.module_name(module_id),
+                    kept: kept.len(),
+                    removed: removed.into_iter().cloned().collect(),
+                });
+            }
+        }
+
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_into_report_lists_modules_conflicts_and_tree_shaking() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+        let helpers = resolver.register_module("helpers", Path::new("helpers.py"));
+
+        let mut conflict_resolver = SymbolConflictResolver::new();
+        for (module_id, source, path) in [
+            (entry, "def run():\n    pass\n", "main.py"),
+            (
+                utils,
+                "def run():\n    pass\n\ndef unused():\n    pass\n",
+                "utils.py",
+            ),
+            (helpers, "VALUE = 1\n", "helpers.py"),
+        ] {
+            let ast = parse_module(source)
+                .expect("Test code should parse successfully")
+                .into_syntax();
+            conflict_resolver.analyze_module(module_id, &ast, Path::new(path));
+        }
+        conflict_resolver.detect_and_resolve_conflicts();
+
+        let module_ids = [helpers, utils, entry];
+        let inlined: FxIndexSet<ModuleId> = [utils].into_iter().collect();
+        let wrapper: FxIndexSet<ModuleId> = [helpers].into_iter().collect();
+        let mut keep_symbols: FxIndexMap<ModuleId, FxIndexSet<String>> = FxIndexMap::default();
+        keep_symbols.insert(utils, std::iter::once("run".to_owned()).collect());
+        keep_symbols.insert(helpers, std::iter::once("VALUE".to_owned()).collect());
+
+        let report = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &inlined,
+            wrapper_modules: &wrapper,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: Some(&keep_symbols),
+        }
+        .into_report();
+
+        let kinds: Vec<_> = report
+            .modules
+            .iter()
+            .map(|module| (module.name.as_str(), module.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("helpers", ModuleKind::Wrapper),
+                ("utils", ModuleKind::Inline),
+                ("main", ModuleKind::Entry),
+            ]
+        );
+
+        assert_eq!(report.symbol_conflicts.len(), 1);
+        assert_eq!(report.symbol_conflicts[0].symbol, "run");
+        assert_eq!(report.symbol_conflicts[0].resolutions.len(), 2);
+
+        let tree_shaking = report
+            .tree_shaking
+            .expect("tree-shaking statistics should be present");
+        assert_eq!(tree_shaking.symbols_kept, 2);
+        assert_eq!(tree_shaking.symbols_removed, 1);
+        assert_eq!(tree_shaking.modules[0].removed, ["unused"]);
+        assert!(report.circular_dependencies.is_empty());
+    }
+}
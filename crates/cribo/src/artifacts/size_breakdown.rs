@@ -0,0 +1,126 @@
+//! Size each module contributes to the bundle (`--emit-size-breakdown`).
+//!
+//! Every top-level bundle statement is counted towards the module it was copied or wrapped
+//! from; statements the bundler synthesizes on its own (namespaces, registries) are counted as
+//! bundler glue. Sizes are bytes of generated code, including the newline after each statement.
+
+use std::fmt::Write;
+
+use crate::{artifacts::attribution::AttributedStatement, resolver::ModuleId, types::FxIndexMap};
+
+/// Row name for statements generated by the bundler itself
+const GENERATED_ROW: &str = "(bundler)";
+
+/// A bundled module with its original source size
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleSize {
+    pub module_id: ModuleId,
+    pub name: String,
+    /// Bytes of the module's source file
+    pub original: usize,
+}
+
+/// Render a table of the original and bundled size of each module, largest first
+pub(crate) fn render(statements: &[AttributedStatement], modules: &[ModuleSize]) -> String {
+    let mut bundled: FxIndexMap<Option<ModuleId>, usize> = FxIndexMap::default();
+    for statement in statements {
+        *bundled.entry(statement.origin.module_id()).or_default() += statement.code.len() + 1;
+    }
+    let total: usize = bundled.values().sum();
+
+    let mut rows: Vec<(&str, Option<usize>, usize)> = modules
+        .iter()
+        .map(|module| {
+            (
+                module.name.as_str(),
+                Some(module.original),
+                bundled.get(&Some(module.module_id)).copied().unwrap_or(0),
+            )
+        })
+        .collect();
+    if let Some(&generated) = bundled.get(&None) {
+        rows.push((GENERATED_ROW, None, generated));
+    }
+    rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+
+    let name_width = rows
+        .iter()
+        .map(|(name, ..)| name.len())
+        .chain(std::iter::once("Module".len()))
+        .max()
+        .unwrap_or_default();
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "{:<name_width$}  {:>12}  {:>12}  {:>7}",
+        "Module", "Original (B)", "Bundled (B)", "Share"
+    );
+    for (name, original, size) in rows {
+        let original = original.map_or_else(|| "-".to_owned(), |original| original.to_string());
+        let share = if total == 0 {
+            0.0
+        } else {
+            size as f64 * 100.0 / total as f64
+        };
+        let _ = writeln!(
+            output,
+            "{name:<name_width$}  {original:>12}  {size:>12}  {share:>6.1}%"
+        );
+    }
+    let _ = writeln!(output, "{:<name_width$}  {:>12}  {total:>12}", "Total", "");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::artifacts::attribution::StatementOrigin;
+
+    fn statement(code: &str, module_id: Option<ModuleId>) -> AttributedStatement {
+        AttributedStatement {
+            code: code.to_owned(),
+            origin: module_id.map_or(StatementOrigin::Generated, |module_id| {
+                StatementOrigin::Source {
+                    module_id,
+                    offset: TextSize::default(),
+                }
+            }),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_render_counts_statements_per_module() {
+        let utils = ModuleId::new(1);
+        let statements = [
+            statement("import types", None),
+            statement("def helper():\n    return 1", Some(utils)),
+            statement("print(helper())", Some(ModuleId::ENTRY)),
+        ];
+        let modules = [
+            ModuleSize {
+                module_id: ModuleId::ENTRY,
+                name: "main".to_owned(),
+                original: 40,
+            },
+            ModuleSize {
+                module_id: utils,
+                name: "utils".to_owned(),
+                original: 30,
+            },
+        ];
+
+        assert_eq!(
+            render(&statements, &modules),
+            "\
+Module     Original (B)   Bundled (B)    Share
+utils                30            27    48.2%
+main                 40            16    28.6%
+(bundler)             -            13    23.2%
+Total                              56
+"
+        );
+    }
+}
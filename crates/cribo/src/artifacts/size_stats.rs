@@ -0,0 +1,78 @@
+//! Raw and gzip-compressed size of the bundle (`--emit-size-stats`).
+//!
+//! Bundled scripts are often shipped compressed, so the raw size overstates what is actually
+//! distributed. The bundle is compressed at gzip levels 1 (fastest), 6 (the gzip default) and 9
+//! (smallest) to show the trade-off between them.
+
+use std::{fmt::Write as _, io::Write as _};
+
+use flate2::{Compression, write::GzEncoder};
+
+/// Gzip levels the bundle is compressed at
+const GZIP_LEVELS: [u32; 3] = [1, 6, 9];
+
+/// Render a table of the bundle size, raw and at each of [`GZIP_LEVELS`]
+pub(crate) fn render(code: &str) -> String {
+    let raw = code.len();
+    let mut rows = vec![("raw".to_owned(), raw)];
+    rows.extend(
+        GZIP_LEVELS
+            .iter()
+            .map(|&level| (format!("gzip -{level}"), gzip_size(code, level))),
+    );
+
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "{:<11}  {:>10}  {:>7}",
+        "Encoding", "Size (B)", "Ratio"
+    );
+    for (encoding, size) in rows {
+        let ratio = if raw == 0 {
+            0.0
+        } else {
+            size as f64 * 100.0 / raw as f64
+        };
+        let _ = writeln!(output, "{encoding:<11}  {size:>10}  {ratio:>6.1}%");
+    }
+    output
+}
+
+/// Bytes of `code` compressed with gzip at `level`
+fn gzip_size(code: &str, level: u32) -> usize {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level));
+    encoder
+        .write_all(code.as_bytes())
+        .expect("Writing to a Vec never fails");
+    encoder
+        .finish()
+        .expect("Writing to a Vec never fails")
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_reports_raw_and_compressed_sizes() {
+        let code = "def helper():\n    return 1\n".repeat(100);
+        let output = render(&code);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "Encoding       Size (B)    Ratio");
+        assert_eq!(lines[1], "raw                2700   100.0%");
+
+        let sizes: Vec<usize> = lines[2..]
+            .iter()
+            .map(|line| {
+                line.split_whitespace()
+                    .nth(2)
+                    .and_then(|size| size.parse().ok())
+                    .expect("every row has a size")
+            })
+            .collect();
+        assert!(sizes.iter().all(|&size| size > 0 && size < code.len()));
+        assert!(sizes[2] <= sizes[0]);
+    }
+}
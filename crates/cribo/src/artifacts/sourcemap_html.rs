@@ -0,0 +1,185 @@
+//! Side-by-side HTML view of the bundle and its original sources (`--emit-sourcemap-html`).
+//!
+//! The bundle is shown on the left and the source of every bundled module on the right.
+//! Statement attribution maps each bundle statement to the line it starts on in its module:
+//! clicking a bundle statement highlights and scrolls to that line, and clicking a source line
+//! highlights the bundle statement starting closest before it in the same module.
+
+use std::{fmt::Write, path::PathBuf};
+
+use crate::artifacts::{
+    attribution::AttributedStatement,
+    html::{escape_html, highlight_python},
+};
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 0; background: #fafafa; color: #222; }
+h1 { margin: 0; padding: 0.5em 1em; font-size: 1.2em; border-bottom: 1px solid #ddd; }
+main { display: grid; grid-template-columns: 1fr 1fr; height: calc(100vh - 3em); }
+section { overflow: auto; border-right: 1px solid #ddd; }
+h2 { margin: 0; padding: 0.4em 0.8em; font-size: 1em; background: #f0f0f0; }
+h2 .path { font-weight: normal; color: #666; margin-left: 1em; }
+pre { margin: 0; padding: 0.5em 0.8em; }
+.stmt, .line { display: block; border-left: 3px solid transparent; }
+.stmt[data-module], .line { cursor: pointer; }
+.stmt.generated { color: #666; }
+.line::before { content: attr(data-line); display: inline-block; width: 3em; color: #999; }
+.selected { background: #fffbe6; border-left-color: #e0b000; }
+.kw { color: #0033b3; font-weight: bold; }
+.str { color: #067d17; }
+.num { color: #1750eb; }
+.com { color: #8c8c8c; font-style: italic; }
+";
+
+const SCRIPT: &str = "\
+function select(stmt, line) {
+  document.querySelectorAll('.selected').forEach(el => el.classList.remove('selected'));
+  for (const el of [stmt, line]) {
+    if (el) {
+      el.classList.add('selected');
+      el.scrollIntoView({ block: 'nearest' });
+    }
+  }
+}
+document.querySelectorAll('.stmt[data-module]').forEach(stmt => {
+  stmt.addEventListener('click', () => {
+    select(stmt, document.getElementById(`m${stmt.dataset.module}-L${stmt.dataset.line}`));
+  });
+});
+document.querySelectorAll('.line').forEach(line => {
+  line.addEventListener('click', () => {
+    const number = Number(line.dataset.line);
+    let closest = null;
+    document.querySelectorAll(`.stmt[data-module=\"${line.dataset.module}\"]`).forEach(stmt => {
+      const start = Number(stmt.dataset.line);
+      if (start <= number && (!closest || start > Number(closest.dataset.line))) {
+        closest = stmt;
+      }
+    });
+    select(closest, line);
+  });
+});
+";
+
+/// Source of a bundled module as it was read from disk
+#[derive(Debug, Clone)]
+pub(crate) struct OriginalSource {
+    pub module_name: String,
+    pub path: PathBuf,
+    pub source: String,
+}
+
+/// Render the side-by-side page for a bundle
+pub(crate) fn render(
+    title: &str,
+    statements: &[AttributedStatement],
+    sources: &[OriginalSource],
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>Cribo bundle: {}</title>", escape_html(title));
+    let _ = writeln!(html, "<style>\n{STYLE}</style>\n</head>\n<body>");
+    let _ = writeln!(
+        html,
+        "<h1>Cribo bundle: {}</h1>\n<main>",
+        escape_html(title)
+    );
+
+    html.push_str("<section class=\"bundle\">\n<h2>bundle</h2>\n<pre><code>");
+    for statement in statements {
+        let module = statement.location.as_ref().and_then(|location| {
+            sources
+                .iter()
+                .position(|source| source.path == location.path)
+                .map(|index| (index, location.line))
+        });
+        match module {
+            Some((index, line)) => {
+                let _ = write!(
+                    html,
+                    "<span class=\"stmt\" data-module=\"{index}\" data-line=\"{line}\">"
+                );
+            }
+            None => html.push_str("<span class=\"stmt generated\">"),
+        }
+        highlight_python(&statement.code, &mut html);
+        html.push_str("</span>");
+    }
+    html.push_str("</code></pre>\n</section>\n");
+
+    html.push_str("<section class=\"sources\">\n");
+    for (index, source) in sources.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<h2>{}<span class=\"path\">{}</span></h2>",
+            escape_html(&source.module_name),
+            escape_html(&source.path.display().to_string())
+        );
+        html.push_str("<pre><code>");
+        for (number, line) in source.source.lines().enumerate() {
+            let number = number + 1;
+            let _ = write!(
+                html,
+                "<span class=\"line\" id=\"m{index}-L{number}\" data-module=\"{index}\" \
+                 data-line=\"{number}\">{}</span>",
+                escape_html(line)
+            );
+        }
+        html.push_str("</code></pre>\n");
+    }
+    html.push_str("</section>\n</main>\n");
+
+    let _ = writeln!(html, "<script>\n{SCRIPT}</script>\n</body>\n</html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_text_size::TextSize;
+
+    use super::*;
+    use crate::{
+        artifacts::attribution::{SourceLocation, StatementOrigin},
+        resolver::ModuleId,
+    };
+
+    #[test]
+    fn test_render_links_bundle_statements_to_source_lines() {
+        let utils = ModuleId::new(1);
+        let statements = [
+            AttributedStatement {
+                code: "import types".to_owned(),
+                origin: StatementOrigin::Generated,
+                location: None,
+            },
+            AttributedStatement {
+                code: "def helper_1():\n    return 1".to_owned(),
+                origin: StatementOrigin::Source {
+                    module_id: utils,
+                    offset: TextSize::new(11),
+                },
+                location: Some(SourceLocation {
+                    module_name: "utils".to_owned(),
+                    path: PathBuf::from("utils.py"),
+                    line: 3,
+                }),
+            },
+        ];
+        let sources = [OriginalSource {
+            module_name: "utils".to_owned(),
+            path: PathBuf::from("utils.py"),
+            source: "import os\n\ndef helper():\n    return 1 < 2\n".to_owned(),
+        }];
+
+        let html = render("main.py", &statements, &sources);
+
+        assert!(html.contains("<span class=\"stmt generated\"><span class=\"kw\">import</span>"));
+        assert!(html.contains("<span class=\"stmt\" data-module=\"0\" data-line=\"3\">"));
+        assert!(html.contains(
+            "<span class=\"line\" id=\"m0-L3\" data-module=\"0\" data-line=\"3\">def \
+             helper():</span>"
+        ));
+        assert!(html.contains("data-line=\"4\">    return 1 &lt; 2</span>"));
+        assert!(html.contains("<h2>utils<span class=\"path\">utils.py</span></h2>"));
+    }
+}
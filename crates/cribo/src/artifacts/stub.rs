@@ -0,0 +1,114 @@
+//! Import-only stub for the bundle (`--emit-imports-only`).
+//!
+//! The stub re-exports the public API of the entry module from the generated bundle, so that
+//! adapter layers can import from a stable module while the bundle itself is regenerated.
+
+use std::fmt::Write;
+
+use ruff_python_ast::{Expr, ModModule, Stmt};
+
+use crate::{types::FxIndexSet, visitors::ExportCollector};
+
+/// Public API of a module: its static `__all__` when present, otherwise every module-level
+/// function, class and assigned name that does not start with an underscore
+pub(crate) fn public_api(module: &ModModule) -> Vec<String> {
+    let export_info = ExportCollector::analyze(module);
+    if let Some(names) = export_info.exported_names
+        && !export_info.is_dynamic
+    {
+        return names;
+    }
+
+    let mut names = FxIndexSet::default();
+    for stmt in &module.body {
+        match stmt {
+            Stmt::FunctionDef(func_def) => {
+                names.insert(func_def.name.to_string());
+            }
+            Stmt::ClassDef(class_def) => {
+                names.insert(class_def.name.to_string());
+            }
+            Stmt::Assign(assign) => {
+                for target in &assign.targets {
+                    if let Expr::Name(name) = target {
+                        names.insert(name.id.to_string());
+                    }
+                }
+            }
+            Stmt::AnnAssign(ann_assign) => {
+                if let Expr::Name(name) = ann_assign.target.as_ref() {
+                    names.insert(name.id.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+        .into_iter()
+        .filter(|name| !name.starts_with('_'))
+        .collect()
+}
+
+/// Render the stub importing `symbols` from the bundle module `bundle_module`
+pub(crate) fn render(bundle_module: &str, symbols: &[String]) -> String {
+    let mut stub = String::new();
+    stub.push_str("# Generated by Cribo - Python Source Bundler\n");
+    writeln!(
+        stub,
+        "# Re-exports the public API of the `{bundle_module}` bundle\n"
+    )
+    .expect("Writing to String never fails");
+    writeln!(stub, "from {bundle_module} import *  # noqa: F403")
+        .expect("Writing to String never fails");
+    if !symbols.is_empty() {
+        writeln!(stub, "from {bundle_module} import (").expect("Writing to String never fails");
+        for symbol in symbols {
+            writeln!(stub, "    {symbol},").expect("Writing to String never fails");
+        }
+        stub.push_str(")\n");
+    }
+    stub
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    fn api(source: &str) -> Vec<String> {
+        let module = parse_module(source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        public_api(&module)
+    }
+
+    #[test]
+    fn test_public_api_prefers_all() {
+        assert_eq!(
+            api("__all__ = ['run']\n\ndef run():\n    pass\n\ndef helper():\n    pass\n"),
+            ["run"]
+        );
+    }
+
+    #[test]
+    fn test_public_api_falls_back_to_public_definitions() {
+        assert_eq!(
+            api(
+                "import os\n\nVERSION: str = '1.0'\n_cache = {}\n\nclass App:\n    pass\n\ndef \
+                 main():\n    pass\n"
+            ),
+            ["VERSION", "App", "main"]
+        );
+    }
+
+    #[test]
+    fn test_render_imports_each_symbol() {
+        assert_eq!(
+            render("bundle", &["App".to_owned(), "main".to_owned()]),
+            "# Generated by Cribo - Python Source Bundler\n# Re-exports the public API of the \
+             `bundle` bundle\n\nfrom bundle import *  # noqa: F403\nfrom bundle import (\n    \
+             App,\n    main,\n)\n"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Cross-reference of bundled symbols (`--emit-symbol-table`).
+//!
+//! For every public module-level definition of every bundled module, the table records where
+//! it was defined, the name it has in the bundle after conflict renaming, which modules import
+//! it, and whether tree-shaking kept it.
+
+use ruff_python_ast::{Expr, ModModule, Stmt};
+use ruff_text_size::{Ranged, TextSize};
+use serde::Serialize;
+
+use crate::{
+    artifacts::{
+        attribution::{line_number, line_starts},
+        report::AnalysisResults,
+    },
+    resolver::ModuleId,
+    types::{FxIndexMap, FxIndexSet},
+};
+
+/// A bundled module's AST together with the source its offsets refer to
+pub(crate) struct ModuleSource<'a> {
+    pub module_id: ModuleId,
+    pub ast: &'a ModModule,
+    pub source: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SymbolEntry {
+    pub path: String,
+    /// 1-based line of the first definition
+    pub line: usize,
+    /// Name of the symbol in the bundle (differs from the original when renamed)
+    pub bundled_name: String,
+    /// Modules importing the symbol with `from ... import`
+    pub imported_by: Vec<String>,
+    /// Whether the symbol survived tree-shaking
+    pub kept: bool,
+}
+
+/// Module name -> symbol name -> entry, in bundle order
+pub(crate) type SymbolTable = FxIndexMap<String, FxIndexMap<String, SymbolEntry>>;
+
+/// Build the symbol table for the bundled modules
+pub(crate) fn build(results: &AnalysisResults<'_>, modules: &[ModuleSource<'_>]) -> SymbolTable {
+    let sources: FxIndexMap<ModuleId, &ModuleSource<'_>> = modules
+        .iter()
+        .map(|module| (module.module_id, module))
+        .collect();
+    let importers = collect_importers(results, modules);
+    let registry = results.conflict_resolver.symbol_registry();
+
+    let mut table = SymbolTable::default();
+    for &module_id in results.module_ids {
+        let is_bundled = module_id.is_entry()
+            || results.inlined_modules.contains(&module_id)
+            || results.wrapper_modules.contains(&module_id);
+        let Some(module) = sources.get(&module_id).filter(|_| is_bundled) else {
+            continue;
+        };
+        let module_name = module_name(results, module_id);
+        let path = results
+            .resolver
+            .get_module_path(module_id)
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let starts = line_starts(module.source);
+        let kept_symbols = results
+            .tree_shaking_keep_symbols
+            .and_then(|keep_symbols| keep_symbols.get(&module_id));
+        let module_importers = importers.get(&module_name);
+
+        let mut symbols = FxIndexMap::default();
+        for (name, offset) in public_definitions(module.ast) {
+            let mut imported_by: FxIndexSet<String> = FxIndexSet::default();
+            if let Some(module_importers) = module_importers {
+                for imported in [name.as_str(), "*"] {
+                    if let Some(importing) = module_importers.get(imported) {
+                        imported_by.extend(importing.iter().cloned());
+                    }
+                }
+            }
+            let entry = SymbolEntry {
+                path: path.clone(),
+                line: line_number(&starts, offset),
+                bundled_name: registry
+                    .get_rename(module_id, &name)
+                    .unwrap_or(&name)
+                    .to_owned(),
+                imported_by: imported_by.into_iter().collect(),
+                // The entry module is never tree-shaken
+                kept: module_id.is_entry()
+                    || results.tree_shaking_keep_symbols.is_none()
+                    || kept_symbols.is_some_and(|kept| kept.contains(&name)),
+            };
+            symbols.insert(name, entry);
+        }
+        table.insert(module_name, symbols);
+    }
+    table
+}
+
+fn module_name(results: &AnalysisResults<'_>, module_id: ModuleId) -> String {
+    results
+        .resolver
+        .get_module_name(module_id)
+        .unwrap_or_else(|| format!("module_{}", module_id.as_u32()))
+}
+
+/// Imported module name -> imported name (or `*`) -> importing modules
+fn collect_importers(
+    results: &AnalysisResults<'_>,
+    modules: &[ModuleSource<'_>],
+) -> FxIndexMap<String, FxIndexMap<String, FxIndexSet<String>>> {
+    let mut importers: FxIndexMap<String, FxIndexMap<String, FxIndexSet<String>>> =
+        FxIndexMap::default();
+    for module in modules {
+        let importing_module = module_name(results, module.module_id);
+        for stmt in &module.ast.body {
+            let Stmt::ImportFrom(import_from) = stmt else {
+                continue;
+            };
+            let imported_module = if import_from.level > 0 {
+                results.resolver.resolve_relative_import_from_package_name(
+                    import_from.level,
+                    import_from.module.as_deref(),
+                    &importing_module,
+                )
+            } else if let Some(module) = &import_from.module {
+                module.to_string()
+            } else {
+                continue;
+            };
+            let names = importers.entry(imported_module).or_default();
+            for alias in &import_from.names {
+                names
+                    .entry(alias.name.to_string())
+                    .or_default()
+                    .insert(importing_module.clone());
+            }
+        }
+    }
+    importers
+}
+
+/// Public names defined at module level, with the offset of their first definition
+fn public_definitions(module: &ModModule) -> Vec<(String, TextSize)> {
+    let mut definitions: FxIndexMap<String, TextSize> = FxIndexMap::default();
+    for stmt in &module.body {
+        let names: Vec<&str> = match stmt {
+            Stmt::FunctionDef(func_def) => vec![func_def.name.as_str()],
+            Stmt::ClassDef(class_def) => vec![class_def.name.as_str()],
+            Stmt::Assign(assign) => assign
+                .targets
+                .iter()
+                .filter_map(|target| match target {
+                    Expr::Name(name) => Some(name.id.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            Stmt::AnnAssign(ann_assign) => match ann_assign.target.as_ref() {
+                Expr::Name(name) => vec![name.id.as_str()],
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        for name in names {
+            if !name.starts_with('_') {
+                definitions
+                    .entry(name.to_owned())
+                    .or_insert_with(|| stmt.range().start());
+            }
+        }
+    }
+    definitions.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::{
+        config::Config, resolver::ModuleResolver, symbol_conflict_resolver::SymbolConflictResolver,
+    };
+
+    #[test]
+    fn test_build_cross_references_symbols() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+
+        let entry_source = "from utils import run\n\ndef run_all():\n    run()\n";
+        let utils_source =
+            "import os\n\ndef run():\n    pass\n\ndef unused():\n    pass\n\n_cache = {}\n";
+        let entry_ast = parse_module(entry_source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        let utils_ast = parse_module(utils_source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+
+        let mut conflict_resolver = SymbolConflictResolver::new();
+        conflict_resolver.analyze_module(entry, &entry_ast, Path::new("main.py"));
+        conflict_resolver.analyze_module(utils, &utils_ast, Path::new("utils.py"));
+        conflict_resolver.detect_and_resolve_conflicts();
+
+        let module_ids = [utils, entry];
+        let inlined: FxIndexSet<ModuleId> = [utils].into_iter().collect();
+        let wrapper = FxIndexSet::default();
+        let mut keep_symbols: FxIndexMap<ModuleId, FxIndexSet<String>> = FxIndexMap::default();
+        keep_symbols.insert(utils, std::iter::once("run".to_owned()).collect());
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &inlined,
+            wrapper_modules: &wrapper,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: Some(&keep_symbols),
+        };
+        let table = build(
+            &results,
+            &[
+                ModuleSource {
+                    module_id: entry,
+                    ast: &entry_ast,
+                    source: entry_source,
+                },
+                ModuleSource {
+                    module_id: utils,
+                    ast: &utils_ast,
+                    source: utils_source,
+                },
+            ],
+        );
+
+        let modules: Vec<&str> = table.keys().map(String::as_str).collect();
+        assert_eq!(modules, ["utils", "main"]);
+
+        let utils_symbols = &table["utils"];
+        let names: Vec<&str> = utils_symbols.keys().map(String::as_str).collect();
+        assert_eq!(names, ["run", "unused"]);
+        assert_eq!(utils_symbols["run"].line, 3);
+        assert_eq!(utils_symbols["run"].bundled_name, "run");
+        assert_eq!(utils_symbols["run"].imported_by, ["main"]);
+        assert!(utils_symbols["run"].kept);
+        assert!(utils_symbols["unused"].imported_by.is_empty());
+        assert!(!utils_symbols["unused"].kept);
+
+        assert!(table["main"]["run_all"].kept);
+    }
+}
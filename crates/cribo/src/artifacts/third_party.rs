@@ -0,0 +1,74 @@
+//! Runtime third-party requirements of the bundle (`--emit-third-party-list`).
+//!
+//! Unlike `--emit-requirements`, which lists every third-party import of the bundled modules,
+//! this only lists the packages the bundle itself still imports: imports dropped by tree-shaking
+//! or import trimming are not runtime requirements. Import names are mapped to their
+//! distribution names the same way as for `--emit-requirements`.
+
+use ruff_python_ast::{ModModule, Stmt};
+
+use crate::{
+    artifacts::unused_imports::collect_imports,
+    resolver::{ImportType, ModuleResolver},
+};
+
+/// Collect the sorted, deduplicated package names of the third-party imports in the bundle
+pub(crate) fn build(resolver: &ModuleResolver, bundled: &ModModule) -> Vec<String> {
+    let mut packages: Vec<String> = collect_imports(&bundled.body)
+        .into_iter()
+        .flat_map(imported_modules)
+        .filter(|module| resolver.classify_import(module) == ImportType::ThirdParty)
+        .map(|module| resolver.map_import_to_package_name(module))
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+/// Render the list with one package per line
+pub(crate) fn render(packages: &[String]) -> String {
+    packages
+        .iter()
+        .map(|package| format!("{package}\n"))
+        .collect()
+}
+
+/// The absolute modules imported by an import statement
+fn imported_modules(stmt: &Stmt) -> Vec<&str> {
+    match stmt {
+        Stmt::Import(import) => import
+            .names
+            .iter()
+            .map(|alias| alias.name.as_str())
+            .collect(),
+        Stmt::ImportFrom(import_from) if import_from.level == 0 => import_from
+            .module
+            .iter()
+            .map(|module| module.as_str())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_build_lists_only_third_party_imports_in_bundle() {
+        let resolver = ModuleResolver::new(Config::default());
+        let bundled = parse_module(
+            "import os\nimport cribo_test_requests.adapters\nfrom cribo_test_yaml import \
+             safe_load\nfrom . import sibling\n\ndef init():\n    import cribo_test_requests\n",
+        )
+        .expect("test bundle should parse")
+        .into_syntax();
+
+        let packages = build(&resolver, &bundled);
+        assert_eq!(packages, ["cribo_test_requests", "cribo_test_yaml"]);
+        assert_eq!(render(&packages), "cribo_test_requests\ncribo_test_yaml\n");
+    }
+}
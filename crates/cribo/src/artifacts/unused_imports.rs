@@ -0,0 +1,171 @@
+//! Report of imports removed by bundling (`--emit-unused-imports`).
+//!
+//! Every import statement of every parsed module (including imports nested in functions) whose
+//! code does not appear as an import statement in the bundle is listed with its file and line.
+//! Imports of inlined or wrapped modules, tree-shaken imports and stdlib imports replaced by
+//! the `_cribo` proxy all show up here; imports kept as written (e.g. third-party imports) do
+//! not.
+
+use std::fmt::Write;
+
+use ruff_python_ast::{
+    ModModule, Stmt,
+    visitor::source_order::{SourceOrderVisitor, walk_stmt},
+};
+use ruff_python_codegen::{Generator, Stylist};
+use ruff_text_size::Ranged;
+
+use crate::{
+    artifacts::{
+        attribution::{line_number, line_starts},
+        report::AnalysisResults,
+        symbol_table::ModuleSource,
+    },
+    types::FxIndexSet,
+};
+
+/// An original import statement that is not part of the bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RemovedImport {
+    pub file: String,
+    /// 1-based line of the import statement
+    pub line: usize,
+    /// The import statement, normalized to a single line
+    pub import: String,
+}
+
+/// Collect the imports of the parsed modules that the bundle does not preserve, in bundle order
+pub(crate) fn build(
+    results: &AnalysisResults<'_>,
+    modules: &[ModuleSource<'_>],
+    bundled: &ModModule,
+    stylist: &Stylist<'_>,
+) -> Vec<RemovedImport> {
+    let to_code = |stmt: &Stmt| Generator::from(stylist).stmt(stmt);
+    let preserved: FxIndexSet<String> = collect_imports(&bundled.body)
+        .into_iter()
+        .map(to_code)
+        .collect();
+
+    let mut removed = Vec::new();
+    for &module_id in results.module_ids {
+        let Some(module) = modules.iter().find(|module| module.module_id == module_id) else {
+            continue;
+        };
+        let file = results
+            .resolver
+            .get_module_path(module_id)
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let starts = line_starts(module.source);
+
+        for stmt in collect_imports(&module.ast.body) {
+            let import = to_code(stmt);
+            if !preserved.contains(&import) {
+                removed.push(RemovedImport {
+                    file: file.clone(),
+                    line: line_number(&starts, stmt.start()),
+                    import,
+                });
+            }
+        }
+    }
+    removed
+}
+
+/// Render the report as one `file:line: import` line per removed import
+pub(crate) fn render(removed: &[RemovedImport]) -> String {
+    let mut output = String::new();
+    for entry in removed {
+        writeln!(output, "{}:{}: {}", entry.file, entry.line, entry.import)
+            .expect("Writing to String never fails");
+    }
+    output
+}
+
+/// All import statements in `body`, including nested ones, in source order
+pub(crate) fn collect_imports(body: &[Stmt]) -> Vec<&Stmt> {
+    let mut collector = ImportCollector::default();
+    collector.visit_body(body);
+    collector.imports
+}
+
+#[derive(Default)]
+struct ImportCollector<'a> {
+    imports: Vec<&'a Stmt>,
+}
+
+impl<'a> SourceOrderVisitor<'a> for ImportCollector<'a> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if matches!(stmt, Stmt::Import(_) | Stmt::ImportFrom(_)) {
+            self.imports.push(stmt);
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+    use crate::{
+        config::Config, resolver::ModuleResolver, symbol_conflict_resolver::SymbolConflictResolver,
+        types::FxIndexMap,
+    };
+
+    #[test]
+    fn test_build_lists_imports_missing_from_bundle() {
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", Path::new("main.py"));
+
+        let entry_source = "import os\nimport requests\nfrom pkg import (\n    helper,\n)\n\ndef \
+                            run():\n    import json\n";
+        let entry_ast = parse_module(entry_source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+        let bundled = parse_module("import requests\n_cribo_os = _cribo.os\n")
+            .expect("Test code should parse successfully");
+        let stylist = Stylist::from_tokens(bundled.tokens(), "");
+
+        let conflict_resolver = SymbolConflictResolver::new();
+        let module_ids = [entry];
+        let empty = FxIndexSet::default();
+        let keep_symbols = FxIndexMap::default();
+        let results = AnalysisResults {
+            resolver: &resolver,
+            module_ids: &module_ids,
+            inlined_modules: &empty,
+            wrapper_modules: &empty,
+            conflict_resolver: &conflict_resolver,
+            circular_dep_analysis: None,
+            tree_shaking_keep_symbols: Some(&keep_symbols),
+        };
+        let removed = build(
+            &results,
+            &[ModuleSource {
+                module_id: entry,
+                ast: &entry_ast,
+                source: entry_source,
+            }],
+            bundled.syntax(),
+            &stylist,
+        );
+
+        let summary: Vec<(usize, &str)> = removed
+            .iter()
+            .map(|entry| (entry.line, entry.import.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, "import os"),
+                (3, "from pkg import helper"),
+                (8, "import json")
+            ]
+        );
+        assert!(render(&removed).ends_with("main.py:8: import json\n"));
+    }
+}
@@ -7,9 +7,9 @@
 use ruff_python_ast::{
     AtomicNodeIndex, BoolOp, CmpOp, Expr, ExprAttribute, ExprBinOp, ExprBoolOp, ExprBooleanLiteral,
     ExprCall, ExprCompare, ExprContext, ExprIf, ExprList, ExprName, ExprNoneLiteral,
-    ExprStringLiteral, ExprSubscript, ExprTuple, ExprUnaryOp, FStringFlags, FStringPart,
-    FStringValue, Keyword, Operator, StringLiteral, StringLiteralFlags, StringLiteralValue,
-    UnaryOp,
+    ExprNumberLiteral, ExprSlice, ExprStringLiteral, ExprSubscript, ExprTuple, ExprUnaryOp,
+    FStringFlags, FStringPart, FStringValue, Int, Keyword, Number, Operator, StringLiteral,
+    StringLiteralFlags, StringLiteralValue, UnaryOp,
 };
 use ruff_text_size::TextRange;
 
@@ -102,6 +102,21 @@ pub(crate) fn string_literal(value: &str) -> Expr {
     })
 }
 
+/// Creates an integer literal expression node.
+///
+/// # Example
+/// ```rust
+/// // Creates: `1`
+/// let expr = int_literal(1);
+/// ```
+pub(crate) fn int_literal(value: u32) -> Expr {
+    Expr::NumberLiteral(ExprNumberLiteral {
+        value: Number::Int(Int::from(value)),
+        range: TextRange::default(),
+        node_index: AtomicNodeIndex::NONE,
+    })
+}
+
 /// Creates a None literal expression node.
 ///
 /// # Example
@@ -329,6 +344,17 @@ pub(crate) fn subscript(value: Expr, slice: Expr, ctx: ExprContext) -> Expr {
     })
 }
 
+/// Create a slice expression: `lower:upper`
+pub(crate) fn slice(lower: Option<Expr>, upper: Option<Expr>) -> Expr {
+    Expr::Slice(ExprSlice {
+        node_index: AtomicNodeIndex::NONE,
+        lower: lower.map(Box::new),
+        upper: upper.map(Box::new),
+        step: None,
+        range: TextRange::default(),
+    })
+}
+
 /// Creates a tuple expression node.
 ///
 /// # Arguments
@@ -447,6 +473,28 @@ pub(crate) fn in_op(left: Expr, right: Expr) -> Expr {
     })
 }
 
+/// Creates a single comparison expression node.
+///
+/// # Arguments
+/// * `left` - The left operand
+/// * `op` - The comparison operator
+/// * `right` - The right operand
+///
+/// # Example
+/// ```rust
+/// // Creates: `x is None`
+/// let expr = compare(name("x", ExprContext::Load), CmpOp::Is, none_literal());
+/// ```
+pub(crate) fn compare(left: Expr, op: CmpOp, right: Expr) -> Expr {
+    Expr::Compare(ExprCompare {
+        left: Box::new(left),
+        ops: Box::new([op]),
+        comparators: Box::new([right]),
+        range: TextRange::default(),
+        node_index: AtomicNodeIndex::NONE,
+    })
+}
+
 /// Creates a keyword argument node.
 ///
 /// # Arguments
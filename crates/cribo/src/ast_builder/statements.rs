@@ -5,9 +5,9 @@
 //! to indicate their synthetic nature.
 
 use ruff_python_ast::{
-    Alias, AtomicNodeIndex, Decorator, ExceptHandler, Expr, ExprContext, Identifier, Parameters,
-    Stmt, StmtAssign, StmtExpr, StmtFunctionDef, StmtGlobal, StmtImport, StmtImportFrom, StmtPass,
-    StmtRaise, StmtReturn, StmtTry,
+    Alias, AtomicNodeIndex, Decorator, ExceptHandler, Expr, ExprContext, Identifier, MatchCase,
+    Parameters, Pattern, Stmt, StmtAssign, StmtExpr, StmtFunctionDef, StmtGlobal, StmtImport,
+    StmtImportFrom, StmtMatch, StmtPass, StmtRaise, StmtReturn, StmtTry,
 };
 use ruff_text_size::TextRange;
 
@@ -390,6 +390,29 @@ pub(crate) fn if_stmt(condition: Expr, body: Vec<Stmt>, orelse: Vec<Stmt>) -> St
     })
 }
 
+/// Create a match statement over `subject` (Python 3.10+ targets only)
+#[expect(dead_code, reason = "no match-based dispatch is generated yet")]
+pub(crate) fn match_stmt(subject: Expr, cases: Vec<MatchCase>) -> Stmt {
+    Stmt::Match(StmtMatch {
+        node_index: AtomicNodeIndex::NONE,
+        subject: Box::new(subject),
+        cases,
+        range: TextRange::default(),
+    })
+}
+
+/// Create a case clause of a match statement: case pattern if guard: body
+#[expect(dead_code, reason = "no match-based dispatch is generated yet")]
+pub(crate) fn match_case(pattern: Pattern, guard: Option<Expr>, body: Vec<Stmt>) -> MatchCase {
+    MatchCase {
+        node_index: AtomicNodeIndex::NONE,
+        pattern,
+        guard: guard.map(Box::new),
+        body,
+        range: TextRange::default(),
+    }
+}
+
 /// Create a subscript assignment statement: target[key] = value
 pub(crate) fn subscript_assign(target: Expr, key: Expr, value: Expr) -> Stmt {
     Stmt::Assign(StmtAssign {
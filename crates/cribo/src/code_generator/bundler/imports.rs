@@ -283,6 +283,21 @@ impl Bundler<'_> {
         None
     }
 
+    /// Key under which a `parent.child = ...` namespace assignment is tracked in
+    /// `parent_child_assignments_made`
+    ///
+    /// The parent is always keyed by its namespace variable, including top-level parents whose
+    /// module name is not a valid identifier as-is.
+    pub(crate) fn parent_child_assignment_key(
+        parent_module: &str,
+        child: &str,
+    ) -> (String, String) {
+        (
+            sanitize_module_name_for_identifier(parent_module),
+            child.to_owned(),
+        )
+    }
+
     /// Create the entire namespace chain for a module with proper parent-child assignments
     /// For example, for "services.auth.manager", this creates:
     /// - services namespace (if needed)
@@ -350,11 +365,11 @@ impl Bundler<'_> {
         // Now create parent.child assignments for the entire chain
         for i in 1..parts.len() {
             let parent_path = parts[0..i].join(".");
-            let parent_var = sanitize_module_name_for_identifier(&parent_path);
             let child_name = parts[i];
 
             // Check if this parent.child assignment has already been made
-            let assignment_key = (parent_var.clone(), child_name.to_owned());
+            let assignment_key = Self::parent_child_assignment_key(&parent_path, child_name);
+            let parent_var = assignment_key.0.clone();
             if self.parent_child_assignments_made.contains(&assignment_key) {
                 log::debug!(
                     "Skipping duplicate namespace chain assignment: {parent_var}.{child_name} \
@@ -984,13 +999,11 @@ impl Bundler<'_> {
             .map_or_else(|| "<unknown>".to_owned(), |m| m.name);
 
         // Check if this module is a parent namespace that already exists
-        let is_parent_namespace = self.bundled_modules.iter().any(|other_module_id| {
-            let Some(module_info) = self.resolver.get_module(*other_module_id) else {
-                return false;
-            };
-            let name = &module_info.name;
-            name != &module_name && name.starts_with(&format!("{module_name}."))
-        });
+        let is_parent_namespace = self
+            .resolver
+            .find_submodules(&module_name)
+            .iter()
+            .any(|(submodule_id, _)| self.bundled_modules.contains(submodule_id));
 
         if is_parent_namespace {
             // Use temp variable and merge attributes for parent namespaces
@@ -3,6 +3,7 @@
 mod imports;
 mod symbols;
 mod transforms;
+pub(crate) mod validation;
 
 use std::{cell::RefCell, path::PathBuf, sync::Arc};
 
@@ -14,6 +15,7 @@ use crate::{
         context::{BundleParams, SemanticContext},
         expression_handlers, import_deduplicator,
         module_registry::is_init_function,
+        profiling::{PhaseTimer, ProfileCollector},
     },
     dependency_graph::DependencyGraph,
     resolver::{ModuleId, ModuleResolver},
@@ -85,6 +87,9 @@ pub(crate) struct Bundler<'a> {
     pub(crate) modules_with_explicit_all: FxIndexSet<ModuleId>,
     /// Transformation context for tracking node mappings
     pub(crate) transformation_context: TransformationContext,
+    /// Modules in the order their ASTs were indexed; position `n` owns node indices
+    /// `n * MODULE_INDEX_RANGE..(n + 1) * MODULE_INDEX_RANGE`
+    pub(crate) indexed_module_order: Vec<ModuleId>,
     /// Module/symbol pairs that should be kept after tree shaking
     /// Maps module ID to set of symbols to keep in that module
     pub(crate) tree_shaking_keep_symbols: Option<FxIndexMap<ModuleId, FxIndexSet<String>>>,
@@ -100,6 +105,8 @@ pub(crate) struct Bundler<'a> {
     pub(crate) emitted_wrapper_inits: FxIndexSet<ModuleId>,
     /// Cached imports that appear under `TYPE_CHECKING` guards, keyed by imported module.
     type_checking_import_index: RefCell<Option<TypeCheckingImportIndex>>,
+    /// Phase timings collected for `--profile` (`None` when not profiling)
+    pub(crate) profile: Option<RefCell<ProfileCollector>>,
 }
 
 impl std::fmt::Debug for Bundler<'_> {
@@ -352,11 +359,20 @@ impl<'a> Bundler<'a> {
             graph: None,
             modules_with_explicit_all: FxIndexSet::default(),
             transformation_context: TransformationContext::new(),
+            indexed_module_order: Vec::new(),
             tree_shaking_keep_symbols: None,
             modules_with_accessed_all: FxIndexSet::default(),
             conflict_resolver: None,
             emitted_wrapper_inits: FxIndexSet::default(),
             type_checking_import_index: RefCell::new(None),
+            profile: None,
+        }
+    }
+
+    /// Add the timings of `phase` to the `--profile` summary, if profiling
+    pub(crate) fn record_phase<P: PhaseTimer>(&self, phase: &P) {
+        if let Some(profile) = &self.profile {
+            profile.borrow_mut().collect(phase);
         }
     }
 
@@ -751,9 +767,11 @@ impl<'a> Bundler<'a> {
         log::debug!("Indexing {} modules", modules.len());
         let mut total_nodes = 0_u32;
         let mut module_id_counter = 0_u32;
+        self.indexed_module_order.clear();
 
         for (module_id, (ast, _, _content_hash)) in modules.iter_mut() {
             let indexed = crate::ast_indexer::index_module_with_id(ast, module_id_counter);
+            self.indexed_module_order.push(*module_id);
             let node_count = indexed.node_count;
             let module_name = self
                 .resolver
@@ -136,6 +136,22 @@ impl Bundler<'_> {
 
         final_body.push(stmt.clone());
 
+        // A renamed class keeps its original name for introspection (`type(self).__name__`,
+        // repr), as inlined classes do
+        if matches!(stmt, Stmt::ClassDef(_))
+            && let Some((original, renamed)) = &pending_reassignment
+            && original != renamed
+        {
+            final_body.push(statements::set_string_attribute(
+                renamed, "__name__", original,
+            ));
+            final_body.push(statements::set_string_attribute(
+                renamed,
+                "__qualname__",
+                original,
+            ));
+        }
+
         // Add reassignment if needed, but skip if original and renamed are the same
         // or if the reassignment already exists
         if let Some((original, renamed)) = pending_reassignment
@@ -567,9 +583,10 @@ impl Bundler<'_> {
                     );
                 }
             }
-            for stmt in &mut class_def.body {
-                expression_handlers::rewrite_aliases_in_stmt(stmt, entry_module_renames);
-            }
+            expression_handlers::rewrite_aliases_in_class_body(
+                &mut class_def.body,
+                entry_module_renames,
+            );
         }
 
         if needs_reassignment {
@@ -1009,6 +1026,7 @@ impl<'a> Visitor<'a> for TypeCheckingImportCollector<'a> {
 #[cfg(test)]
 mod tests {
     use ruff_python_ast::Expr;
+    use ruff_python_codegen::{Generator, Stylist};
 
     use super::*;
     use crate::{config::Config, resolver::ModuleResolver};
@@ -1063,6 +1081,42 @@ else:
         assert!(symbols.contains("U"));
     }
 
+    #[test]
+    fn test_renamed_entry_class_keeps_original_name() {
+        let resolver = ModuleResolver::new(Config::default());
+        let mut bundler = Bundler::new(None, &resolver);
+        bundler.entry_module_name = "main".to_owned();
+        let entry_module_renames =
+            FxIndexMap::from_iter([("Settings".to_owned(), "Settings_main".to_owned())]);
+
+        let source = "\
+@dataclass
+class Settings:
+    name: str = 'app'
+    def __post_init__(self):
+        print(type(self).__name__)
+";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut stmt = parsed.into_syntax().body.remove(0);
+        let mut final_body = Vec::new();
+        bundler.process_entry_module_statement(&mut stmt, &entry_module_renames, &mut final_body);
+
+        let generated: Vec<String> = final_body
+            .iter()
+            .skip(1)
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect();
+        assert_eq!(
+            generated,
+            [
+                "Settings_main.__name__ = 'Settings'",
+                "Settings_main.__qualname__ = 'Settings'",
+                "Settings = Settings_main",
+            ]
+        );
+    }
+
     #[test]
     fn test_reorder_statements_for_circular_module_preserves_entry_order() {
         let resolver = ModuleResolver::new(Config::default());
@@ -0,0 +1,144 @@
+//! Consistency checks on the bundling plan before code generation.
+//!
+//! After classification and rename collection, the bundler holds the full plan: which modules
+//! are inlined or wrapped, which symbols are renamed and which survive tree-shaking. A plan that
+//! contradicts itself produces broken bundles far from the cause, so violations are reported
+//! before any code is generated.
+
+use std::fmt;
+
+use crate::{code_generator::bundler::Bundler, resolver::ModuleId, types::FxIndexMap};
+
+/// A violation of the bundling plan's invariants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PlanValidationError {
+    /// A module is classified both as inlined and as a wrapper module
+    InlinedAndWrapped { module_id: ModuleId },
+    /// Symbol renames are recorded for a module the resolver does not know
+    RenamesForUnknownModule { module_id: ModuleId },
+    /// A symbol is renamed to something that is not a valid Python identifier
+    InvalidRename {
+        module_id: ModuleId,
+        symbol: String,
+        renamed_to: String,
+    },
+    /// Tree-shaking kept symbols of a module the resolver does not know
+    KeptSymbolsForUnknownModule { module_id: ModuleId },
+}
+
+impl fmt::Display for PlanValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InlinedAndWrapped { module_id } => {
+                write!(f, "Module {module_id} is both inlined and wrapped")
+            }
+            Self::RenamesForUnknownModule { module_id } => {
+                write!(f, "Symbol renames recorded for unknown module {module_id}")
+            }
+            Self::InvalidRename {
+                module_id,
+                symbol,
+                renamed_to,
+            } => write!(
+                f,
+                "Symbol '{symbol}' of module {module_id} is renamed to invalid identifier \
+                 '{renamed_to}'"
+            ),
+            Self::KeptSymbolsForUnknownModule { module_id } => {
+                write!(f, "Tree-shaking kept symbols of unknown module {module_id}")
+            }
+        }
+    }
+}
+
+impl Bundler<'_> {
+    /// Check the bundling plan for contradictions, returning every violation found
+    pub(crate) fn validate_plan(
+        &self,
+        symbol_renames: &FxIndexMap<ModuleId, FxIndexMap<String, String>>,
+    ) -> Vec<PlanValidationError> {
+        let mut errors = Vec::new();
+
+        for &module_id in &self.inlined_modules {
+            if self.wrapper_modules.contains(&module_id) {
+                errors.push(PlanValidationError::InlinedAndWrapped { module_id });
+            }
+        }
+
+        for (&module_id, renames) in symbol_renames {
+            if self.resolver.get_module_name(module_id).is_none() {
+                errors.push(PlanValidationError::RenamesForUnknownModule { module_id });
+            }
+            for (symbol, renamed_to) in renames {
+                if !ruff_python_stdlib::identifiers::is_identifier(renamed_to) {
+                    errors.push(PlanValidationError::InvalidRename {
+                        module_id,
+                        symbol: symbol.clone(),
+                        renamed_to: renamed_to.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(keep_symbols) = &self.tree_shaking_keep_symbols {
+            for &module_id in keep_symbols.keys() {
+                if self.resolver.get_module_name(module_id).is_none() {
+                    errors.push(PlanValidationError::KeptSymbolsForUnknownModule { module_id });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{config::Config, resolver::ModuleResolver, types::FxIndexSet};
+
+    #[test]
+    fn test_validate_plan_reports_all_violations() {
+        let resolver = ModuleResolver::new(Config::default());
+        resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+        let unknown = ModuleId::new(42);
+
+        let mut bundler = Bundler::new(None, &resolver);
+        bundler.inlined_modules.insert(utils);
+        bundler.wrapper_modules.insert(utils);
+        let mut keep_symbols = FxIndexMap::default();
+        keep_symbols.insert(unknown, FxIndexSet::default());
+        bundler.tree_shaking_keep_symbols = Some(keep_symbols);
+
+        let mut symbol_renames: FxIndexMap<ModuleId, FxIndexMap<String, String>> =
+            FxIndexMap::default();
+        symbol_renames.insert(
+            utils,
+            [
+                ("helper".to_owned(), "helper_1".to_owned()),
+                ("other".to_owned(), "other-1".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        symbol_renames.insert(unknown, FxIndexMap::default());
+
+        assert_eq!(
+            bundler.validate_plan(&symbol_renames),
+            [
+                PlanValidationError::InlinedAndWrapped { module_id: utils },
+                PlanValidationError::InvalidRename {
+                    module_id: utils,
+                    symbol: "other".to_owned(),
+                    renamed_to: "other-1".to_owned(),
+                },
+                PlanValidationError::RenamesForUnknownModule { module_id: unknown },
+                PlanValidationError::KeptSymbolsForUnknownModule { module_id: unknown },
+            ]
+        );
+        assert_eq!(bundler.validate_plan(&FxIndexMap::default()).len(), 2);
+    }
+}
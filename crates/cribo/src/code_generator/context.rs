@@ -62,6 +62,16 @@ pub(crate) struct BundleParams<'a> {
     pub python_version: u8,                                           /* Target Python version
                                                                        * for
                                                                        * builtin checks */
+    /// Bundle every first-party module through a wrapper init function (`--no-inline`)
+    pub no_inline: bool,
+    /// Leave out modules whose every symbol was tree-shaken (`--prune-unused-modules`)
+    pub prune_unused_modules: bool,
+    /// Bind namespace attributes to flat names instead of namespace objects
+    /// (`--no-namespace-objects`)
+    pub no_namespace_objects: bool,
+    /// Keep imports the entry module repeats from earlier in the bundle
+    /// (`--no-deduplicate-imports`)
+    pub no_deduplicate_imports: bool,
 }
 
 // ==================== Phase Result Types ====================
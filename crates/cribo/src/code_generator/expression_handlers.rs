@@ -5,8 +5,8 @@
 
 use log::debug;
 use ruff_python_ast::{
-    AtomicNodeIndex, ExceptHandler, Expr, ExprAttribute, ExprContext, Identifier, Stmt, StmtAssign,
-    StmtClassDef, StmtFunctionDef,
+    AtomicNodeIndex, ExceptHandler, Expr, ExprAttribute, ExprContext, Identifier, Pattern, Stmt,
+    StmtAssign, StmtClassDef, StmtFunctionDef,
 };
 use ruff_text_size::TextRange;
 
@@ -884,6 +884,18 @@ pub(crate) fn rewrite_aliases_in_stmt(
                 rewrite_aliases_in_expr(target, alias_to_canonical);
             }
         }
+        Stmt::Match(match_stmt) => {
+            rewrite_aliases_in_expr(&mut match_stmt.subject, alias_to_canonical);
+            for case in &mut match_stmt.cases {
+                rewrite_aliases_in_pattern(&mut case.pattern, alias_to_canonical);
+                if let Some(ref mut guard) = case.guard {
+                    rewrite_aliases_in_expr(guard, alias_to_canonical);
+                }
+                for stmt in &mut case.body {
+                    rewrite_aliases_in_stmt(stmt, alias_to_canonical);
+                }
+            }
+        }
         Stmt::Global(_)
         | Stmt::Nonlocal(_)
         | Stmt::Pass(_)
@@ -901,6 +913,72 @@ pub(crate) fn rewrite_aliases_in_stmt(
     }
 }
 
+/// Rewrite aliases in `match` case patterns
+///
+/// Capture names bind like assignment targets, so they are renamed the same way; value and class
+/// patterns are ordinary load expressions.
+fn rewrite_aliases_in_pattern(
+    pattern: &mut Pattern,
+    alias_to_canonical: &FxIndexMap<String, String>,
+) {
+    match pattern {
+        Pattern::MatchValue(value_pattern) => {
+            rewrite_aliases_in_expr(&mut value_pattern.value, alias_to_canonical);
+        }
+        Pattern::MatchSingleton(_) => {}
+        Pattern::MatchSequence(sequence_pattern) => {
+            for pattern in &mut sequence_pattern.patterns {
+                rewrite_aliases_in_pattern(pattern, alias_to_canonical);
+            }
+        }
+        Pattern::MatchMapping(mapping_pattern) => {
+            for key in &mut mapping_pattern.keys {
+                rewrite_aliases_in_expr(key, alias_to_canonical);
+            }
+            for pattern in &mut mapping_pattern.patterns {
+                rewrite_aliases_in_pattern(pattern, alias_to_canonical);
+            }
+            rewrite_capture_name(&mut mapping_pattern.rest, alias_to_canonical);
+        }
+        Pattern::MatchClass(class_pattern) => {
+            rewrite_aliases_in_expr(&mut class_pattern.cls, alias_to_canonical);
+            for pattern in &mut class_pattern.arguments.patterns {
+                rewrite_aliases_in_pattern(pattern, alias_to_canonical);
+            }
+            for keyword in &mut class_pattern.arguments.keywords {
+                rewrite_aliases_in_pattern(&mut keyword.pattern, alias_to_canonical);
+            }
+        }
+        Pattern::MatchStar(star_pattern) => {
+            rewrite_capture_name(&mut star_pattern.name, alias_to_canonical);
+        }
+        Pattern::MatchAs(as_pattern) => {
+            if let Some(ref mut pattern) = as_pattern.pattern {
+                rewrite_aliases_in_pattern(pattern, alias_to_canonical);
+            }
+            rewrite_capture_name(&mut as_pattern.name, alias_to_canonical);
+        }
+        Pattern::MatchOr(or_pattern) => {
+            for pattern in &mut or_pattern.patterns {
+                rewrite_aliases_in_pattern(pattern, alias_to_canonical);
+            }
+        }
+    }
+}
+
+/// Rename a pattern capture variable (dotted canonical names cannot be binding targets)
+fn rewrite_capture_name(
+    name: &mut Option<Identifier>,
+    alias_to_canonical: &FxIndexMap<String, String>,
+) {
+    if let Some(identifier) = name
+        && let Some(canonical) = alias_to_canonical.get(identifier.as_str())
+        && !canonical.contains('.')
+    {
+        *identifier = Identifier::new(canonical.clone(), identifier.range);
+    }
+}
+
 /// Rewrite aliases in exception handlers
 fn rewrite_aliases_in_except_handler(
     handler: &mut ExceptHandler,
@@ -1129,9 +1207,48 @@ fn rewrite_aliases_in_class(
         }
     }
 
-    // Rewrite class body
-    for stmt in &mut class_def.body {
-        rewrite_aliases_in_stmt(stmt, alias_to_canonical);
+    rewrite_aliases_in_class_body(&mut class_def.body, alias_to_canonical);
+}
+
+/// Rewrite the statements of a class body
+///
+/// Names bound by assignments in a class body are class attributes, not module-level symbols,
+/// so they keep their names even when a module-level symbol of the same name is renamed: they
+/// are read as `obj.name`, as the fields of dataclasses, `TypedDict`s and `NamedTuple`s, and as
+/// enum members. Only annotations and assigned values are rewritten.
+pub(crate) fn rewrite_aliases_in_class_body(
+    body: &mut [Stmt],
+    alias_to_canonical: &FxIndexMap<String, String>,
+) {
+    for stmt in body {
+        match stmt {
+            Stmt::AnnAssign(ann_assign) if is_binding_target(&ann_assign.target) => {
+                rewrite_aliases_in_expr(&mut ann_assign.annotation, alias_to_canonical);
+                if let Some(value) = &mut ann_assign.value {
+                    rewrite_aliases_in_expr(value, alias_to_canonical);
+                }
+            }
+            Stmt::Assign(assign) => {
+                for target in &mut assign.targets {
+                    if !is_binding_target(target) {
+                        rewrite_aliases_in_expr(target, alias_to_canonical);
+                    }
+                }
+                rewrite_aliases_in_expr(&mut assign.value, alias_to_canonical);
+            }
+            _ => rewrite_aliases_in_stmt(stmt, alias_to_canonical),
+        }
+    }
+}
+
+/// Check if an assignment target only binds names (`x`, `a, b`, `[first, *rest]`)
+fn is_binding_target(target: &Expr) -> bool {
+    match target {
+        Expr::Name(_) => true,
+        Expr::Tuple(tuple) => tuple.elts.iter().all(is_binding_target),
+        Expr::List(list) => list.elts.iter().all(is_binding_target),
+        Expr::Starred(starred) => is_binding_target(&starred.value),
+        _ => false,
     }
 }
 
@@ -1211,3 +1328,154 @@ pub(crate) fn expressions_are_equal(expr1: &Expr, expr2: &Expr) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+
+    use super::*;
+
+    fn rewrite(source: &str, renames: &[(&str, &str)]) -> String {
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.into_syntax();
+        let renames: FxIndexMap<String, String> = renames
+            .iter()
+            .map(|(from, to)| ((*from).to_owned(), (*to).to_owned()))
+            .collect();
+
+        for stmt in &mut module.body {
+            rewrite_aliases_in_stmt(stmt, &renames);
+        }
+        module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_rewrite_aliases_in_match_capture_patterns() {
+        let source = "\
+match command:
+    case [Point(x=0, y=y), *rest] if y > LIMIT:
+        result = y
+    case {\"key\": value, **other}:
+        result = value
+    case Config() as config:
+        result = config
+";
+        let rewritten = rewrite(
+            source,
+            &[
+                ("command", "command_1"),
+                ("Point", "Point_2"),
+                ("y", "y_1"),
+                ("rest", "rest_1"),
+                ("LIMIT", "LIMIT_1"),
+                ("value", "value_1"),
+                ("other", "other_1"),
+                ("Config", "Config_1"),
+                ("config", "config_1"),
+            ],
+        );
+
+        assert!(rewritten.starts_with("match command_1:"));
+        assert!(rewritten.contains("case [Point_2(x=0, y=y_1), *rest_1] if y_1 > LIMIT_1:"));
+        assert!(rewritten.contains("result = y_1"));
+        assert!(rewritten.contains("case {\"key\": value_1, **other_1}:"));
+        assert!(rewritten.contains("case Config_1() as config_1:"));
+    }
+
+    #[test]
+    fn test_rewrite_aliases_keeps_class_attribute_names() {
+        let source = "\
+@dataclasses.dataclass(frozen=True)
+class User:
+    name: Name = DEFAULT
+    tags: list = field(default_factory=list)
+
+class Plain:
+    name: Name = DEFAULT
+
+updated = dataclasses.replace(user, name=name)
+";
+        let rewritten = rewrite(
+            source,
+            &[
+                ("name", "name_1"),
+                ("Name", "Name_1"),
+                ("DEFAULT", "DEFAULT_1"),
+            ],
+        );
+
+        assert!(rewritten.contains("class User:\n    name: Name_1 = DEFAULT_1"));
+        assert!(rewritten.contains("class Plain:\n    name: Name_1 = DEFAULT_1"));
+        assert!(rewritten.contains("updated = dataclasses.replace(user, name=name_1)"));
+    }
+
+    #[test]
+    fn test_rewrite_aliases_keeps_record_and_enum_member_names() {
+        let source = "\
+class Movie(typing.TypedDict):
+    name: Name
+
+class Sequel(Movie):
+    name: Name
+
+class Point(NamedTuple):
+    name: Name = DEFAULT
+
+class Color(Enum):
+    name = DEFAULT
+    first, *rest = DEFAULT
+";
+        let rewritten = rewrite(
+            source,
+            &[
+                ("name", "name_1"),
+                ("first", "first_1"),
+                ("rest", "rest_1"),
+                ("Name", "Name_1"),
+                ("DEFAULT", "DEFAULT_1"),
+            ],
+        );
+
+        assert!(rewritten.contains("class Movie(typing.TypedDict):\n    name: Name_1"));
+        assert!(rewritten.contains("class Sequel(Movie):\n    name: Name_1"));
+        assert!(rewritten.contains("class Point(NamedTuple):\n    name: Name_1 = DEFAULT_1"));
+        assert!(rewritten.contains("class Color(Enum):\n    name = DEFAULT_1"));
+        assert!(!rewritten.contains("first_1") && !rewritten.contains("rest_1"));
+    }
+
+    #[test]
+    fn test_rewrite_aliases_keeps_namedtuple_factory_field_names() {
+        let source = r#"
+Point = namedtuple("Point", ["x", "y"], defaults=[DEFAULT])
+Pair = NamedTuple("Pair", left=Name)
+"#;
+        let rewritten = rewrite(
+            source,
+            &[
+                ("x", "x_1"),
+                ("left", "left_1"),
+                ("Name", "Name_1"),
+                ("DEFAULT", "DEFAULT_1"),
+            ],
+        );
+
+        assert!(rewritten.contains(r#"namedtuple("Point", ["x", "y"], defaults=[DEFAULT_1])"#));
+        assert!(rewritten.contains(r#"NamedTuple("Pair", left=Name_1)"#));
+    }
+
+    #[test]
+    fn test_rewrite_aliases_in_match_keeps_dotted_renames_out_of_captures() {
+        let rewritten = rewrite(
+            "match x:\n    case Path() as p:\n        pass\n",
+            &[("Path", "pathlib.Path"), ("p", "os.p")],
+        );
+
+        assert!(rewritten.contains("case pathlib.Path() as p:"));
+    }
+}
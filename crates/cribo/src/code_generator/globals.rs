@@ -31,6 +31,11 @@ fn sanitize_var_name(name: &str) -> String {
 }
 
 /// Transformer that lifts module-level globals to true global scope
+///
+/// Lifted names are always derived from the declaring module, even for names the module
+/// imported from another bundled module: `from a import X` followed by `global X` rebinds only
+/// the importing module's `X`, so sharing one lifted variable with `a` would leak assignments
+/// back into `a`.
 pub(crate) struct GlobalsLifter {
     /// Map from original name to lifted name
     pub lifted_names: FxIndexMap<String, String>,
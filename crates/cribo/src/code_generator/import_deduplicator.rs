@@ -10,9 +10,39 @@ use ruff_python_ast::{Alias, ModModule, Stmt, StmtImport, StmtImportFrom};
 use super::bundler::Bundler;
 use crate::{
     dependency_graph::DependencyGraph,
+    resolver::ImportType,
     types::{FxIndexMap, FxIndexSet},
 };
 
+/// Origin of a module imported by a statement that is kept in the bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HoistedImportType {
+    /// `__future__`, whose imports must stay at the top of the bundle
+    Future,
+    /// Standard library module, imported through the `_cribo` proxy
+    Stdlib,
+    /// Installed package, imported at runtime as written
+    ThirdParty,
+    /// First-party module that could not be bundled
+    FirstParty,
+}
+
+/// Classify the module of a kept import with the resolver's classification (configured
+/// first/third-party names, the full stdlib list for the target version, search paths)
+pub(super) fn classify_hoisted_import(
+    bundler: &Bundler<'_>,
+    module_name: &str,
+) -> HoistedImportType {
+    if module_name == "__future__" {
+        return HoistedImportType::Future;
+    }
+    match bundler.resolver.classify_import(module_name) {
+        ImportType::StandardLibrary => HoistedImportType::Stdlib,
+        ImportType::ThirdParty => HoistedImportType::ThirdParty,
+        ImportType::FirstParty => HoistedImportType::FirstParty,
+    }
+}
+
 /// Check if a statement is a hoisted import
 pub(super) fn is_hoisted_import(_bundler: &Bundler<'_>, stmt: &Stmt) -> bool {
     match stmt {
@@ -40,18 +70,17 @@ pub(super) fn is_duplicate_import_from(
     bundler: &Bundler<'_>,
     import_from: &StmtImportFrom,
     existing_body: &[Stmt],
-    python_version: u8,
 ) -> bool {
     if let Some(ref module) = import_from.module {
         let module_name = module.as_str();
-        // For third-party imports, check if they're already in the body
-        // Check if it's a stdlib module
-        let root_module = module_name.split('.').next().unwrap_or(module_name);
-        let is_stdlib =
-            ruff_python_stdlib::sys::is_known_standard_library(python_version, root_module);
-        let is_third_party = !is_stdlib && !is_bundled_module_or_package(bundler, module_name);
-
-        if is_third_party {
+        // For imports that stay as written, check if they're already in the body
+        let is_kept_as_written = match classify_hoisted_import(bundler, module_name) {
+            HoistedImportType::ThirdParty => true,
+            HoistedImportType::FirstParty => !is_bundled_module_or_package(bundler, module_name),
+            HoistedImportType::Future | HoistedImportType::Stdlib => false,
+        };
+
+        if is_kept_as_written {
             return existing_body.iter().any(|existing| {
                 if let Stmt::ImportFrom(existing_import) = existing {
                     existing_import
@@ -766,3 +795,97 @@ fn should_remove_import_stmt(
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{config::Config, resolver::ModuleResolver};
+
+    fn resolver() -> ModuleResolver {
+        let config = Config {
+            known_first_party: ["mylib".to_owned(), "vendored".to_owned()]
+                .into_iter()
+                .collect(),
+            known_third_party: ["requests".to_owned()].into_iter().collect(),
+            ..Default::default()
+        };
+        let resolver = ModuleResolver::new(config);
+        resolver.register_module("main", Path::new("main.py"));
+        resolver
+    }
+
+    /// Check whether the `from` import `source` repeats an identical earlier import
+    fn is_repeated_import_from(bundler: &Bundler<'_>, source: &str) -> bool {
+        let module = ruff_python_parser::parse_module(&format!("{source}\n{source}\n"))
+            .expect("test module should parse")
+            .into_syntax();
+        let Stmt::ImportFrom(import_from) = &module.body[1] else {
+            panic!("expected a from import");
+        };
+        is_duplicate_import_from(bundler, import_from, &module.body[..1])
+    }
+
+    #[test]
+    fn test_classify_hoisted_import() {
+        let resolver = resolver();
+        let bundler = Bundler::new(None, &resolver);
+
+        assert_eq!(
+            classify_hoisted_import(&bundler, "__future__"),
+            HoistedImportType::Future
+        );
+        assert_eq!(
+            classify_hoisted_import(&bundler, "os"),
+            HoistedImportType::Stdlib
+        );
+        assert_eq!(
+            classify_hoisted_import(&bundler, "collections.abc"),
+            HoistedImportType::Stdlib
+        );
+        assert_eq!(
+            classify_hoisted_import(&bundler, "requests"),
+            HoistedImportType::ThirdParty
+        );
+        assert_eq!(
+            classify_hoisted_import(&bundler, "not_installed_anywhere"),
+            HoistedImportType::ThirdParty
+        );
+        assert_eq!(
+            classify_hoisted_import(&bundler, "mylib"),
+            HoistedImportType::FirstParty
+        );
+    }
+
+    #[test]
+    fn test_duplicate_import_from_by_hoisted_import_type() {
+        let resolver = resolver();
+        let mut bundler = Bundler::new(None, &resolver);
+        let mylib = resolver.register_module("mylib", Path::new("mylib.py"));
+        bundler.bundled_modules.insert(mylib);
+
+        // Future and stdlib imports are not kept as written, so they are never duplicates
+        assert!(!is_repeated_import_from(
+            &bundler,
+            "from __future__ import annotations"
+        ));
+        assert!(!is_repeated_import_from(&bundler, "from os import path"));
+
+        // Third-party imports and first-party modules that were not bundled stay as written
+        assert!(is_repeated_import_from(
+            &bundler,
+            "from requests import get"
+        ));
+        assert!(is_repeated_import_from(
+            &bundler,
+            "from vendored import helper"
+        ));
+
+        // A bundled first-party module is not imported at runtime
+        assert!(!is_repeated_import_from(
+            &bundler,
+            "from mylib import helper"
+        ));
+    }
+}
@@ -384,6 +384,10 @@ impl ExpressionRewriter {
                 Self::transform_expr(transformer, &mut if_expr.body);
                 Self::transform_expr(transformer, &mut if_expr.orelse);
             }
+            Expr::Named(named_expr) => {
+                // The target binds a new local name; only the value can refer to modules
+                Self::transform_expr(transformer, &mut named_expr.value);
+            }
             Expr::List(list_expr) => {
                 for elem in &mut list_expr.elts {
                     Self::transform_expr(transformer, elem);
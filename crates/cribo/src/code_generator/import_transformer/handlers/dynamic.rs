@@ -1,15 +1,277 @@
-use ruff_python_ast::{AtomicNodeIndex, Expr, ExprAttribute, ExprCall, ExprContext, ExprName};
+use std::path::{Path, PathBuf};
+
+use ruff_python_ast::{
+    AtomicNodeIndex, ExceptHandler, Expr, ExprAttribute, ExprCall, ExprContext, ExprName,
+    ModModule, Stmt,
+    visitor::{Visitor, walk_expr, walk_stmt},
+};
+use ruff_text_size::{Ranged, TextRange};
 
 use crate::{
+    ast_builder::{expressions, statements},
     code_generator::bundler::Bundler,
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
+    util::module_name_from_relative,
 };
 
 /// Handle dynamic import transformations (`importlib.import_module`)
 pub(crate) struct DynamicHandler;
 
 impl DynamicHandler {
+    /// Find dynamic imports whose target cannot be determined at bundle time
+    ///
+    /// These are `importlib.import_module(...)` and `__import__(...)` calls whose module name
+    /// argument is not a string literal. Returns the ranges of the offending calls.
+    pub(crate) fn find_unresolvable_dynamic_imports(module: &ModModule) -> Vec<TextRange> {
+        let mut finder = UnresolvableDynamicImportFinder::default();
+        finder.visit_body(&module.body);
+        finder.found
+    }
+
+    /// Rewrite modules loaded from a literal file path into `importlib.import_module` calls
+    ///
+    /// Matches the three-statement sequence
+    /// `spec = importlib.util.spec_from_file_location("name", "path")`,
+    /// `mod = importlib.util.module_from_spec(spec)`, `spec.loader.exec_module(mod)`. When the
+    /// path (relative paths are taken from the working directory, as Python does) is a `.py` file
+    /// inside one of `search_dirs`, the sequence becomes
+    /// `mod = importlib.import_module("<module name>")`, which the static importlib handling
+    /// then discovers and bundles. Returns the number of rewritten loads.
+    ///
+    /// The rewrite drops the `spec` binding and names the module after its location, so it is
+    /// only done when nothing else in the body reads `spec` and the name passed to
+    /// `spec_from_file_location` is already the module's name.
+    pub(crate) fn rewrite_spec_file_loads(
+        module: &mut ModModule,
+        search_dirs: &[PathBuf],
+    ) -> usize {
+        Self::rewrite_spec_file_loads_in_body(&mut module.body, search_dirs)
+    }
+
+    fn rewrite_spec_file_loads_in_body(body: &mut Vec<Stmt>, search_dirs: &[PathBuf]) -> usize {
+        let mut rewritten = 0;
+        let mut index = 0;
+        while index < body.len() {
+            if let Some((_, module_var, module_name)) =
+                Self::match_spec_file_load(&body[index..], search_dirs)
+                    .filter(|(spec_var, _, _)| !Self::reads_spec(body, index, spec_var))
+            {
+                log::debug!(
+                    "Rewriting file-based spec load of '{module_name}' into \
+                     importlib.import_module"
+                );
+                let import_call = expressions::call(
+                    expressions::dotted_name(&["importlib", "import_module"], ExprContext::Load),
+                    vec![expressions::string_literal(&module_name)],
+                    vec![],
+                );
+                body.splice(
+                    index..index + 3,
+                    [statements::simple_assign(&module_var, import_call)],
+                );
+                rewritten += 1;
+            } else {
+                rewritten += Self::rewrite_spec_file_loads_in_nested(&mut body[index], search_dirs);
+            }
+            index += 1;
+        }
+        rewritten
+    }
+
+    fn rewrite_spec_file_loads_in_nested(stmt: &mut Stmt, search_dirs: &[PathBuf]) -> usize {
+        let rewrite =
+            |body: &mut Vec<Stmt>| Self::rewrite_spec_file_loads_in_body(body, search_dirs);
+        match stmt {
+            Stmt::FunctionDef(function_def) => rewrite(&mut function_def.body),
+            Stmt::ClassDef(class_def) => rewrite(&mut class_def.body),
+            Stmt::If(if_stmt) => {
+                rewrite(&mut if_stmt.body)
+                    + if_stmt
+                        .elif_else_clauses
+                        .iter_mut()
+                        .map(|clause| rewrite(&mut clause.body))
+                        .sum::<usize>()
+            }
+            Stmt::Try(try_stmt) => {
+                let handlers: usize = try_stmt
+                    .handlers
+                    .iter_mut()
+                    .map(|handler| match handler {
+                        ExceptHandler::ExceptHandler(handler) => rewrite(&mut handler.body),
+                    })
+                    .sum();
+                rewrite(&mut try_stmt.body)
+                    + handlers
+                    + rewrite(&mut try_stmt.orelse)
+                    + rewrite(&mut try_stmt.finalbody)
+            }
+            Stmt::With(with_stmt) => rewrite(&mut with_stmt.body),
+            Stmt::For(for_stmt) => rewrite(&mut for_stmt.body) + rewrite(&mut for_stmt.orelse),
+            Stmt::While(while_stmt) => {
+                rewrite(&mut while_stmt.body) + rewrite(&mut while_stmt.orelse)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Whether a statement of `body` other than the spec load starting at `index` reads
+    /// `spec_var`
+    fn reads_spec(body: &[Stmt], index: usize, spec_var: &str) -> bool {
+        let mut finder = NameReadFinder {
+            name: spec_var,
+            found: false,
+        };
+        finder.visit_body(&body[..index]);
+        finder.visit_body(&body[index + 3..]);
+        finder.found
+    }
+
+    /// Match a spec file load at the start of `stmts`, returning the spec variable, the module
+    /// variable and the name of the first-party module the file maps to
+    fn match_spec_file_load(
+        stmts: &[Stmt],
+        search_dirs: &[PathBuf],
+    ) -> Option<(String, String, String)> {
+        let [spec_stmt, module_stmt, exec_stmt, ..] = stmts else {
+            return None;
+        };
+
+        let (spec_var, spec_call) = Self::single_name_call_assignment(spec_stmt)?;
+        if !Self::is_importlib_util_call(spec_call, "spec_from_file_location") {
+            return None;
+        }
+        let Expr::StringLiteral(location) = spec_call.arguments.args.get(1).or_else(|| {
+            spec_call
+                .arguments
+                .find_keyword("location")
+                .map(|keyword| &keyword.value)
+        })?
+        else {
+            return None;
+        };
+
+        let (module_var, module_call) = Self::single_name_call_assignment(module_stmt)?;
+        if !Self::is_importlib_util_call(module_call, "module_from_spec")
+            || !matches!(
+                &*module_call.arguments.args,
+                [Expr::Name(name)] if name.id.as_str() == spec_var
+            )
+        {
+            return None;
+        }
+
+        // spec.loader.exec_module(mod)
+        let Stmt::Expr(exec_expr) = exec_stmt else {
+            return None;
+        };
+        let Expr::Call(exec_call) = exec_expr.value.as_ref() else {
+            return None;
+        };
+        let is_exec_module = matches!(
+            exec_call.func.as_ref(),
+            Expr::Attribute(exec_attr) if exec_attr.attr.as_str() == "exec_module"
+                && matches!(
+                    exec_attr.value.as_ref(),
+                    Expr::Attribute(loader) if loader.attr.as_str() == "loader"
+                        && matches!(
+                            loader.value.as_ref(),
+                            Expr::Name(name) if name.id.as_str() == spec_var
+                        )
+                )
+        );
+        if !is_exec_module
+            || !matches!(
+                &*exec_call.arguments.args,
+                [Expr::Name(name)] if name.id.as_str() == module_var
+            )
+        {
+            return None;
+        }
+
+        let module_name =
+            Self::first_party_module_name(Path::new(location.value.to_str()), search_dirs)?;
+        // The loaded module's `__name__` is the name given to the spec
+        let Expr::StringLiteral(name) = spec_call.arguments.args.first().or_else(|| {
+            spec_call
+                .arguments
+                .find_keyword("name")
+                .map(|keyword| &keyword.value)
+        })?
+        else {
+            return None;
+        };
+        if name.value.to_str() != module_name {
+            log::debug!(
+                "Not rewriting spec load of '{module_name}': it is loaded as '{}'",
+                name.value
+            );
+            return None;
+        }
+        Some((spec_var.to_owned(), module_var.to_owned(), module_name))
+    }
+
+    /// `name = <call>` with a single name target
+    fn single_name_call_assignment(stmt: &Stmt) -> Option<(&str, &ExprCall)> {
+        let Stmt::Assign(assign) = stmt else {
+            return None;
+        };
+        let ([Expr::Name(target)], Expr::Call(call)) = (&*assign.targets, assign.value.as_ref())
+        else {
+            return None;
+        };
+        Some((target.id.as_str(), call))
+    }
+
+    /// Check if this is an `importlib.util.<function>()` call
+    fn is_importlib_util_call(call: &ExprCall, function: &str) -> bool {
+        let Expr::Attribute(attr) = call.func.as_ref() else {
+            return false;
+        };
+        attr.attr.as_str() == function
+            && matches!(
+                attr.value.as_ref(),
+                Expr::Attribute(util) if util.attr.as_str() == "util"
+                    && matches!(util.value.as_ref(), Expr::Name(name) if name.id.as_str() == "importlib")
+            )
+    }
+
+    /// Module name of a `.py` file inside one of the (canonical) search directories
+    fn first_party_module_name(path: &Path, search_dirs: &[PathBuf]) -> Option<String> {
+        if path
+            .extension()
+            .is_none_or(|extension| !extension.eq_ignore_ascii_case("py"))
+        {
+            return None;
+        }
+        let path = path.canonicalize().ok()?;
+        search_dirs
+            .iter()
+            .find_map(|dir| module_name_from_relative(path.strip_prefix(dir).ok()?))
+    }
+
+    /// Check if a call imports a module whose name is only known at runtime
+    fn is_unresolvable_dynamic_import(
+        call: &ExprCall,
+        import_aliases: &FxIndexMap<String, String>,
+    ) -> bool {
+        let is_dunder_import =
+            matches!(call.func.as_ref(), Expr::Name(name) if name.id.as_str() == "__import__");
+        if !is_dunder_import && !Self::is_importlib_import_module_call(call, import_aliases) {
+            return false;
+        }
+
+        call.arguments
+            .args
+            .first()
+            .or_else(|| {
+                call.arguments
+                    .find_keyword("name")
+                    .map(|keyword| &keyword.value)
+            })
+            .is_some_and(|arg| !arg.is_string_literal_expr())
+    }
+
     /// Check if this is an `importlib.import_module()` call
     pub(in crate::code_generator::import_transformer) fn is_importlib_import_module_call(
         call: &ExprCall,
@@ -40,61 +302,34 @@ impl DynamicHandler {
     }
 
     /// Resolve `importlib.import_module()` target module name, handling relative imports
+    ///
+    /// A relative name is resolved against the `package` argument, given positionally or as
+    /// `package=...`, like a `from .name import ...` inside that package.
     fn resolve_importlib_target(call: &ExprCall, bundler: &Bundler<'_>) -> Option<String> {
-        if let Some(arg) = call.arguments.args.first()
-            && let Expr::StringLiteral(lit) = arg
-        {
-            let module_name = lit.value.to_str();
-
-            // Handle relative imports with package context
-            let resolved_name = if module_name.starts_with('.') && call.arguments.args.len() >= 2 {
-                // Get the package context from the second argument
-                if let Expr::StringLiteral(package_lit) = &call.arguments.args[1] {
-                    let package = package_lit.value.to_str();
-
-                    // Resolve package to path, then use resolver
-                    if let Ok(Some(package_path)) = bundler.resolver.resolve_module_path(package) {
-                        let level = module_name.chars().take_while(|&c| c == '.').count() as u32;
-                        let name_part = module_name.trim_start_matches('.');
-
-                        bundler
-                            .resolver
-                            .resolve_relative_to_absolute_module_name(
-                                level,
-                                if name_part.is_empty() {
-                                    None
-                                } else {
-                                    Some(name_part)
-                                },
-                                &package_path,
-                            )
-                            .unwrap_or_else(|| module_name.to_owned())
-                    } else {
-                        // Use resolver's method for package name resolution when path not found
-                        let level = module_name.chars().take_while(|&c| c == '.').count() as u32;
-                        let name_part = module_name.trim_start_matches('.');
-
-                        bundler.resolver.resolve_relative_import_from_package_name(
-                            level,
-                            if name_part.is_empty() {
-                                None
-                            } else {
-                                Some(name_part)
-                            },
-                            package,
-                        )
-                    }
-                } else {
-                    module_name.to_owned()
-                }
+        let (module_name, package) = crate::visitors::utils::import_module_literal_arguments(call);
+        let module_name = module_name?;
+
+        // Handle relative imports with package context
+        let Some(package) = package.filter(|_| module_name.starts_with('.')) else {
+            return Some(module_name.to_owned());
+        };
+        let level = module_name.chars().take_while(|&c| c == '.').count() as u32;
+        let name_part = Some(module_name.trim_start_matches('.')).filter(|name| !name.is_empty());
+
+        // Resolve package to path, then use resolver
+        let resolved_name =
+            if let Ok(Some(package_path)) = bundler.resolver.resolve_module_path(package) {
+                bundler
+                    .resolver
+                    .resolve_relative_to_absolute_module_name(level, name_part, &package_path)
+                    .unwrap_or_else(|| module_name.to_owned())
             } else {
-                module_name.to_owned()
+                // Use resolver's method for package name resolution when path not found
+                bundler
+                    .resolver
+                    .resolve_relative_import_from_package_name(level, name_part, package)
             };
-
-            Some(resolved_name)
-        } else {
-            None
-        }
+        Some(resolved_name)
     }
 
     /// Transform importlib.import_module("module-name") to direct module reference
@@ -195,3 +430,196 @@ impl DynamicHandler {
         }
     }
 }
+
+/// Visitor collecting dynamic imports that cannot be resolved statically
+#[derive(Default)]
+struct UnresolvableDynamicImportFinder {
+    /// Local aliases of `importlib` and `importlib.import_module`
+    import_aliases: FxIndexMap<String, String>,
+    found: Vec<TextRange>,
+}
+
+impl<'a> Visitor<'a> for UnresolvableDynamicImportFinder {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Import(import) => {
+                for alias in &import.names {
+                    if alias.name.as_str() == "importlib"
+                        && let Some(asname) = &alias.asname
+                    {
+                        self.import_aliases
+                            .insert(asname.to_string(), "importlib".to_owned());
+                    }
+                }
+            }
+            Stmt::ImportFrom(import_from)
+                if import_from.level == 0
+                    && import_from
+                        .module
+                        .as_ref()
+                        .is_some_and(|module| module.as_str() == "importlib") =>
+            {
+                for alias in &import_from.names {
+                    if alias.name.as_str() == "import_module" {
+                        let local_name = alias.asname.as_ref().unwrap_or(&alias.name);
+                        self.import_aliases
+                            .insert(local_name.to_string(), "importlib.import_module".to_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr
+            && DynamicHandler::is_unresolvable_dynamic_import(call, &self.import_aliases)
+        {
+            self.found.push(call.range());
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Visitor checking whether a name is read
+struct NameReadFinder<'a> {
+    name: &'a str,
+    found: bool,
+}
+
+impl<'a> Visitor<'a> for NameReadFinder<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if self.found {
+            return;
+        }
+        if let Expr::Name(name) = expr
+            && name.ctx == ExprContext::Load
+            && name.id.as_str() == self.name
+        {
+            self.found = true;
+            return;
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(source: &str) -> Vec<TextRange> {
+        let module = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        DynamicHandler::find_unresolvable_dynamic_imports(&module)
+    }
+
+    #[test]
+    fn test_find_unresolvable_dynamic_imports() {
+        let source = r#"
+import importlib
+import importlib as il
+from importlib import import_module as im
+
+ok = importlib.import_module("json")
+also_ok = __import__("os")
+
+def load(name):
+    a = importlib.import_module(name)
+    b = il.import_module(f"plugins.{name}")
+    c = im(name)
+    d = __import__(name=name)
+    return a, b, c, d
+"#;
+        let found = find(source);
+        let calls: Vec<&str> = found.iter().map(|range| &source[*range]).collect();
+        assert_eq!(
+            calls,
+            [
+                "importlib.import_module(name)",
+                "il.import_module(f\"plugins.{name}\")",
+                "im(name)",
+                "__import__(name=name)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_spec_file_loads_inside_search_dirs() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = dir.path().canonicalize().expect("Temp dir should exist");
+        std::fs::create_dir(root.join("plugins")).expect("Failed to create package dir");
+        std::fs::write(root.join("plugins").join("extra.py"), "VALUE = 1\n")
+            .expect("Failed to write module");
+        let plugin_path = root.join("plugins").join("extra.py").display().to_string();
+
+        let source = format!(
+            r#"
+import importlib.util
+
+spec = importlib.util.spec_from_file_location("plugins.extra", {plugin_path:?})
+extra = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(extra)
+
+def load_outside():
+    s = importlib.util.spec_from_file_location("other", "/elsewhere/other.py")
+    m = importlib.util.module_from_spec(s)
+    s.loader.exec_module(m)
+    return m
+
+def load_renamed():
+    s = importlib.util.spec_from_file_location("extra", {plugin_path:?})
+    m = importlib.util.module_from_spec(s)
+    s.loader.exec_module(m)
+    return m
+
+def load_with_spec():
+    s = importlib.util.spec_from_file_location("plugins.extra", {plugin_path:?})
+    m = importlib.util.module_from_spec(s)
+    s.loader.exec_module(m)
+    return s.name, m
+"#
+        );
+        let mut module = ruff_python_parser::parse_module(&source)
+            .expect("test module should parse")
+            .into_syntax();
+
+        let rewritten = DynamicHandler::rewrite_spec_file_loads(&mut module, &[root]);
+
+        assert_eq!(rewritten, 1);
+        assert_eq!(module.body.len(), 5);
+        let Stmt::Assign(assign) = &module.body[1] else {
+            panic!("expected rewritten assignment");
+        };
+        let Expr::Call(call) = assign.value.as_ref() else {
+            panic!("expected import_module call");
+        };
+        assert!(DynamicHandler::is_importlib_import_module_call(
+            call,
+            &FxIndexMap::default()
+        ));
+        assert!(matches!(
+            &*call.arguments.args,
+            [Expr::StringLiteral(name)] if name.value.to_str() == "plugins.extra"
+        ));
+        // Loads from outside the project, under another name or whose spec is read afterwards
+        // are left alone
+        for stmt in &module.body[2..] {
+            let Stmt::FunctionDef(function_def) = stmt else {
+                panic!("expected function definition");
+            };
+            assert_eq!(
+                function_def.body.len(),
+                4,
+                "{} was rewritten",
+                function_def.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_unrelated_import_module_is_ignored() {
+        assert!(find("def import_module(x):\n    pass\n\nimport_module(value)\n").is_empty());
+    }
+}
@@ -63,12 +63,25 @@ impl InlinedHandler {
             }
         }
 
-        // Also check if module has module-level variables that weren't renamed
-        if let Some(module_id) = bundler.get_module_id(module_name)
-            && let Some(exports) = bundler.module_exports.get(&module_id)
-            && let Some(export_list) = exports
-        {
-            for export in export_list {
+        // Also add the exports that weren't renamed: `__all__` when the module defines it,
+        // otherwise its semantic exports (symbols without a rename have no rename map entry)
+        if let Some(module_id) = bundler.get_module_id(module_name) {
+            let exports: Vec<&String> = match bundler.module_exports.get(&module_id) {
+                Some(Some(export_list)) => export_list.iter().collect(),
+                _ => bundler
+                    .semantic_exports
+                    .get(&module_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|export| {
+                        // Submodules are attached to the namespace separately
+                        bundler
+                            .get_module_id(&format!("{module_name}.{export}"))
+                            .is_none_or(|id| !bundler.bundled_modules.contains(&id))
+                    })
+                    .collect(),
+            };
+            for export in exports {
                 // Check if this export was already added as a renamed symbol
                 let was_renamed =
                     module_renames.is_some_and(|renames| renames.contains_key(export));
@@ -92,6 +105,8 @@ impl InlinedHandler {
             }
         }
 
+        // The namespace replaces an expression (e.g. `importlib.import_module(...)`), so there is
+        // no statement to attach post-assignments to: every export is passed to the constructor
         // Create types.SimpleNamespace(**kwargs) call
         expressions::call(expressions::simple_namespace_ctor(), vec![], keywords)
     }
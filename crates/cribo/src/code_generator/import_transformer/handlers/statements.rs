@@ -1,9 +1,9 @@
 use ruff_python_ast::{
-    ExceptHandler, StmtAnnAssign, StmtAssert, StmtAugAssign, StmtClassDef, StmtExpr, StmtFor,
-    StmtIf, StmtRaise, StmtReturn, StmtTry, StmtWhile, StmtWith,
+    ExceptHandler, Expr, Stmt, StmtAnnAssign, StmtAssert, StmtAugAssign, StmtClassDef, StmtExpr,
+    StmtFor, StmtIf, StmtRaise, StmtReturn, StmtTry, StmtWhile, StmtWith,
 };
 
-use crate::code_generator::import_transformer::RecursiveImportTransformer;
+use crate::{code_generator::import_transformer::RecursiveImportTransformer, types::FxIndexSet};
 
 pub(crate) struct StatementsHandler;
 
@@ -205,8 +205,45 @@ impl StatementsHandler {
         // Transform base classes
         t.transform_class_bases(s);
 
-        // Transform class body
+        // Transform class body. A property's getter, setter and deleter form a single unit:
+        // `@name.setter` must keep pointing at the class-local property even when a module-level
+        // symbol with the same name is rewritten
+        let saved_properties = std::mem::replace(
+            &mut t.state.class_properties,
+            Self::collect_class_properties(s),
+        );
         t.transform_statements(&mut s.body);
+        t.state.class_properties = saved_properties;
+    }
+
+    /// Names of the properties defined directly in a class body
+    fn collect_class_properties(class_def: &StmtClassDef) -> FxIndexSet<String> {
+        let mut properties = FxIndexSet::default();
+        for stmt in &class_def.body {
+            let Stmt::FunctionDef(func_def) = stmt else {
+                continue;
+            };
+            let is_property = func_def.decorator_list.iter().any(|decorator| {
+                matches!(&decorator.expression, Expr::Name(name) if name.id.as_str() == "property")
+                    || Self::property_accessor_base(&decorator.expression)
+                        .is_some_and(|base| properties.contains(base))
+            });
+            if is_property {
+                properties.insert(func_def.name.to_string());
+            }
+        }
+        properties
+    }
+
+    /// Return `name` for a `name.setter`, `name.getter` or `name.deleter` decorator
+    fn property_accessor_base(expr: &Expr) -> Option<&str> {
+        let Expr::Attribute(attr) = expr else {
+            return None;
+        };
+        let Expr::Name(base) = attr.value.as_ref() else {
+            return None;
+        };
+        matches!(attr.attr.as_str(), "setter" | "getter" | "deleter").then(|| base.id.as_str())
     }
 
     pub(in crate::code_generator::import_transformer) fn handle_function_def(
@@ -218,8 +255,13 @@ impl StatementsHandler {
             s.name.as_str()
         );
 
-        // Transform decorators
+        // Transform decorators, leaving accessors of class-local properties untouched
         for decorator in &mut s.decorator_list {
+            if Self::property_accessor_base(&decorator.expression)
+                .is_some_and(|base| t.state.class_properties.contains(base))
+            {
+                continue;
+            }
             t.transform_expr(&mut decorator.expression);
         }
 
@@ -334,6 +376,9 @@ impl StatementsHandler {
         let saved_at_module_level = t.state.at_module_level;
         t.state.at_module_level = false;
 
+        // Class-local properties are not visible from inside the function body
+        let saved_properties = std::mem::take(&mut t.state.class_properties);
+
         // Save current function context and compute symbol analysis once
         let saved_function_body = t.state.current_function_body.take();
         let saved_used_symbols = t.state.current_function_used_symbols.take();
@@ -358,6 +403,7 @@ impl StatementsHandler {
 
         // Restore the previous scope level
         t.state.at_module_level = saved_at_module_level;
+        t.state.class_properties = saved_properties;
 
         // Restore the previous function context
         t.state.current_function_body = saved_function_body;
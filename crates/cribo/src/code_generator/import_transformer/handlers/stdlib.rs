@@ -12,11 +12,16 @@ pub(crate) struct StdlibHandler;
 impl StdlibHandler {
     /// Check if this is a stdlib import that should be normalized
     pub(in crate::code_generator::import_transformer) fn should_normalize_stdlib_import(
+        bundler: &Bundler<'_>,
         module_name: &str,
         python_version: u8,
     ) -> bool {
-        // Recognize full stdlib module paths and submodules for the current Python version
+        // Recognize full stdlib module paths and submodules for the current Python version,
+        // except modules embedded in the bundle with `--embed-stdlib`
         crate::resolver::is_stdlib_module(module_name, python_version)
+            && !bundler
+                .get_module_id(module_name)
+                .is_some_and(|id| bundler.bundled_modules.contains(&id))
     }
 
     /// Build a mapping of stdlib imports to their rewritten paths
@@ -37,6 +42,7 @@ impl StdlibHandler {
 
     /// Handle stdlib from imports, transforming them to use _cribo proxy
     pub(in crate::code_generator::import_transformer) fn handle_stdlib_from_import(
+        bundler: &Bundler<'_>,
         import_from: &StmtImportFrom,
         module_str: &str,
         python_version: u8,
@@ -44,7 +50,7 @@ impl StdlibHandler {
         import_aliases: &mut FxIndexMap<String, String>,
     ) -> Option<Vec<Stmt>> {
         if import_from.level != 0
-            || !Self::should_normalize_stdlib_import(module_str, python_version)
+            || !Self::should_normalize_stdlib_import(bundler, module_str, python_version)
         {
             return None;
         }
@@ -67,6 +67,7 @@ impl SubmoduleHandler {
 
                 // For wrapper modules importing wrapper submodules from the same package
                 if transformer.state.is_wrapper_init {
+                    let mut reexport_stmts = Vec::new();
                     // Initialize the wrapper submodule if needed
                     // Pass the current module context to avoid recursive initialization
                     if let Some(module_id) =
@@ -82,7 +83,7 @@ impl SubmoduleHandler {
                                     format!("module#{}", transformer.state.module_id)
                                 }),
                         );
-                        result_stmts.extend(
+                        reexport_stmts.extend(
                             transformer
                                 .state
                                 .bundler
@@ -98,7 +99,27 @@ impl SubmoduleHandler {
                     let module_expr =
                         expressions::module_reference(&full_module_path, ExprContext::Load);
 
-                    result_stmts.push(statements::simple_assign(local_name, module_expr));
+                    reexport_stmts.push(statements::simple_assign(local_name, module_expr));
+
+                    // A submodule importing back from this module must not run before this
+                    // module's own definitions
+                    if transformer
+                        .state
+                        .bundler
+                        .get_module_id(&full_module_path)
+                        .is_some_and(|id| transformer.defers_submodule_reexport(id, local_name))
+                    {
+                        log::debug!(
+                            "  Deferring '{full_module_path}' to the end of the init function: it \
+                             imports from its parent"
+                        );
+                        transformer
+                            .state
+                            .deferred_submodule_reexports
+                            .append(&mut reexport_stmts);
+                    } else {
+                        result_stmts.append(&mut reexport_stmts);
+                    }
 
                     // Track as local to avoid any accidental rewrites later in this transform
                     // pass
@@ -267,15 +288,11 @@ impl SubmoduleHandler {
                             );
                             transformer.state.created_namespace_objects = true;
 
-                            // If this is a submodule being imported (from . import compat),
-                            // and the parent module is also being used as a namespace
-                            // externally, we need to create the
-                            // parent.child assignment
-                            transformer.maybe_log_parent_child_assignment(
-                                Some(resolved_base),
-                                imported_name,
-                                local_name,
-                            );
+                            // Note: no `parent.child = child` assignment is emitted here. This
+                            // branch only runs for an imported module that is inlined, and the
+                            // parent's namespace population assigns inlined and wrapper
+                            // children itself, so a parent-child assignment for this import
+                            // could never be reached
 
                             // Mark namespace populated if needed (keep deferred behavior)
                             transformer.mark_namespace_populated_if_needed(&full_module_path);
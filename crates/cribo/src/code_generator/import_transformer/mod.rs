@@ -116,24 +116,33 @@ impl<'a> RecursiveImportTransformer<'a> {
     }
 
     /// Should emit __all__ for a local namespace binding
+    ///
+    /// `modules_with_accessed_all` records the name of the accessed module rather than the
+    /// local alias, so `import mymod as m; m.__all__` is matched through `module_id`.
     fn should_emit_all_for_local(
         &self,
         module_id: crate::resolver::ModuleId,
-        local_name: &str,
         filtered_exports: &[String],
     ) -> bool {
-        !filtered_exports.is_empty()
-            && self
+        if filtered_exports.is_empty()
+            || !self
                 .state
                 .bundler
                 .modules_with_explicit_all
                 .contains(&module_id)
-            && self
-                .state
-                .bundler
-                .modules_with_accessed_all
-                .iter()
-                .any(|(module, alias)| module == &self.state.module_id && alias == local_name)
+        {
+            return false;
+        }
+        let Some(module_name) = self.state.bundler.resolver.get_module_name(module_id) else {
+            return false;
+        };
+        self.state
+            .bundler
+            .modules_with_accessed_all
+            .iter()
+            .any(|(accessing, accessed)| {
+                *accessing == self.state.module_id && *accessed == module_name
+            })
     }
 
     /// Mark namespace as populated for a module path if needed (non-bundled, not yet marked)
@@ -161,7 +170,7 @@ impl<'a> RecursiveImportTransformer<'a> {
         if let Some((module_id, filtered_exports)) =
             self.get_filtered_exports_for_path(full_module_path)
         {
-            if self.should_emit_all_for_local(module_id, local_name, &filtered_exports) {
+            if self.should_emit_all_for_local(module_id, &filtered_exports) {
                 let export_strings: Vec<&str> =
                     filtered_exports.iter().map(String::as_str).collect();
                 result_stmts.push(statements::set_list_attribute(
@@ -261,6 +270,10 @@ impl<'a> RecursiveImportTransformer<'a> {
                 );
                 return None;
             }
+            // Collapsing to the bare name is safe for every kind of export, including instances
+            // of descriptor classes: attribute lookup on a module returns values from its
+            // `__dict__` as-is and never invokes `__get__`, so `module.CALLABLE` and `CALLABLE`
+            // are the same object
             log::debug!("Rewrote {base}.{attr_name} to {attr_name} (not renamed)");
             return Some(Expr::Name(ExprName {
                 node_index: AtomicNodeIndex::NONE,
@@ -299,117 +312,46 @@ impl<'a> RecursiveImportTransformer<'a> {
         None
     }
 
-    /// Handle parent.child alias when importing from the same parent module, with early exits
-    fn maybe_log_parent_child_assignment(
+    /// Whether binding wrapper submodule `submodule_id` as `local_name` in its parent's init
+    /// function should wait until the parent's body has run
+    ///
+    /// A submodule whose init imports back from the parent (`from pkg import shared` in
+    /// `pkg/sub/__init__.py`) reads the parent's attributes while it initializes, so it runs
+    /// after them. Only done when no module-level statement of the parent reads `local_name`.
+    fn defers_submodule_reexport(
         &self,
-        import_base: Option<&str>,
-        imported_name: &str,
+        submodule_id: crate::resolver::ModuleId,
         local_name: &str,
-    ) {
-        if import_base
-            != Some(
-                self.state
-                    .bundler
-                    .resolver
-                    .get_module_name(self.state.module_id)
-                    .unwrap_or_else(|| format!("module#{}", self.state.module_id))
-                    .as_str(),
-            )
-        {
-            return;
-        }
-
-        // Check if this submodule is in the parent's __all__ exports
-        let parent_exports = self
-            .state
-            .bundler
-            .module_exports
-            .get(&self.state.module_id)
-            .and_then(|opt| opt.as_ref())
-            .is_some_and(|exports| exports.contains(&imported_name.to_owned()));
-        if !parent_exports {
-            return;
-        }
-
-        let full_submodule_path = format!(
-            "{}.{}",
-            self.state
-                .bundler
-                .resolver
-                .get_module_name(self.state.module_id)
-                .unwrap_or_else(|| format!("module#{}", self.state.module_id)),
-            imported_name
-        );
-        let is_inlined_submodule = self
-            .state
-            .bundler
-            .get_module_id(&full_submodule_path)
-            .is_some_and(|id| self.state.bundler.inlined_modules.contains(&id));
-        let uses_init_function = self
-            .state
-            .bundler
-            .get_module_id(&full_submodule_path)
-            .and_then(|id| self.state.bundler.module_init_functions.get(&id))
-            .is_some();
-
-        log::debug!(
-            "  Checking submodule status for {full_submodule_path}: \
-             is_inlined={is_inlined_submodule}, uses_init={uses_init_function}"
-        );
-
-        if is_inlined_submodule || uses_init_function {
-            log::debug!(
-                "  Skipping parent module assignment for {}.{} - already handled by init function",
-                self.state
-                    .bundler
-                    .resolver
-                    .get_module_name(self.state.module_id)
-                    .unwrap_or_else(|| format!("module#{}", self.state.module_id)),
-                local_name
-            );
-            return;
-        }
-
-        // Double-check if this is actually a module
-        let is_actually_a_module = self
+    ) -> bool {
+        let Some(graph) = self.state.bundler.graph else {
+            return false;
+        };
+        let parent_name = self.state.get_module_name();
+        let is_direct_child = self
             .state
             .bundler
-            .get_module_id(&full_submodule_path)
-            .is_some_and(|id| {
-                self.state.bundler.bundled_modules.contains(&id)
-                    || self
-                        .state
-                        .bundler
-                        .module_info_registry
-                        .as_ref()
-                        .is_some_and(|reg| reg.contains_module(id))
-                    || self.state.bundler.inlined_modules.contains(&id)
+            .resolver
+            .get_module_name(submodule_id)
+            .is_some_and(|name| {
+                name.rsplit_once('.')
+                    .is_some_and(|(parent, _)| parent == parent_name)
             });
-        if is_actually_a_module {
-            log::debug!(
-                "Skipping assignment for {}.{} - it's a module, not a symbol",
-                self.state
-                    .bundler
-                    .resolver
-                    .get_module_name(self.state.module_id)
-                    .unwrap_or_else(|| format!("module#{}", self.state.module_id)),
-                local_name
-            );
-            return;
+        if !self.state.at_module_level
+            || !is_direct_child
+            || !graph
+                .get_dependencies(submodule_id)
+                .contains(&self.state.module_id)
+        {
+            return false;
         }
-
-        // At this point, we would create parent.local = local if needed.
-        // Original code only logged due to deferred imports removal.
-        log::debug!(
-            "Creating parent module assignment: {}.{} = {} (symbol exported from parent)",
-            self.state
-                .bundler
-                .resolver
-                .get_module_name(self.state.module_id)
-                .unwrap_or_else(|| format!("module#{}", self.state.module_id)),
-            local_name,
-            local_name
-        );
+        graph
+            .get_module(self.state.module_id)
+            .is_some_and(|parent| {
+                !parent
+                    .items
+                    .values()
+                    .any(|item| item.read_vars.contains(local_name))
+            })
     }
 
     /// If accessing attribute on an inlined submodule, rewrite to direct access symbol name
@@ -497,6 +439,9 @@ impl<'a> RecursiveImportTransformer<'a> {
         );
         // Transform all statements recursively
         self.transform_statements(&mut module.body);
+        module
+            .body
+            .append(&mut self.state.deferred_submodule_reexports);
     }
 
     /// Transform a list of statements recursively
@@ -611,7 +556,10 @@ impl<'a> RecursiveImportTransformer<'a> {
         }
     }
 
-    /// Transform a class definition's base classes
+    /// Transform a class definition's base classes and keyword arguments
+    ///
+    /// Keyword arguments (`metaclass=Meta`, or `__init_subclass__` kwargs such as
+    /// `registry=Registry`) may reference bundled symbols just like the bases do.
     fn transform_class_bases(&mut self, class_def: &mut StmtClassDef) {
         let Some(ref mut arguments) = class_def.arguments else {
             return;
@@ -620,6 +568,9 @@ impl<'a> RecursiveImportTransformer<'a> {
         for base in &mut arguments.args {
             self.transform_expr(base);
         }
+        for keyword in &mut arguments.keywords {
+            self.transform_expr(&mut keyword.value);
+        }
     }
 
     /// Track aliases for from-import statements
@@ -715,6 +666,7 @@ impl<'a> RecursiveImportTransformer<'a> {
 
                         // Normalize ALL stdlib imports, including those with aliases
                         if StdlibHandler::should_normalize_stdlib_import(
+                            self.state.bundler,
                             module_name,
                             self.state.python_version,
                         ) {
@@ -994,6 +946,7 @@ impl<'a> RecursiveImportTransformer<'a> {
         if let Some(module) = &import_from.module {
             let module_str = module.as_str();
             if let Some(result) = StdlibHandler::handle_stdlib_from_import(
+                self.state.bundler,
                 import_from,
                 module_str,
                 self.state.python_version,
@@ -1173,16 +1126,9 @@ fn emit_dotted_assignment_if_needed_for(
 
     // Avoid emitting duplicate parent.child assignments when the bundler has
     // already created the namespace chain for this module.
-    // The Bundler tracks created parent->child links using a sanitized parent
-    // variable for multi-level parents and the raw name for top-level parents.
-    let parent_key = if parent.contains('.') {
-        sanitize_module_name_for_identifier(parent)
-    } else {
-        parent.to_owned()
-    };
     if bundler
         .parent_child_assignments_made
-        .contains(&(parent_key, attr.to_owned()))
+        .contains(&Bundler::parent_child_assignment_key(parent, attr))
     {
         log::debug!(
             "Skipping duplicate dotted assignment: {parent}.{attr} (already created by bundler)"
@@ -1751,3 +1697,631 @@ fn rewrite_import_from(params: RewriteImportFromParams<'_>) -> Vec<Stmt> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        resolver::{ModuleId, ModuleResolver},
+    };
+
+    /// Transform `source` as a non-entry module where `imports` were imported from the
+    /// wrapper module `models`
+    fn transform_with_wrapper_imports(source: &str, imports: &[&str]) -> ModModule {
+        let resolver = ModuleResolver::new(Config::default());
+        let bundler = Bundler::new(None, &resolver);
+        let symbol_renames = FxIndexMap::default();
+        let mut transformer = RecursiveImportTransformer::new(&RecursiveImportTransformerParams {
+            bundler: &bundler,
+            module_id: ModuleId::new(1),
+            symbol_renames: &symbol_renames,
+            is_wrapper_init: false,
+            python_version: 10,
+        });
+        for name in imports {
+            transformer.state.wrapper_module_imports.insert(
+                (*name).to_owned(),
+                ("models".to_owned(), (*name).to_owned()),
+            );
+        }
+
+        let mut module = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        transformer.transform_module(&mut module);
+        module
+    }
+
+    /// Transform `source` as the module `app`, where `module` is inlined with `renames` and
+    /// `exports`, and imported as `alias` when given
+    fn transform_with_inlined_alias(
+        source: &str,
+        alias: Option<&str>,
+        module: &str,
+        renames: &[(&str, &str)],
+        exports: &[&str],
+    ) -> ModModule {
+        let resolver = ModuleResolver::new(Config::default());
+        resolver.register_module("main", std::path::Path::new("main.py"));
+        let inlined =
+            resolver.register_module(module, &std::path::PathBuf::from(format!("{module}.py")));
+        let app = resolver.register_module("app", std::path::Path::new("app.py"));
+        let mut bundler = Bundler::new(None, &resolver);
+        bundler.inlined_modules.insert(inlined);
+        if !exports.is_empty() {
+            bundler.semantic_exports.insert(
+                inlined,
+                exports.iter().map(|name| (*name).to_owned()).collect(),
+            );
+        }
+        let mut symbol_renames = FxIndexMap::default();
+        symbol_renames.insert(
+            inlined,
+            renames
+                .iter()
+                .map(|(name, renamed)| ((*name).to_owned(), (*renamed).to_owned()))
+                .collect(),
+        );
+        let mut transformer = RecursiveImportTransformer::new(&RecursiveImportTransformerParams {
+            bundler: &bundler,
+            module_id: app,
+            symbol_renames: &symbol_renames,
+            is_wrapper_init: false,
+            python_version: 10,
+        });
+        if let Some(alias) = alias {
+            transformer
+                .state
+                .import_aliases
+                .insert(alias.to_owned(), module.to_owned());
+        }
+
+        let mut module = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        transformer.transform_module(&mut module);
+        module
+    }
+
+    fn attribute_path(expr: &Expr) -> Option<(&str, &str)> {
+        let Expr::Attribute(attr) = expr else {
+            return None;
+        };
+        let Expr::Name(base) = &*attr.value else {
+            return None;
+        };
+        Some((base.id.as_str(), attr.attr.as_str()))
+    }
+
+    fn class_arguments(module: &ModModule) -> &ruff_python_ast::Arguments {
+        let Stmt::ClassDef(class_def) = &module.body[0] else {
+            panic!("expected a class definition");
+        };
+        class_def
+            .arguments
+            .as_deref()
+            .expect("class should have arguments")
+    }
+
+    #[test]
+    fn test_dotted_assignment_skips_links_created_by_namespace_chain() {
+        let resolver = ModuleResolver::new(Config::default());
+        let mut bundler = Bundler::new(None, &resolver);
+        let mut chain = Vec::new();
+        bundler.create_namespace_chain_for_module(
+            "services.auth.manager",
+            "services_auth_manager",
+            &mut chain,
+        );
+        bundler.create_namespace_chain_for_module("2fa.tokens", "_2fa_tokens", &mut chain);
+        for namespace in ["services_auth_manager", "_2fa_tokens"] {
+            bundler.created_namespaces.insert(namespace.to_owned());
+        }
+
+        // Every link of the chains is looked up under the key the bundler stored it with,
+        // including top-level parents that are not valid identifiers
+        let mut emitted = Vec::new();
+        for (parent, attr, full_path) in [
+            ("services", "auth", "services.auth"),
+            ("services.auth", "manager", "services.auth.manager"),
+            ("2fa", "tokens", "2fa.tokens"),
+        ] {
+            emit_dotted_assignment_if_needed_for(&bundler, parent, attr, full_path, &mut emitted);
+        }
+        assert!(emitted.is_empty());
+
+        emit_dotted_assignment_if_needed_for(
+            &bundler,
+            "services",
+            "manager",
+            "services.auth.manager",
+            &mut emitted,
+        );
+        assert_eq!(emitted.len(), 1);
+    }
+
+    #[test]
+    fn test_transform_class_bases_rewrites_metaclass() {
+        let module = transform_with_wrapper_imports(
+            "class Model(Base, metaclass=Meta):\n    pass\n",
+            &["Base", "Meta"],
+        );
+        let arguments = class_arguments(&module);
+
+        assert_eq!(attribute_path(&arguments.args[0]), Some(("models", "Base")));
+        assert_eq!(arguments.keywords[0].arg.as_deref(), Some("metaclass"));
+        assert_eq!(
+            attribute_path(&arguments.keywords[0].value),
+            Some(("models", "Meta"))
+        );
+    }
+
+    #[test]
+    fn test_transform_class_bases_rewrites_init_subclass_kwargs() {
+        let module = transform_with_wrapper_imports(
+            "class Plugin(Base, registry=Registry, slots=True, name=local):\n    pass\n",
+            &["Base", "Registry"],
+        );
+        let arguments = class_arguments(&module);
+
+        assert_eq!(
+            attribute_path(&arguments.keywords[0].value),
+            Some(("models", "Registry"))
+        );
+        // Literals and names not imported from bundled modules are left alone
+        assert!(arguments.keywords[1].value.is_boolean_literal_expr());
+        assert!(
+            matches!(&arguments.keywords[2].value, Expr::Name(name) if name.id.as_str() == "local")
+        );
+    }
+
+    #[test]
+    fn test_typing_cast_type_argument_rewritten_like_annotation() {
+        let module = transform_with_wrapper_imports(
+            "def load(raw) -> Model:\n    return cast(Model, raw)\n",
+            &["Model"],
+        );
+        let Stmt::FunctionDef(func_def) = &module.body[0] else {
+            panic!("expected a function definition");
+        };
+        let returns = func_def
+            .returns
+            .as_deref()
+            .expect("function should have a return type");
+        let Stmt::Return(ret) = &func_def.body[0] else {
+            panic!("expected a return statement");
+        };
+        let Some(Expr::Call(call)) = ret.value.as_deref() else {
+            panic!("expected a cast call");
+        };
+
+        // The type argument resolves to the same bundled symbol as the annotation
+        assert_eq!(attribute_path(returns), Some(("models", "Model")));
+        assert_eq!(
+            attribute_path(&call.arguments.args[0]),
+            Some(("models", "Model"))
+        );
+        assert!(matches!(&call.arguments.args[1], Expr::Name(name) if name.id.as_str() == "raw"));
+    }
+
+    #[test]
+    fn test_platform_guarded_stdlib_import_stays_conditional() {
+        let module = transform_with_wrapper_imports(
+            "import sys\nif sys.platform == 'win32':\n    import winreg\n    key = \
+             winreg.HKEY_CURRENT_USER\n",
+            &[],
+        );
+        let Some(Stmt::If(if_stmt)) = module.body.iter().find(|stmt| stmt.is_if_stmt()) else {
+            panic!("expected the platform check to remain");
+        };
+        // The proxy lookup imports winreg only when the branch runs
+        let Stmt::Assign(assign) = &if_stmt.body[0] else {
+            panic!("expected the import to become an assignment inside the branch");
+        };
+        assert!(matches!(&assign.targets[0], Expr::Name(name) if name.id.as_str() == "winreg"));
+        assert!(matches!(&*assign.value, Expr::Attribute(attr) if attr.attr.as_str() == "winreg"));
+        // Nothing is hoisted to module level
+        assert!(!module.body.iter().any(|stmt| {
+            matches!(stmt, Stmt::Assign(assign)
+                if matches!(&assign.targets[0], Expr::Name(name) if name.id.as_str() == "winreg"))
+        }));
+    }
+
+    #[test]
+    fn test_should_emit_all_for_aliased_module_import() {
+        // import mymod as m
+        // m.__all__
+        let resolver = ModuleResolver::new(Config::default());
+        let entry = resolver.register_module("main", std::path::Path::new("main.py"));
+        let mymod = resolver.register_module("mymod", std::path::Path::new("mymod.py"));
+        let mut bundler = Bundler::new(None, &resolver);
+        bundler.modules_with_explicit_all.insert(mymod);
+        bundler
+            .modules_with_accessed_all
+            .insert((entry, "mymod".to_owned()));
+
+        let symbol_renames = FxIndexMap::default();
+        let transformer = RecursiveImportTransformer::new(&RecursiveImportTransformerParams {
+            bundler: &bundler,
+            module_id: entry,
+            symbol_renames: &symbol_renames,
+            is_wrapper_init: false,
+            python_version: 10,
+        });
+
+        let exports = ["helper".to_owned()];
+        assert!(transformer.should_emit_all_for_local(mymod, &exports));
+        assert!(!transformer.should_emit_all_for_local(mymod, &[]));
+    }
+
+    #[test]
+    fn test_submodule_importing_parent_is_bound_after_parent_body() {
+        // pkg/sub/__init__.py: from pkg import shared_thing
+        let resolver = ModuleResolver::new(Config::default());
+        resolver.register_module("main", std::path::Path::new("main.py"));
+        let pkg = resolver.register_module("pkg", std::path::Path::new("pkg/__init__.py"));
+        let sub = resolver.register_module("pkg.sub", std::path::Path::new("pkg/sub/__init__.py"));
+
+        let defers = |parent_source: &str, imports_parent: bool| {
+            let mut graph = crate::dependency_graph::DependencyGraph::new();
+            graph.add_module(
+                pkg,
+                "pkg".to_owned(),
+                std::path::Path::new("pkg/__init__.py"),
+            );
+            graph.add_module(
+                sub,
+                "pkg.sub".to_owned(),
+                std::path::Path::new("pkg/sub/__init__.py"),
+            );
+            if imports_parent {
+                graph.add_module_dependency(sub, pkg);
+            }
+            let ast = ruff_python_parser::parse_module(parent_source)
+                .expect("test module should parse")
+                .into_syntax();
+            let parent_graph = graph
+                .get_module_by_name_mut("pkg")
+                .expect("pkg should be in the graph");
+            crate::graph_builder::GraphBuilder::new(parent_graph, 10)
+                .build_from_ast(&ast)
+                .expect("graph should build");
+
+            let mut bundler = Bundler::new(None, &resolver);
+            bundler.graph = Some(&graph);
+            let symbol_renames = FxIndexMap::default();
+            let transformer = RecursiveImportTransformer::new(&RecursiveImportTransformerParams {
+                bundler: &bundler,
+                module_id: pkg,
+                symbol_renames: &symbol_renames,
+                is_wrapper_init: true,
+                python_version: 10,
+            });
+            transformer.defers_submodule_reexport(sub, "sub")
+        };
+
+        assert!(defers("shared_thing = 1\nfrom . import sub\n", true));
+        // Without the back-reference the submodule is bound where it is imported
+        assert!(!defers("shared_thing = 1\nfrom . import sub\n", false));
+        // The parent's body uses the submodule, so it has to be bound in place
+        assert!(!defers(
+            "shared_thing = 1\nfrom . import sub\nsub.setup()\n",
+            true
+        ));
+    }
+
+    #[test]
+    fn test_property_accessors_keep_class_local_name() {
+        let module = transform_with_wrapper_imports(
+            "class Config:\n    @property\n    def value(self):\n        return value\n\n    \
+             @value.setter\n    def value(self, new):\n        pass\n\n    @value.deleter\n    \
+             def value(self):\n        pass\n",
+            &["value"],
+        );
+        let Stmt::ClassDef(class_def) = &module.body[0] else {
+            panic!("expected a class definition");
+        };
+        let accessors: Vec<_> = class_def.body[1..]
+            .iter()
+            .map(|stmt| {
+                let Stmt::FunctionDef(func_def) = stmt else {
+                    panic!("expected a method");
+                };
+                attribute_path(&func_def.decorator_list[0].expression)
+            })
+            .collect();
+        assert_eq!(
+            accessors,
+            [Some(("value", "setter")), Some(("value", "deleter"))]
+        );
+
+        // Inside method bodies the name still refers to the imported symbol
+        let Stmt::FunctionDef(getter) = &class_def.body[0] else {
+            panic!("expected a method");
+        };
+        let Stmt::Return(ret) = &getter.body[0] else {
+            panic!("expected a return statement");
+        };
+        assert_eq!(
+            attribute_path(ret.value.as_deref().expect("return should have a value")),
+            Some(("models", "value"))
+        );
+    }
+
+    #[test]
+    fn test_exit_stack_bodies_rewrite_imported_context_managers() {
+        let module = transform_with_wrapper_imports(
+            "with contextlib.ExitStack() as stack:\n    stack.enter_context(Session())\n\nasync \
+             def run():\n    async with contextlib.AsyncExitStack() as stack:\n        await \
+             stack.enter_async_context(Session())\n",
+            &["Session"],
+        );
+
+        let enter_context_call = |stmt: &Stmt| {
+            let Stmt::Expr(expr_stmt) = stmt else {
+                panic!("expected an expression statement");
+            };
+            let value = match &*expr_stmt.value {
+                Expr::Await(await_expr) => &*await_expr.value,
+                value => value,
+            };
+            let Expr::Call(call) = value else {
+                panic!("expected a call");
+            };
+            let Expr::Call(ctx_call) = &call.arguments.args[0] else {
+                panic!("expected a context manager call");
+            };
+            (
+                attribute_path(&call.func).map(|(base, attr)| (base.to_owned(), attr.to_owned())),
+                attribute_path(&ctx_call.func)
+                    .map(|(base, attr)| (base.to_owned(), attr.to_owned())),
+            )
+        };
+
+        let Stmt::With(with_stmt) = &module.body[0] else {
+            panic!("expected a with statement");
+        };
+        assert_eq!(
+            enter_context_call(&with_stmt.body[0]),
+            (
+                Some(("stack".to_owned(), "enter_context".to_owned())),
+                Some(("models".to_owned(), "Session".to_owned()))
+            )
+        );
+
+        let Stmt::FunctionDef(func_def) = &module.body[1] else {
+            panic!("expected a function definition");
+        };
+        let Stmt::With(async_with) = &func_def.body[0] else {
+            panic!("expected an async with statement");
+        };
+        assert_eq!(
+            enter_context_call(&async_with.body[0]),
+            (
+                Some(("stack".to_owned(), "enter_async_context".to_owned())),
+                Some(("models".to_owned(), "Session".to_owned()))
+            )
+        );
+    }
+
+    #[test]
+    fn test_regex_constants_from_bundled_modules() {
+        // PATTERN imported from a wrapper module
+        let module = transform_with_wrapper_imports(
+            "RX = re.compile(PATTERN, re.MULTILINE | re.IGNORECASE)\n",
+            &["PATTERN"],
+        );
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let Expr::Call(call) = &*assign.value else {
+            panic!("expected a call");
+        };
+        assert_eq!(attribute_path(&call.func), Some(("re", "compile")));
+        assert_eq!(
+            attribute_path(&call.arguments.args[0]),
+            Some(("models", "PATTERN"))
+        );
+        let Expr::BinOp(flags) = &call.arguments.args[1] else {
+            panic!("expected combined flags");
+        };
+        assert_eq!(attribute_path(&flags.left), Some(("re", "MULTILINE")));
+        assert_eq!(attribute_path(&flags.right), Some(("re", "IGNORECASE")));
+
+        // `import consts as c` where `consts` is inlined and PATTERN was renamed
+        let module = transform_with_inlined_alias(
+            "RX = re.compile(c.PATTERN)\n",
+            Some("c"),
+            "consts",
+            &[("PATTERN", "PATTERN_1")],
+            &[],
+        );
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let Expr::Call(call) = &*assign.value else {
+            panic!("expected a call");
+        };
+        assert!(
+            matches!(&call.arguments.args[0], Expr::Name(name) if name.id.as_str() == "PATTERN_1")
+        );
+    }
+
+    #[test]
+    fn test_comprehensions_rewrite_element_iterable_and_conditions() {
+        let module = transform_with_wrapper_imports(
+            "a = [Model for x in Tags if Model]\nb = {Model for x in Tags if Model}\nc = (Model \
+             for x in Tags if Model)\nd = {Model: Model for x in Tags if Model}\n",
+            &["Model", "Tags"],
+        );
+
+        for stmt in &module.body {
+            let Stmt::Assign(assign) = stmt else {
+                panic!("expected an assignment");
+            };
+            let (elements, generators) = match &*assign.value {
+                Expr::ListComp(comp) => (vec![&*comp.elt], &comp.generators),
+                Expr::SetComp(comp) => (vec![&*comp.elt], &comp.generators),
+                Expr::Generator(comp) => (vec![&*comp.elt], &comp.generators),
+                Expr::DictComp(comp) => (vec![&*comp.key, &*comp.value], &comp.generators),
+                _ => panic!("expected a comprehension"),
+            };
+            for element in elements {
+                assert_eq!(attribute_path(element), Some(("models", "Model")));
+            }
+            assert_eq!(
+                attribute_path(&generators[0].iter),
+                Some(("models", "Tags"))
+            );
+            assert_eq!(
+                attribute_path(&generators[0].ifs[0]),
+                Some(("models", "Model"))
+            );
+            assert!(matches!(&generators[0].target, Expr::Name(name) if name.id.as_str() == "x"));
+        }
+    }
+
+    #[test]
+    fn test_frozenset_elements_rewritten() {
+        let module = transform_with_wrapper_imports(
+            "FLAGS = frozenset({READ, WRITE})\n",
+            &["READ", "WRITE"],
+        );
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let Expr::Call(call) = &*assign.value else {
+            panic!("expected a call");
+        };
+        assert!(matches!(&*call.func, Expr::Name(name) if name.id.as_str() == "frozenset"));
+        let Expr::Set(set) = &call.arguments.args[0] else {
+            panic!("expected a set literal");
+        };
+        let elements: Vec<_> = set.elts.iter().map(attribute_path).collect();
+        assert_eq!(
+            elements,
+            [Some(("models", "READ")), Some(("models", "WRITE"))]
+        );
+    }
+
+    #[test]
+    fn test_named_expr_value_rewritten_and_target_kept() {
+        let module = transform_with_inlined_alias(
+            "if (match := module.lookup(key)):\n    pass\n",
+            Some("module"),
+            "registry",
+            &[("lookup", "lookup_1")],
+            &[],
+        );
+        let Stmt::If(if_stmt) = &module.body[0] else {
+            panic!("expected an if statement");
+        };
+        let Expr::Named(named) = &*if_stmt.test else {
+            panic!("expected a named expression");
+        };
+        assert!(matches!(&*named.target, Expr::Name(name) if name.id.as_str() == "match"));
+        let Expr::Call(call) = &*named.value else {
+            panic!("expected a call");
+        };
+        assert!(matches!(&*call.func, Expr::Name(name) if name.id.as_str() == "lookup_1"));
+    }
+
+    #[test]
+    fn test_annotated_metadata_rewrites_bundled_symbols() {
+        let module = transform_with_inlined_alias(
+            "limit: Annotated[int, Validator(rule=my_module.RULE)] = 1\n\ndef check(value: \
+             Annotated[str, (Validator(rule=my_module.RULE), 'doc')]) -> None:\n    pass\n",
+            Some("my_module"),
+            "rules",
+            &[("RULE", "RULE_1")],
+            &[],
+        );
+
+        // The metadata argument sits in the tuple slice of the subscript
+        let validator_rule = |annotation: &Expr| -> Option<String> {
+            let Expr::Subscript(subscript) = annotation else {
+                return None;
+            };
+            let Expr::Tuple(slice) = &*subscript.slice else {
+                return None;
+            };
+            let metadata = match &slice.elts[1] {
+                Expr::Tuple(nested) => &nested.elts[0],
+                other => other,
+            };
+            let Expr::Call(call) = metadata else {
+                return None;
+            };
+            let Expr::Name(name) = &call.arguments.keywords[0].value else {
+                return None;
+            };
+            Some(name.id.to_string())
+        };
+
+        let Stmt::AnnAssign(ann_assign) = &module.body[0] else {
+            panic!("expected an annotated assignment");
+        };
+        assert_eq!(
+            validator_rule(&ann_assign.annotation).as_deref(),
+            Some("RULE_1")
+        );
+        let Stmt::FunctionDef(func_def) = &module.body[1] else {
+            panic!("expected a function definition");
+        };
+        let annotation = func_def.parameters.args[0]
+            .parameter
+            .annotation
+            .as_deref()
+            .expect("parameter should be annotated");
+        assert_eq!(validator_rule(annotation).as_deref(), Some("RULE_1"));
+    }
+
+    #[test]
+    fn test_descriptor_export_collapses_to_bare_name() {
+        // `CALLABLE = Descriptor()` is exported unrenamed
+        let module = transform_with_inlined_alias(
+            "result = module.CALLABLE()\n",
+            Some("module"),
+            "handlers",
+            &[("Descriptor", "Descriptor_1")],
+            &[],
+        );
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let Expr::Call(call) = &*assign.value else {
+            panic!("expected a call");
+        };
+        assert!(matches!(&*call.func, Expr::Name(name) if name.id.as_str() == "CALLABLE"));
+    }
+
+    #[test]
+    fn test_importlib_namespace_includes_renamed_and_unrenamed_exports() {
+        let module = transform_with_inlined_alias(
+            "m = importlib.import_module('utils')\n",
+            None,
+            "utils",
+            &[("helper", "helper_1")],
+            &["helper", "CONST"],
+        );
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let Expr::Call(call) = &*assign.value else {
+            panic!("expected a namespace call");
+        };
+        let keywords: Vec<(&str, &str)> = call
+            .arguments
+            .keywords
+            .iter()
+            .filter_map(|keyword| {
+                let Expr::Name(value) = &keyword.value else {
+                    return None;
+                };
+                Some((keyword.arg.as_ref()?.as_str(), value.id.as_str()))
+            })
+            .collect();
+        assert_eq!(keywords, [("helper", "helper_1"), ("CONST", "CONST")]);
+    }
+}
@@ -28,6 +28,9 @@ pub(super) struct TransformerState<'a> {
     pub(super) is_wrapper_init: bool,
     /// Track local variable assignments to avoid treating them as module aliases
     pub(super) local_variables: FxIndexSet<String>,
+    /// Properties defined in the class body being transformed. `@name.setter`, `@name.getter`
+    /// and `@name.deleter` refer to these class-local names, never to module-level symbols
+    pub(super) class_properties: FxIndexSet<String>,
     /// Track variables that were assigned from `importlib.import_module()` of inlined modules
     /// Maps variable name to the inlined module name
     pub(super) importlib_inlined_modules: FxIndexMap<String, String>,
@@ -54,6 +57,9 @@ pub(super) struct TransformerState<'a> {
     pub(super) current_function_body: Option<Vec<Stmt>>,
     /// Cached set of symbols used at runtime in the current function (for performance)
     pub(super) current_function_used_symbols: Option<FxIndexSet<String>>,
+    /// Submodule initializations and bindings appended after the module body, for submodules
+    /// that import back from this module while it initializes
+    pub(super) deferred_submodule_reexports: Vec<Stmt>,
 }
 
 impl<'a> TransformerState<'a> {
@@ -65,6 +71,7 @@ impl<'a> TransformerState<'a> {
             import_aliases: FxIndexMap::default(),
             is_wrapper_init: params.is_wrapper_init,
             local_variables: FxIndexSet::default(),
+            class_properties: FxIndexSet::default(),
             importlib_inlined_modules: FxIndexMap::default(),
             created_namespace_objects: false,
             wrapper_module_imports: FxIndexMap::default(),
@@ -75,6 +82,7 @@ impl<'a> TransformerState<'a> {
             current_assignment_targets: None,
             current_function_body: None,
             current_function_used_symbols: None,
+            deferred_submodule_reexports: Vec::new(),
         }
     }
 
@@ -9,7 +9,8 @@ pub(super) struct StatementProcessor;
 
 impl StatementProcessor {
     /// Collect all names assigned in a target expression.
-    /// Supports simple names and destructuring via tuples/lists.
+    /// Supports simple names and destructuring via tuples/lists at any nesting depth, including
+    /// starred targets (`(a, (b, *rest)), d = ...`).
     pub(super) fn collect_assigned_names(target: &Expr, out: &mut FxIndexSet<String>) {
         match target {
             Expr::Name(name) => {
@@ -25,6 +26,9 @@ impl StatementProcessor {
                     Self::collect_assigned_names(elt, out);
                 }
             }
+            Expr::Starred(starred) => {
+                Self::collect_assigned_names(&starred.value, out);
+            }
             _ => {}
         }
     }
@@ -101,3 +105,22 @@ impl StatementProcessor {
         func_def.body = new_body;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_assigned_names_nested_unpacking() {
+        let module = ruff_python_parser::parse_module("(a, [b, (c, *rest)]), d = func()\n")
+            .expect("test module should parse")
+            .into_syntax();
+        let Stmt::Assign(assign) = &module.body[0] else {
+            panic!("expected an assignment");
+        };
+        let mut names = FxIndexSet::default();
+        StatementProcessor::collect_assigned_names(&assign.targets[0], &mut names);
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        assert_eq!(names, ["a", "b", "c", "rest", "d"]);
+    }
+}
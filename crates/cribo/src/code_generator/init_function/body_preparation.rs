@@ -4,12 +4,17 @@
 //! necessary state and declarations.
 
 use log::debug;
-use ruff_python_ast::{Expr, ModModule, Stmt};
+use ruff_python_ast::{ExceptHandler, Expr, ModModule, Stmt};
 
 use super::state::InitFunctionState;
 use crate::{
     ast_builder,
-    code_generator::{bundler::Bundler, context::ModuleTransformContext, expression_handlers},
+    code_generator::{
+        bundler::Bundler,
+        context::ModuleTransformContext,
+        expression_handlers,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     types::FxIndexSet,
 };
 
@@ -28,7 +33,12 @@ pub(crate) struct BodyPreparationContext<'a> {
 }
 
 /// Phase responsible for preparing the body for processing
-pub(crate) struct BodyPreparationPhase;
+#[derive(Default)]
+pub(crate) struct BodyPreparationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(BodyPreparationPhase, "Init function: body preparation");
 
 impl BodyPreparationPhase {
     /// Prepare for body processing by analyzing the module
@@ -40,7 +50,8 @@ impl BodyPreparationPhase {
     /// 4. Scans for built-in names that will be assigned as local variables
     /// 5. Processes the body recursively using bundler
     /// 6. Filters out circular init attempts
-    /// 7. Declares lifted globals
+    /// 7. Removes bare module-level annotations
+    /// 8. Declares lifted globals
     ///
     /// Returns a context with computed analysis results and the processed body.
     pub(crate) fn execute<'a>(
@@ -68,7 +79,10 @@ impl BodyPreparationPhase {
             bundler.process_body_recursive(ast.body.clone(), ctx.module_name, module_scope_symbols);
 
         // Filter out accidental attempts to (re)initialize the entry package (__init__)
-        let processed_body = Self::filter_circular_init_attempts(processed_body_raw);
+        let mut processed_body = Self::filter_circular_init_attempts(processed_body_raw);
+
+        // Bare annotations behave differently once the module body runs as a function
+        Self::remove_bare_annotations(&mut processed_body, ctx);
 
         debug!(
             "Processing init function for module '{}', inlined_import_bindings: {:?}",
@@ -271,6 +285,69 @@ impl BodyPreparationPhase {
             .collect()
     }
 
+    /// Remove bare annotated assignments (`x: int`) at module level
+    ///
+    /// At module level a bare annotation binds nothing: until `x` is assigned, reads of it fall
+    /// through to the builtins and `hasattr(module, "x")` is false. Inside the init function it
+    /// would make `x` a local of the function instead, so a read of a builtin of that name
+    /// raises `UnboundLocalError`. Without the annotation the name resolves as it does at
+    /// module level; giving it a value would bind a name the module never binds. Function and
+    /// class bodies keep their annotations untouched.
+    fn remove_bare_annotations(body: &mut Vec<Stmt>, ctx: &ModuleTransformContext<'_>) {
+        let original_len = body.len();
+        body.retain(|stmt| {
+            let Stmt::AnnAssign(ann_assign) = stmt else {
+                return true;
+            };
+            let Expr::Name(target) = ann_assign.target.as_ref() else {
+                return true;
+            };
+            if ann_assign.value.is_some() {
+                return true;
+            }
+            debug!(
+                "Removing bare annotation '{}' in wrapper module '{}'",
+                target.id, ctx.module_name
+            );
+            false
+        });
+        if body.is_empty() && original_len > 0 {
+            body.push(ast_builder::statements::pass());
+        }
+
+        for stmt in body {
+            match stmt {
+                Stmt::If(if_stmt) => {
+                    Self::remove_bare_annotations(&mut if_stmt.body, ctx);
+                    for clause in &mut if_stmt.elif_else_clauses {
+                        Self::remove_bare_annotations(&mut clause.body, ctx);
+                    }
+                }
+                Stmt::Try(try_stmt) => {
+                    Self::remove_bare_annotations(&mut try_stmt.body, ctx);
+                    for handler in &mut try_stmt.handlers {
+                        let ExceptHandler::ExceptHandler(handler) = handler;
+                        Self::remove_bare_annotations(&mut handler.body, ctx);
+                    }
+                    Self::remove_bare_annotations(&mut try_stmt.orelse, ctx);
+                    Self::remove_bare_annotations(&mut try_stmt.finalbody, ctx);
+                }
+                Stmt::With(with_stmt) => {
+                    Self::remove_bare_annotations(&mut with_stmt.body, ctx);
+                }
+                Stmt::For(for_stmt) => {
+                    Self::remove_bare_annotations(&mut for_stmt.body, ctx);
+                    Self::remove_bare_annotations(&mut for_stmt.orelse, ctx);
+                }
+                Stmt::While(while_stmt) => {
+                    Self::remove_bare_annotations(&mut while_stmt.body, ctx);
+                    Self::remove_bare_annotations(&mut while_stmt.orelse, ctx);
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Declare lifted globals if any exist
     fn declare_lifted_globals(
         lifted_names: Option<&crate::types::FxIndexMap<String, String>>,
@@ -1,16 +1,27 @@
 //! Final cleanup phase for init function transformation
 //!
 //! This phase handles final elements: stdlib re-exports and remaining explicit imports
-//! from inlined modules.
+//! from inlined modules, and drops `if` blocks left empty by import suppression.
 
 use log::debug;
-use ruff_python_ast::{Expr, ExprContext, Stmt};
+use ruff_python_ast::{ExceptHandler, Expr, ExprContext, Int, Number, Stmt};
 
 use super::state::InitFunctionState;
-use crate::{ast_builder, code_generator::bundler::Bundler};
+use crate::{
+    ast_builder,
+    code_generator::{
+        bundler::Bundler,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
+};
 
 /// Phase responsible for final cleanup tasks
-pub(crate) struct CleanupPhase;
+#[derive(Default)]
+pub(crate) struct CleanupPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(CleanupPhase, "Init function: cleanup");
 
 impl CleanupPhase {
     /// Add final elements: stdlib re-exports and remaining imports
@@ -18,6 +29,7 @@ impl CleanupPhase {
     /// This phase:
     /// 1. Adds stdlib re-exports to the module namespace
     /// 2. Adds explicit imports from inlined modules as module attributes (if not already added)
+    /// 3. Removes `if` blocks that can never run and only contain `pass`
     ///
     /// **NOTE**: Wildcard imports (`imports_from_inlined`) were already handled earlier
     /// by the Wildcard Import Processing phase, so we only handle explicit imports here.
@@ -31,6 +43,91 @@ impl CleanupPhase {
 
         // Add explicit imports from inlined modules as module attributes
         Self::add_explicit_inlined_imports(bundler, ctx, state);
+
+        // Drop `if False: pass` leftovers of suppressed `TYPE_CHECKING` imports
+        Self::remove_dead_pass_blocks(&mut state.body);
+    }
+
+    /// Remove `if` blocks with an always-false constant condition (`False`, `0`, `None`), only
+    /// `pass` in their body and no `elif`/`else` branches, including in nested blocks
+    ///
+    /// A block that would be left empty gets a single `pass`.
+    fn remove_dead_pass_blocks(body: &mut Vec<Stmt>) {
+        Self::remove_dead_pass_blocks_in_branch(body);
+        if body.is_empty() {
+            body.push(ast_builder::statements::pass());
+        }
+    }
+
+    /// Like `remove_dead_pass_blocks`, for `else`/`finally` branches that may become empty
+    fn remove_dead_pass_blocks_in_branch(body: &mut Vec<Stmt>) {
+        let before = body.len();
+        body.retain(|stmt| !Self::is_dead_pass_block(stmt));
+        if body.len() < before {
+            debug!(
+                "Removed {} always-false `if` block(s) with only `pass`",
+                before - body.len()
+            );
+        }
+
+        for stmt in body {
+            match stmt {
+                Stmt::FunctionDef(function_def) => {
+                    Self::remove_dead_pass_blocks(&mut function_def.body);
+                }
+                Stmt::ClassDef(class_def) => Self::remove_dead_pass_blocks(&mut class_def.body),
+                Stmt::If(if_stmt) => {
+                    Self::remove_dead_pass_blocks(&mut if_stmt.body);
+                    for clause in &mut if_stmt.elif_else_clauses {
+                        Self::remove_dead_pass_blocks(&mut clause.body);
+                    }
+                }
+                Stmt::For(for_stmt) => {
+                    Self::remove_dead_pass_blocks(&mut for_stmt.body);
+                    Self::remove_dead_pass_blocks_in_branch(&mut for_stmt.orelse);
+                }
+                Stmt::While(while_stmt) => {
+                    Self::remove_dead_pass_blocks(&mut while_stmt.body);
+                    Self::remove_dead_pass_blocks_in_branch(&mut while_stmt.orelse);
+                }
+                Stmt::With(with_stmt) => Self::remove_dead_pass_blocks(&mut with_stmt.body),
+                Stmt::Try(try_stmt) => {
+                    Self::remove_dead_pass_blocks(&mut try_stmt.body);
+                    for ExceptHandler::ExceptHandler(handler) in &mut try_stmt.handlers {
+                        Self::remove_dead_pass_blocks(&mut handler.body);
+                    }
+                    Self::remove_dead_pass_blocks_in_branch(&mut try_stmt.orelse);
+                    // `try` without `except` clauses needs its `finally` block
+                    if try_stmt.handlers.is_empty() {
+                        Self::remove_dead_pass_blocks(&mut try_stmt.finalbody);
+                    } else {
+                        Self::remove_dead_pass_blocks_in_branch(&mut try_stmt.finalbody);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Check whether `stmt` is `if <False | 0 | None>:` with only `pass` and no other branches
+    fn is_dead_pass_block(stmt: &Stmt) -> bool {
+        let Stmt::If(if_stmt) = stmt else {
+            return false;
+        };
+        let always_false = match if_stmt.test.as_ref() {
+            Expr::BooleanLiteral(literal) => !literal.value,
+            Expr::NoneLiteral(_) => true,
+            Expr::NumberLiteral(literal) => {
+                matches!(&literal.value, Number::Int(value) if *value == Int::ZERO)
+            }
+            _ => false,
+        };
+        always_false
+            && if_stmt.elif_else_clauses.is_empty()
+            && if_stmt
+                .body
+                .iter()
+                .all(|stmt| matches!(stmt, Stmt::Pass(_)))
     }
 
     /// Add stdlib re-exports to the module namespace
@@ -105,3 +202,101 @@ impl CleanupPhase {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+
+    use super::*;
+
+    fn cleanup(source: &str) -> String {
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.into_syntax();
+
+        CleanupPhase::remove_dead_pass_blocks(&mut module.body);
+        module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_remove_dead_pass_blocks_at_top_level() {
+        let source = "\
+import os
+if False:
+    pass
+if 0:
+    pass
+if None:
+    pass
+if False:
+    import typing
+if False:
+    pass
+else:
+    x = 1
+";
+        assert_eq!(
+            cleanup(source),
+            "import os\nif False:\n    import typing\nif False:\n    pass\nelse:\n    x = 1"
+        );
+    }
+
+    #[test]
+    fn test_remove_dead_pass_blocks_in_function_and_class() {
+        let source = "\
+def f():
+    if False:
+        pass
+    return 1
+class C:
+    if False:
+        pass
+    x = 1
+";
+        assert_eq!(
+            cleanup(source),
+            "def f():\n    return 1\nclass C:\n    x = 1"
+        );
+    }
+
+    #[test]
+    fn test_remove_dead_pass_blocks_keeps_emptied_bodies_valid() {
+        let source = "\
+def f():
+    if False:
+        pass
+try:
+    x = 1
+except ValueError:
+    x = 2
+else:
+    if False:
+        pass
+try:
+    x = 3
+finally:
+    if False:
+        pass
+";
+        let expected = "\
+def f():
+    pass
+try:
+    x = 1
+except ValueError:
+    x = 2
+try:
+    x = 3
+finally:
+    pass";
+        assert_eq!(cleanup(source), expected);
+
+        // A body with nothing left still holds a statement
+        assert_eq!(cleanup("if False:\n    pass\n"), "pass");
+    }
+}
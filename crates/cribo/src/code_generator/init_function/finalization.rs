@@ -9,12 +9,20 @@ use super::{TransformError, state::InitFunctionState};
 use crate::{
     ast_builder,
     code_generator::{
-        bundler::Bundler, context::ModuleTransformContext, module_transformer::SELF_PARAM,
+        bundler::Bundler,
+        context::ModuleTransformContext,
+        module_transformer::SELF_PARAM,
+        profiling::{PhaseClock, impl_phase_timer},
     },
 };
 
 /// Phase responsible for finalizing and building the init function statement
-pub(crate) struct FinalizationPhase;
+#[derive(Default)]
+pub(crate) struct FinalizationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(FinalizationPhase, "Init function: finalization");
 
 impl FinalizationPhase {
     /// Build the final function statement from accumulated state
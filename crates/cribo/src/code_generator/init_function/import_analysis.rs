@@ -8,13 +8,22 @@ use ruff_python_ast::{Identifier, ModModule, Stmt};
 
 use super::state::InitFunctionState;
 use crate::{
-    code_generator::{bundler::Bundler, context::ModuleTransformContext},
+    code_generator::{
+        bundler::Bundler,
+        context::ModuleTransformContext,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     resolver::ModuleId,
     types::FxIndexMap,
 };
 
 /// Phase responsible for analyzing imports in the module
-pub(crate) struct ImportAnalysisPhase;
+#[derive(Default)]
+pub(crate) struct ImportAnalysisPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(ImportAnalysisPhase, "Init function: import analysis");
 
 impl ImportAnalysisPhase {
     /// Analyze all imports in the module and populate tracking state
@@ -14,13 +14,22 @@ use crate::{
         bundler::Bundler,
         context::ModuleTransformContext,
         import_transformer::{RecursiveImportTransformer, RecursiveImportTransformerParams},
+        profiling::{PhaseClock, impl_phase_timer},
     },
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
 };
 
 /// Phase responsible for transforming imports in the module AST
-pub(crate) struct ImportTransformationPhase;
+#[derive(Default)]
+pub(crate) struct ImportTransformationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(
+    ImportTransformationPhase,
+    "Init function: import transformation"
+);
 
 impl ImportTransformationPhase {
     /// Transform imports using `RecursiveImportTransformer` and add global declarations
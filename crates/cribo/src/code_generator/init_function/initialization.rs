@@ -13,11 +13,17 @@ use crate::{
         context::ModuleTransformContext,
         globals::GlobalsLifter,
         module_transformer::{SELF_PARAM, transform_ast_with_lifted_globals},
+        profiling::{PhaseClock, impl_phase_timer},
     },
 };
 
 /// Phase responsible for adding initialization guards and globals lifting
-pub(crate) struct InitializationPhase;
+#[derive(Default)]
+pub(crate) struct InitializationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(InitializationPhase, "Init function: initialization");
 
 impl InitializationPhase {
     /// Execute the initialization phase
@@ -28,7 +28,7 @@ use super::{
     WrapperSymbolSetupPhase,
 };
 use crate::{
-    code_generator::{bundler::Bundler, context::ModuleTransformContext},
+    code_generator::{bundler::Bundler, context::ModuleTransformContext, profiling::PhaseTimer},
     resolver::ModuleId,
     types::FxIndexMap,
 };
@@ -73,56 +73,100 @@ impl<'a> InitFunctionBuilder<'a> {
         let mut state = InitFunctionState::new();
 
         // Phase 1: Initialization
-        InitializationPhase::execute(self.bundler, self.ctx, &mut ast, &mut state);
+        let mut initialization = InitializationPhase::default();
+        initialization.time(|| {
+            InitializationPhase::execute(self.bundler, self.ctx, &mut ast, &mut state);
+        });
 
         // Phase 2: Import Analysis
-        ImportAnalysisPhase::execute(
-            self.bundler,
-            self.ctx,
-            &ast,
-            self.symbol_renames,
-            &mut state,
-        );
+        let mut import_analysis = ImportAnalysisPhase::default();
+        import_analysis.time(|| {
+            ImportAnalysisPhase::execute(
+                self.bundler,
+                self.ctx,
+                &ast,
+                self.symbol_renames,
+                &mut state,
+            );
+        });
 
         // Phase 3: Import Transformation
-        ImportTransformationPhase::execute(
-            self.bundler,
-            self.ctx,
-            &mut ast,
-            self.symbol_renames,
-            &mut state,
-        )?;
+        let mut import_transformation = ImportTransformationPhase::default();
+        import_transformation.time(|| {
+            ImportTransformationPhase::execute(
+                self.bundler,
+                self.ctx,
+                &mut ast,
+                self.symbol_renames,
+                &mut state,
+            )
+        })?;
 
         // Phase 4: Wrapper Symbol Setup
-        WrapperSymbolSetupPhase::execute(self.bundler, &mut state);
+        let mut wrapper_symbols = WrapperSymbolSetupPhase::default();
+        wrapper_symbols.time(|| WrapperSymbolSetupPhase::execute(self.bundler, &mut state));
 
         // Phase 5: Wildcard Import Processing
-        WildcardImportPhase::execute(self.bundler, self.ctx, &mut state);
+        let mut wildcard_imports = WildcardImportPhase::default();
+        wildcard_imports.time(|| WildcardImportPhase::execute(self.bundler, self.ctx, &mut state));
 
         // Phase 6: Body Preparation
         // Clone lifted_names to avoid borrow conflict
         let lifted_names_for_prep = state.lifted_names.clone();
-        let prep_context = BodyPreparationPhase::execute(
-            self.bundler,
-            self.ctx,
-            &ast,
-            &mut state,
-            lifted_names_for_prep.as_ref(),
-        );
+        let mut body_preparation = BodyPreparationPhase::default();
+        let prep_context = body_preparation.time(|| {
+            BodyPreparationPhase::execute(
+                self.bundler,
+                self.ctx,
+                &ast,
+                &mut state,
+                lifted_names_for_prep.as_ref(),
+            )
+        });
 
         // Phase 7: Wrapper Globals Collection
-        WrapperGlobalsPhase::execute(&prep_context.processed_body, &mut state);
+        let mut wrapper_globals = WrapperGlobalsPhase::default();
+        wrapper_globals
+            .time(|| WrapperGlobalsPhase::execute(&prep_context.processed_body, &mut state));
 
         // Phase 8: Statement Processing
-        StatementProcessingPhase::execute(prep_context, self.bundler, self.ctx, &mut state);
+        let mut statement_processing = StatementProcessingPhase::default();
+        statement_processing.time(|| {
+            StatementProcessingPhase::execute(prep_context, self.bundler, self.ctx, &mut state);
+        });
 
         // Phase 9: Submodule Handling
-        SubmoduleHandlingPhase::execute(self.bundler, self.ctx, self.symbol_renames, &mut state);
+        let mut submodules = SubmoduleHandlingPhase::default();
+        submodules.time(|| {
+            SubmoduleHandlingPhase::execute(
+                self.bundler,
+                self.ctx,
+                self.symbol_renames,
+                &mut state,
+            );
+        });
 
         // Phase 10: Final Cleanup
-        CleanupPhase::execute(self.bundler, self.ctx, &mut state);
+        let mut cleanup = CleanupPhase::default();
+        cleanup.time(|| CleanupPhase::execute(self.bundler, self.ctx, &mut state));
 
         // Phase 11: Finalization
-        FinalizationPhase::build_function_stmt(self.bundler, self.ctx, state)
+        let mut finalization = FinalizationPhase::default();
+        let function_stmt = finalization
+            .time(|| FinalizationPhase::build_function_stmt(self.bundler, self.ctx, state));
+
+        self.bundler.record_phase(&initialization);
+        self.bundler.record_phase(&import_analysis);
+        self.bundler.record_phase(&import_transformation);
+        self.bundler.record_phase(&wrapper_symbols);
+        self.bundler.record_phase(&wildcard_imports);
+        self.bundler.record_phase(&body_preparation);
+        self.bundler.record_phase(&wrapper_globals);
+        self.bundler.record_phase(&statement_processing);
+        self.bundler.record_phase(&submodules);
+        self.bundler.record_phase(&cleanup);
+        self.bundler.record_phase(&finalization);
+
+        function_stmt
     }
 }
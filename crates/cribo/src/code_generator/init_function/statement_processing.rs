@@ -14,10 +14,22 @@
 //! - Default statements (transform for module vars)
 
 use super::{InitFunctionState, body_preparation::BodyPreparationContext};
-use crate::code_generator::{bundler::Bundler, context::ModuleTransformContext};
+use crate::code_generator::{
+    bundler::Bundler,
+    context::ModuleTransformContext,
+    profiling::{PhaseClock, impl_phase_timer},
+};
 
 /// Statement Processing phase - processes transformed statements
-pub(crate) struct StatementProcessingPhase;
+#[derive(Default)]
+pub(crate) struct StatementProcessingPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(
+    StatementProcessingPhase,
+    "Init function: statement processing"
+);
 
 impl StatementProcessingPhase {
     /// Execute the statement processing phase
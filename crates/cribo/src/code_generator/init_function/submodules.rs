@@ -7,13 +7,22 @@ use log::debug;
 
 use super::state::InitFunctionState;
 use crate::{
-    code_generator::{bundler::Bundler, context::ModuleTransformContext},
+    code_generator::{
+        bundler::Bundler,
+        context::ModuleTransformContext,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
 };
 
 /// Phase responsible for setting up submodules as module attributes
-pub(crate) struct SubmoduleHandlingPhase;
+#[derive(Default)]
+pub(crate) struct SubmoduleHandlingPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(SubmoduleHandlingPhase, "Init function: submodules");
 
 impl SubmoduleHandlingPhase {
     /// Set up submodules as attributes on this module
@@ -7,10 +7,21 @@
 use log::debug;
 
 use super::state::InitFunctionState;
-use crate::{code_generator::bundler::Bundler, types::FxIndexSet};
+use crate::{
+    code_generator::{
+        bundler::Bundler,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
+    types::FxIndexSet,
+};
 
 /// Phase responsible for processing wildcard imports
-pub(crate) struct WildcardImportPhase;
+#[derive(Default)]
+pub(crate) struct WildcardImportPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(WildcardImportPhase, "Init function: wildcard imports");
 
 impl WildcardImportPhase {
     /// Process wildcard imports and add module attributes
@@ -10,10 +10,19 @@ use ruff_python_ast::{
 };
 
 use super::state::InitFunctionState;
-use crate::{ast_builder, types::FxIndexSet};
+use crate::{
+    ast_builder,
+    code_generator::profiling::{PhaseClock, impl_phase_timer},
+    types::FxIndexSet,
+};
 
 /// Phase responsible for collecting and declaring wrapper module globals
-pub(crate) struct WrapperGlobalsPhase;
+#[derive(Default)]
+pub(crate) struct WrapperGlobalsPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(WrapperGlobalsPhase, "Init function: wrapper globals");
 
 impl WrapperGlobalsPhase {
     /// Collect and declare wrapper module namespace variables
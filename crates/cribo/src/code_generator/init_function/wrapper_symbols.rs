@@ -6,10 +6,24 @@
 use log::debug;
 
 use super::state::InitFunctionState;
-use crate::{ast_builder, code_generator::bundler::Bundler};
+use crate::{
+    ast_builder,
+    code_generator::{
+        bundler::Bundler,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
+};
 
 /// Phase responsible for setting up placeholder assignments for wrapper module symbols
-pub(crate) struct WrapperSymbolSetupPhase;
+#[derive(Default)]
+pub(crate) struct WrapperSymbolSetupPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(
+    WrapperSymbolSetupPhase,
+    "Init function: wrapper symbol setup"
+);
 
 impl WrapperSymbolSetupPhase {
     /// Create placeholder assignments for wrapper module symbols
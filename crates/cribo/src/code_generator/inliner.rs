@@ -6,7 +6,7 @@
 use std::path::Path;
 
 use log::debug;
-use ruff_python_ast::{Expr, Identifier, ModModule, Stmt, StmtAssign, StmtClassDef};
+use ruff_python_ast::{Expr, ExprName, Identifier, ModModule, Stmt, StmtAssign, StmtClassDef};
 use ruff_text_size::TextRange;
 
 use super::{
@@ -332,44 +332,86 @@ impl Bundler<'_> {
         arg_kind: &str,
     ) {
         if let Expr::Name(name_expr) = expr {
-            let name = name_expr.id.as_str();
-
-            // Check if this value was imported from another module.
-            // If it was imported under an alias (e.g. `from pkg import X as Y`),
-            // resolve the canonical symbol via ctx.import_aliases and use its last
-            // segment to query the source module's renames.
-            if let Some(source_module) = ctx.import_sources.get(name) {
-                let lookup_key = ctx.import_aliases.get(name).map_or(name, |canonical| {
-                    canonical.rsplit('.').next().unwrap_or(canonical.as_str())
-                });
-
-                // Use that module's renames instead of the current module's
-                let source_module_id = self
-                    .get_module_id(source_module)
-                    .expect("Source module should exist");
-                if let Some(source_renames) = ctx.module_renames.get(&source_module_id)
-                    && let Some(renamed) = source_renames.get(lookup_key)
-                {
-                    log::debug!(
-                        "Applying cross-module rename for {arg_kind} '{name}' from module \
-                         '{source_module}': '{lookup_key}' -> '{renamed}'"
-                    );
-                    name_expr.id = renamed.clone().into();
-                    return;
-                }
+            if self.apply_cross_module_rename(name_expr, ctx, arg_kind) {
+                return;
             }
 
             // Not imported or no rename found in source module, apply local renames
-            if let Some(renamed) = module_renames.get(name) {
+            if let Some(renamed) = module_renames.get(name_expr.id.as_str()) {
                 name_expr.id = renamed.clone().into();
             }
         } else {
+            // Type arguments of a generic base (`Generic[T]`) may be imported from another
+            // module, and `__class_getitem__` must receive the objects under their bundled names
+            if let Expr::Subscript(subscript) = expr {
+                self.rewrite_type_arguments(&mut subscript.slice, ctx);
+            }
+
             // Complex expression: first resolve import aliases, then apply renames
             expression_handlers::resolve_import_aliases_in_expr(expr, &ctx.import_aliases);
             expression_handlers::rewrite_aliases_in_expr(expr, module_renames);
         }
     }
 
+    /// Rename `name_expr` if it was imported from a module that renamed the symbol
+    ///
+    /// Returns whether a cross-module rename was applied.
+    fn apply_cross_module_rename(
+        &self,
+        name_expr: &mut ExprName,
+        ctx: &InlineContext<'_>,
+        arg_kind: &str,
+    ) -> bool {
+        let name = name_expr.id.as_str();
+
+        // Check if this value was imported from another module.
+        // If it was imported under an alias (e.g. `from pkg import X as Y`),
+        // resolve the canonical symbol via ctx.import_aliases and use its last
+        // segment to query the source module's renames.
+        let Some(source_module) = ctx.import_sources.get(name) else {
+            return false;
+        };
+        let lookup_key = ctx.import_aliases.get(name).map_or(name, |canonical| {
+            canonical.rsplit('.').next().unwrap_or(canonical.as_str())
+        });
+
+        // Use that module's renames instead of the current module's
+        let source_module_id = self
+            .get_module_id(source_module)
+            .expect("Source module should exist");
+        let Some(renamed) = ctx
+            .module_renames
+            .get(&source_module_id)
+            .and_then(|source_renames| source_renames.get(lookup_key))
+        else {
+            return false;
+        };
+        log::debug!(
+            "Applying cross-module rename for {arg_kind} '{name}' from module '{source_module}': \
+             '{lookup_key}' -> '{renamed}'"
+        );
+        name_expr.id = renamed.clone().into();
+        true
+    }
+
+    /// Apply cross-module renames to the type arguments of a subscripted class base
+    fn rewrite_type_arguments(&self, expr: &mut Expr, ctx: &InlineContext<'_>) {
+        match expr {
+            Expr::Name(name_expr) => {
+                self.apply_cross_module_rename(name_expr, ctx, "type argument");
+            }
+            Expr::Subscript(subscript) => {
+                self.rewrite_type_arguments(&mut subscript.slice, ctx);
+            }
+            Expr::Tuple(tuple) => {
+                for elt in &mut tuple.elts {
+                    self.rewrite_type_arguments(elt, ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Inline a class definition
     pub(crate) fn inline_class(
         &self,
@@ -446,11 +488,17 @@ impl Bundler<'_> {
         // Apply renames and resolve import aliases in class body
         for body_stmt in &mut class_def_clone.body {
             Self::resolve_import_aliases_in_stmt(body_stmt, &ctx.import_aliases);
-            expression_handlers::rewrite_aliases_in_stmt(body_stmt, module_renames);
-            // Also apply semantic renames from context
-            if let Some(semantic_renames) = ctx.module_renames.get(&module_id) {
-                expression_handlers::rewrite_aliases_in_stmt(body_stmt, semantic_renames);
-            }
+        }
+        expression_handlers::rewrite_aliases_in_class_body(
+            &mut class_def_clone.body,
+            module_renames,
+        );
+        // Also apply semantic renames from context
+        if let Some(semantic_renames) = ctx.module_renames.get(&module_id) {
+            expression_handlers::rewrite_aliases_in_class_body(
+                &mut class_def_clone.body,
+                semantic_renames,
+            );
         }
 
         ctx.inlined_stmts.push(Stmt::ClassDef(class_def_clone));
@@ -682,6 +730,14 @@ impl Bundler<'_> {
             return;
         }
 
+        let mut ann_assign_clone = ann_assign.clone();
+
+        // Apply existing renames to the value BEFORE creating new rename for the target
+        if let Some(value) = ann_assign_clone.value.as_mut() {
+            expression_handlers::resolve_import_aliases_in_expr(value, &ctx.import_aliases);
+            expression_handlers::rewrite_aliases_in_expr(value, module_renames);
+        }
+
         // Check if this symbol was renamed by semantic analysis
         let renamed_name = self.resolve_renamed_name(&var_name, module_name, ctx);
 
@@ -695,8 +751,7 @@ impl Bundler<'_> {
         }
         ctx.global_symbols.insert(renamed_name.clone());
 
-        // Clone and rename the annotated assignment
-        let mut ann_assign_clone = ann_assign.clone();
+        // Rename the annotated assignment target
         if let Expr::Name(name_expr) = ann_assign_clone.target.as_mut() {
             name_expr.id = renamed_name.into();
         }
@@ -720,3 +775,67 @@ impl Bundler<'_> {
         ctx.inlined_stmts.push(Stmt::AnnAssign(ann_assign_clone));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+
+    use super::*;
+    use crate::{config::Config, resolver::ModuleResolver, types::FxIndexSet};
+
+    #[test]
+    fn test_inline_ann_assignment_applies_conflict_rename_to_target() {
+        let resolver = ModuleResolver::new(Config::default());
+        resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("utils", Path::new("utils.py"));
+        let bundler = Bundler::new(None, &resolver);
+
+        let source = "config: int = 5\nlimit: int = config * 2\n";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let module = parsed.into_syntax();
+
+        // `config` collides with a symbol of the same name in another module
+        let module_exports_map = FxIndexMap::default();
+        let mut global_symbols = FxIndexSet::default();
+        let mut semantic_renames = FxIndexMap::default();
+        semantic_renames.insert(
+            utils,
+            FxIndexMap::from_iter([("config".to_owned(), "config_utils".to_owned())]),
+        );
+        let mut inlined_stmts = Vec::new();
+        let mut ctx = InlineContext {
+            module_exports_map: &module_exports_map,
+            global_symbols: &mut global_symbols,
+            module_renames: &mut semantic_renames,
+            inlined_stmts: &mut inlined_stmts,
+            import_aliases: FxIndexMap::default(),
+            import_sources: FxIndexMap::default(),
+            python_version: 10,
+        };
+
+        let mut module_renames = FxIndexMap::default();
+        for stmt in &module.body {
+            let Stmt::AnnAssign(ann_assign) = stmt else {
+                panic!("expected an annotated assignment");
+            };
+            bundler.inline_ann_assignment(
+                ann_assign,
+                "utils",
+                utils,
+                &mut module_renames,
+                &mut ctx,
+            );
+        }
+
+        let inlined: Vec<String> = inlined_stmts
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect();
+        assert_eq!(
+            inlined,
+            ["config_utils: int = 5", "limit: int = config_utils * 2"]
+        );
+        assert_eq!(module_renames["config"], "config_utils");
+    }
+}
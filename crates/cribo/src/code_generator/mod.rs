@@ -17,8 +17,10 @@ pub(crate) mod init_function;
 pub(crate) mod inliner;
 pub(crate) mod module_registry;
 pub(crate) mod module_transformer;
+pub(crate) mod namespace_flattening;
 pub(crate) mod namespace_manager;
 pub(crate) mod phases;
+pub(crate) mod profiling;
 pub(crate) mod symbol_source;
 
 // Re-export the main bundler and key types
@@ -0,0 +1,611 @@
+//! Flat name binding for namespace objects (`--no-namespace-objects`)
+//!
+//! Inlined modules that are accessed as modules are represented by a `types.SimpleNamespace`
+//! holding their symbols. With flat binding, `mymod = SimpleNamespace(...)` and
+//! `mymod.foo = foo` become `mymod_foo = foo`, and every `mymod.foo` access is rewritten to
+//! `mymod_foo`. Namespaces attached to other namespaces (`pkg.mod = pkg_mod`) are followed, so
+//! `pkg.mod.foo` becomes `pkg_mod_foo`.
+//!
+//! Only namespaces used exclusively through attribute access can be flattened. A namespace
+//! keeps its object when it escapes as a value (passed to a function, returned, aliased), when
+//! its name is rebound, when its attributes are assigned or deleted outside module scope, or
+//! when an attribute is read that is never assigned.
+
+use log::debug;
+use ruff_python_ast::{
+    ExceptHandler, Expr, ExprContext, Parameter, Pattern, Stmt, TypeParam,
+    visitor::{
+        self, Visitor,
+        transformer::{self, Transformer},
+    },
+};
+
+use crate::{
+    ast_builder::{CRIBO_PREFIX, expressions, statements},
+    code_generator::module_registry::generate_unique_name,
+    types::{FxIndexMap, FxIndexSet},
+};
+
+/// Namespace attached to an attribute of another namespace: parent -> attribute -> child
+type NamespaceAliases = FxIndexMap<String, FxIndexMap<String, String>>;
+
+/// A module-level `name = _cribo.types.SimpleNamespace(attr=value, ...)`
+struct NamespaceCandidate {
+    /// Index of the creating statement in the module body
+    stmt_index: usize,
+    /// Attributes passed to the constructor
+    initial_attrs: FxIndexSet<String>,
+}
+
+/// Replace namespace objects that are only used through attribute access with flat names
+pub(crate) fn flatten_namespaces(body: &mut Vec<Stmt>) {
+    let candidates = find_candidates(body);
+    if candidates.is_empty() {
+        return;
+    }
+    let candidate_names: FxIndexSet<String> = candidates.keys().cloned().collect();
+    let (aliases, alias_stmts) = find_aliases(body, &candidate_names);
+
+    let mut analysis = UsageAnalysis {
+        namespaces: &candidate_names,
+        aliases: &aliases,
+        bindings: FxIndexMap::default(),
+        names: candidate_names.clone(),
+        assigned: FxIndexMap::default(),
+        read: FxIndexMap::default(),
+        escaping: FxIndexSet::default(),
+        scope_depth: 0,
+    };
+    for (index, stmt) in body.iter().enumerate() {
+        if let Some((parent, attr)) = alias_stmts.get(&index) {
+            // `parent.attr = child` links two namespaces; whether `child` escapes through it is
+            // decided once it is known whether `parent` is flattened
+            analysis.record_assigned(parent, attr);
+        } else {
+            analysis.visit_stmt(stmt);
+        }
+    }
+
+    let flattened = analysis.flattenable(&candidates);
+    if flattened.is_empty() {
+        debug!("No namespace object can be replaced by flat names");
+        return;
+    }
+    debug!(
+        "Replacing {} namespace object(s) with flat names: {:?}",
+        flattened.len(),
+        flattened
+    );
+
+    // Flat names must not collide with any name used in the bundle
+    let mut taken = analysis.names;
+    let mut flat_names: FxIndexMap<String, FxIndexMap<String, String>> = FxIndexMap::default();
+    for namespace in &flattened {
+        let attrs = candidates[namespace].initial_attrs.iter().chain(
+            analysis
+                .assigned
+                .get(namespace)
+                .into_iter()
+                .flat_map(|assigned| assigned.iter()),
+        );
+        let namespace_names = flat_names.entry(namespace.clone()).or_default();
+        for attr in attrs {
+            if namespace_names.contains_key(attr) {
+                continue;
+            }
+            let flat_name = generate_unique_name(&format!("{namespace}_{attr}"), &taken);
+            taken.insert(flat_name.clone());
+            namespace_names.insert(attr.clone(), flat_name);
+        }
+    }
+
+    // Only links between two flattened namespaces are resolved statically
+    let flat_aliases: NamespaceAliases = aliases
+        .iter()
+        .filter(|(parent, _)| flattened.contains(*parent))
+        .map(|(parent, children)| {
+            let children = children
+                .iter()
+                .filter(|(_, child)| flattened.contains(*child))
+                .map(|(attr, child)| (attr.clone(), child.clone()))
+                .collect();
+            (parent.clone(), children)
+        })
+        .collect();
+
+    let rewriter = FlatNameRewriter {
+        flattened: &flattened,
+        aliases: &flat_aliases,
+        flat_names: &flat_names,
+    };
+    let reads = analysis.read;
+    let creations: FxIndexMap<usize, &str> = flattened
+        .iter()
+        .map(|namespace| (candidates[namespace].stmt_index, namespace.as_str()))
+        .collect();
+
+    let original_body = std::mem::take(body);
+    for (index, mut stmt) in original_body.into_iter().enumerate() {
+        if let Some((parent, attr)) = alias_stmts.get(&index)
+            && flat_aliases
+                .get(parent)
+                .is_some_and(|children| children.contains_key(attr))
+        {
+            continue;
+        }
+
+        if let Some(&namespace) = creations.get(&index) {
+            let Stmt::Assign(assign) = stmt else {
+                unreachable!("namespace candidates are created by assignments");
+            };
+            let Expr::Call(call) = *assign.value else {
+                unreachable!("namespace candidates are created by constructor calls");
+            };
+            for keyword in call.arguments.keywords {
+                let Some(attr) = keyword.arg else {
+                    continue;
+                };
+                let is_read = reads
+                    .get(namespace)
+                    .is_some_and(|read| read.contains(attr.as_str()));
+                if !is_read && is_side_effect_free(&keyword.value) {
+                    continue;
+                }
+                let mut value = keyword.value;
+                rewriter.visit_expr(&mut value);
+                body.push(statements::simple_assign(
+                    &flat_names[namespace][attr.as_str()],
+                    value,
+                ));
+            }
+            continue;
+        }
+
+        rewriter.visit_stmt(&mut stmt);
+        body.push(stmt);
+    }
+}
+
+/// Module-level namespace objects created from `_cribo.types.SimpleNamespace(...)`
+fn find_candidates(body: &[Stmt]) -> FxIndexMap<String, NamespaceCandidate> {
+    let mut candidates = FxIndexMap::default();
+    let mut created_twice = FxIndexSet::default();
+    for (stmt_index, stmt) in body.iter().enumerate() {
+        let Stmt::Assign(assign) = stmt else {
+            continue;
+        };
+        let [Expr::Name(target)] = assign.targets.as_slice() else {
+            continue;
+        };
+        let Expr::Call(call) = assign.value.as_ref() else {
+            continue;
+        };
+        if !is_simple_namespace_ctor(&call.func)
+            || !call.arguments.args.is_empty()
+            || call.arguments.keywords.iter().any(|kw| kw.arg.is_none())
+        {
+            continue;
+        }
+
+        let name = target.id.to_string();
+        if candidates.contains_key(&name) {
+            created_twice.insert(name);
+            continue;
+        }
+        let initial_attrs = call
+            .arguments
+            .keywords
+            .iter()
+            .filter_map(|keyword| keyword.arg.as_ref().map(ToString::to_string))
+            .collect();
+        candidates.insert(
+            name,
+            NamespaceCandidate {
+                stmt_index,
+                initial_attrs,
+            },
+        );
+    }
+    candidates.retain(|name, _| !created_twice.contains(name));
+    candidates
+}
+
+/// Whether `func` is `_cribo.types.SimpleNamespace`
+fn is_simple_namespace_ctor(func: &Expr) -> bool {
+    let Expr::Attribute(ctor) = func else {
+        return false;
+    };
+    let Expr::Attribute(types) = ctor.value.as_ref() else {
+        return false;
+    };
+    ctor.attr.as_str() == "SimpleNamespace"
+        && types.attr.as_str() == "types"
+        && matches!(types.value.as_ref(), Expr::Name(root) if root.id.as_str() == CRIBO_PREFIX)
+}
+
+/// Module-level `parent.attr = child` statements between two namespace candidates
+fn find_aliases(
+    body: &[Stmt],
+    namespaces: &FxIndexSet<String>,
+) -> (NamespaceAliases, FxIndexMap<usize, (String, String)>) {
+    let mut aliases = NamespaceAliases::default();
+    let mut alias_stmts = FxIndexMap::default();
+    for (index, stmt) in body.iter().enumerate() {
+        let Stmt::Assign(assign) = stmt else {
+            continue;
+        };
+        let [Expr::Attribute(target)] = assign.targets.as_slice() else {
+            continue;
+        };
+        let (Expr::Name(parent), Expr::Name(child)) =
+            (target.value.as_ref(), assign.value.as_ref())
+        else {
+            continue;
+        };
+        if parent.id == child.id
+            || !namespaces.contains(parent.id.as_str())
+            || !namespaces.contains(child.id.as_str())
+        {
+            continue;
+        }
+        let children = aliases.entry(parent.id.to_string()).or_default();
+        // A second link through the same attribute rebinds it; keep both namespaces as objects
+        if children.contains_key(target.attr.as_str()) {
+            continue;
+        }
+        children.insert(target.attr.to_string(), child.id.to_string());
+        alias_stmts.insert(index, (parent.id.to_string(), target.attr.to_string()));
+    }
+    (aliases, alias_stmts)
+}
+
+/// The namespace `expr` evaluates to: a namespace name, or an attribute chain through aliases
+fn resolve_namespace<'n>(
+    expr: &Expr,
+    namespaces: &'n FxIndexSet<String>,
+    aliases: &'n NamespaceAliases,
+) -> Option<&'n str> {
+    match expr {
+        Expr::Name(name) => namespaces.get(name.id.as_str()).map(String::as_str),
+        Expr::Attribute(attr) => {
+            let parent = resolve_namespace(&attr.value, namespaces, aliases)?;
+            aliases
+                .get(parent)?
+                .get(attr.attr.as_str())
+                .map(String::as_str)
+        }
+        _ => None,
+    }
+}
+
+/// Constructor arguments that can be dropped when nothing reads them
+fn is_side_effect_free(expr: &Expr) -> bool {
+    match expr {
+        Expr::StringLiteral(_)
+        | Expr::BytesLiteral(_)
+        | Expr::NumberLiteral(_)
+        | Expr::BooleanLiteral(_)
+        | Expr::NoneLiteral(_) => true,
+        Expr::List(list) => list.elts.iter().all(is_side_effect_free),
+        Expr::Tuple(tuple) => tuple.elts.iter().all(is_side_effect_free),
+        _ => false,
+    }
+}
+
+/// Collects how namespace candidates are used throughout the module
+struct UsageAnalysis<'c> {
+    namespaces: &'c FxIndexSet<String>,
+    aliases: &'c NamespaceAliases,
+    /// Number of bindings of each name; the creating assignment counts as one
+    bindings: FxIndexMap<String, usize>,
+    /// Every name referenced or bound anywhere in the module
+    names: FxIndexSet<String>,
+    /// Attributes assigned at module scope, per namespace
+    assigned: FxIndexMap<String, FxIndexSet<String>>,
+    /// Attributes read, per namespace
+    read: FxIndexMap<String, FxIndexSet<String>>,
+    /// Namespaces that must stay objects
+    escaping: FxIndexSet<String>,
+    /// Nesting depth of function, class, lambda and comprehension scopes
+    scope_depth: usize,
+}
+
+impl UsageAnalysis<'_> {
+    fn bind(&mut self, name: &str) {
+        self.names.insert(name.to_owned());
+        *self.bindings.entry(name.to_owned()).or_default() += 1;
+    }
+
+    fn record_assigned(&mut self, namespace: &str, attr: &str) {
+        self.assigned
+            .entry(namespace.to_owned())
+            .or_default()
+            .insert(attr.to_owned());
+    }
+
+    fn record_read(&mut self, namespace: &str, attr: &str) {
+        self.read
+            .entry(namespace.to_owned())
+            .or_default()
+            .insert(attr.to_owned());
+    }
+
+    fn in_nested_scope(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.scope_depth += 1;
+        visit(self);
+        self.scope_depth -= 1;
+    }
+
+    /// Candidates that can be replaced by flat names
+    fn flattenable(
+        &self,
+        candidates: &FxIndexMap<String, NamespaceCandidate>,
+    ) -> FxIndexSet<String> {
+        let mut escaping = self.escaping.clone();
+        escaping.extend(
+            self.bindings
+                .iter()
+                .filter(|(name, count)| **count > 1 && candidates.contains_key(*name))
+                .map(|(name, _)| name.clone()),
+        );
+
+        loop {
+            let escaping_before = escaping.len();
+            for (namespace, candidate) in candidates {
+                if escaping.contains(namespace) {
+                    continue;
+                }
+                let assigned = self.assigned.get(namespace);
+                let reads_undefined = self.read.get(namespace).is_some_and(|read| {
+                    read.iter().any(|attr| {
+                        !candidate.initial_attrs.contains(attr)
+                            && !assigned.is_some_and(|assigned| assigned.contains(attr))
+                    })
+                });
+                if reads_undefined {
+                    escaping.insert(namespace.clone());
+                }
+            }
+            // A namespace attached to an object stays reachable as a value through it
+            for (parent, children) in self.aliases {
+                if escaping.contains(parent) {
+                    escaping.extend(children.values().cloned());
+                }
+            }
+            if escaping.len() == escaping_before {
+                break;
+            }
+        }
+
+        candidates
+            .keys()
+            .filter(|namespace| !escaping.contains(*namespace))
+            .cloned()
+            .collect()
+    }
+}
+
+impl<'a> Visitor<'a> for UsageAnalysis<'_> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(func_def) => {
+                self.bind(func_def.name.as_str());
+                self.in_nested_scope(|this| visitor::walk_stmt(this, stmt));
+            }
+            Stmt::ClassDef(class_def) => {
+                self.bind(class_def.name.as_str());
+                self.in_nested_scope(|this| visitor::walk_stmt(this, stmt));
+            }
+            Stmt::Import(import) => {
+                for alias in &import.names {
+                    let bound = alias.asname.as_ref().map_or_else(
+                        || alias.name.as_str().split('.').next().unwrap_or_default(),
+                        |asname| asname.as_str(),
+                    );
+                    self.bind(bound);
+                }
+            }
+            Stmt::ImportFrom(import_from) => {
+                for alias in &import_from.names {
+                    self.bind(alias.asname.as_ref().unwrap_or(&alias.name).as_str());
+                }
+            }
+            Stmt::Global(global) => {
+                self.names
+                    .extend(global.names.iter().map(ToString::to_string));
+            }
+            Stmt::Nonlocal(nonlocal) => {
+                self.names
+                    .extend(nonlocal.names.iter().map(ToString::to_string));
+            }
+            Stmt::AugAssign(aug_assign) => {
+                // `ns.attr += 1` reads the attribute before assigning it
+                if let Expr::Attribute(attr) = aug_assign.target.as_ref()
+                    && let Some(namespace) =
+                        resolve_namespace(&attr.value, self.namespaces, self.aliases)
+                {
+                    self.record_read(namespace, attr.attr.as_str());
+                }
+                visitor::walk_stmt(self, stmt);
+            }
+            _ => visitor::walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) => {
+                if name.ctx == ExprContext::Load {
+                    self.names.insert(name.id.to_string());
+                    if self.namespaces.contains(name.id.as_str()) {
+                        self.escaping.insert(name.id.to_string());
+                    }
+                } else {
+                    self.bind(name.id.as_str());
+                }
+            }
+            Expr::Attribute(attr) => {
+                let Some(namespace) = resolve_namespace(&attr.value, self.namespaces, self.aliases)
+                else {
+                    visitor::walk_expr(self, expr);
+                    return;
+                };
+                let attr_name = attr.attr.as_str();
+                match attr.ctx {
+                    ExprContext::Load => {
+                        // Reading an attached namespace itself uses it as a value
+                        if let Some(child) = self
+                            .aliases
+                            .get(namespace)
+                            .and_then(|children| children.get(attr_name))
+                        {
+                            self.escaping.insert(child.clone());
+                        }
+                        self.record_read(namespace, attr_name);
+                    }
+                    ExprContext::Store if self.scope_depth == 0 => {
+                        self.record_assigned(namespace, attr_name);
+                    }
+                    _ => {
+                        self.escaping.insert(namespace.to_owned());
+                    }
+                }
+            }
+            Expr::Lambda(_)
+            | Expr::ListComp(_)
+            | Expr::SetComp(_)
+            | Expr::DictComp(_)
+            | Expr::Generator(_) => {
+                self.in_nested_scope(|this| visitor::walk_expr(this, expr));
+            }
+            _ => visitor::walk_expr(self, expr),
+        }
+    }
+
+    fn visit_parameter(&mut self, parameter: &'a Parameter) {
+        self.bind(parameter.name.as_str());
+        visitor::walk_parameter(self, parameter);
+    }
+
+    fn visit_except_handler(&mut self, except_handler: &'a ExceptHandler) {
+        let ExceptHandler::ExceptHandler(handler) = except_handler;
+        if let Some(name) = &handler.name {
+            self.bind(name.as_str());
+        }
+        visitor::walk_except_handler(self, except_handler);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'a Pattern) {
+        let bound = match pattern {
+            Pattern::MatchAs(match_as) => match_as.name.as_ref(),
+            Pattern::MatchStar(match_star) => match_star.name.as_ref(),
+            Pattern::MatchMapping(match_mapping) => match_mapping.rest.as_ref(),
+            _ => None,
+        };
+        if let Some(name) = bound {
+            self.bind(name.as_str());
+        }
+        visitor::walk_pattern(self, pattern);
+    }
+
+    fn visit_type_param(&mut self, type_param: &'a TypeParam) {
+        let name = match type_param {
+            TypeParam::TypeVar(type_var) => &type_var.name,
+            TypeParam::ParamSpec(param_spec) => &param_spec.name,
+            TypeParam::TypeVarTuple(type_var_tuple) => &type_var_tuple.name,
+        };
+        self.bind(name.as_str());
+        visitor::walk_type_param(self, type_param);
+    }
+}
+
+/// Rewrites attribute accesses on flattened namespaces to their flat names
+struct FlatNameRewriter<'p> {
+    flattened: &'p FxIndexSet<String>,
+    aliases: &'p NamespaceAliases,
+    flat_names: &'p FxIndexMap<String, FxIndexMap<String, String>>,
+}
+
+impl Transformer for FlatNameRewriter<'_> {
+    fn visit_expr(&self, expr: &mut Expr) {
+        if let Expr::Attribute(attr) = expr
+            && let Some(namespace) = resolve_namespace(&attr.value, self.flattened, self.aliases)
+            && let Some(flat_name) = self
+                .flat_names
+                .get(namespace)
+                .and_then(|names| names.get(attr.attr.as_str()))
+        {
+            *expr = expressions::name(flat_name, attr.ctx);
+            return;
+        }
+        transformer::walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    fn flatten(source: &str) -> String {
+        let parsed = parse_module(source).expect("Test code should parse successfully");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut body = parsed.syntax().body.clone();
+        flatten_namespaces(&mut body);
+        body.iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_flattens_attribute_only_namespaces() {
+        let source = "\
+greetings_greeting = _cribo.types.SimpleNamespace(__name__='greetings.greeting')
+greetings = _cribo.types.SimpleNamespace(__name__='greetings', __path__=[])
+greetings.greeting = greetings_greeting
+def get_greeting(name):
+    return name
+greetings_greeting.get_greeting = get_greeting
+def main():
+    print(greetings.__name__)
+    return greetings.greeting.get_greeting('Python')
+";
+        assert_eq!(
+            flatten(source),
+            "\
+greetings___name__ = 'greetings'
+def get_greeting(name):
+    return name
+greetings_greeting_get_greeting = get_greeting
+def main():
+    print(greetings___name__)
+    return greetings_greeting_get_greeting('Python')"
+        );
+    }
+
+    #[test]
+    fn test_escaping_namespaces_keep_their_object() {
+        let source = "\
+greetings_greeting = _cribo.types.SimpleNamespace(__name__='greetings.greeting')
+greetings = _cribo.types.SimpleNamespace(__name__='greetings')
+greetings.greeting = greetings_greeting
+greetings_greeting.get_greeting = get_greeting
+greeting = greetings.greeting
+utils = _cribo.types.SimpleNamespace(__name__='utils')
+_cribo_init_utils(utils)
+";
+        assert_eq!(
+            flatten(source),
+            "\
+greetings_greeting = _cribo.types.SimpleNamespace(__name__='greetings.greeting')
+greetings_greeting_1 = greetings_greeting
+greetings_greeting.get_greeting = get_greeting
+greeting = greetings_greeting_1
+utils = _cribo.types.SimpleNamespace(__name__='utils')
+_cribo_init_utils(utils)"
+        );
+    }
+}
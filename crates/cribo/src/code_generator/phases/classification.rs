@@ -12,26 +12,35 @@ use ruff_python_ast::ModModule;
 
 use crate::{
     analyzers::module_classifier::{ClassificationResult, ModuleClassifier},
-    code_generator::bundler::Bundler,
+    code_generator::{
+        bundler::Bundler,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     resolver::ModuleId,
     types::FxIndexMap,
 };
 
 /// Classification phase handler (stateless)
 #[derive(Default)]
-pub(crate) struct ClassificationPhase;
+pub(crate) struct ClassificationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(ClassificationPhase, "Classification");
 
 impl ClassificationPhase {
     /// Create a new classification phase
     pub(crate) const fn new() -> Self {
-        Self
+        Self {
+            clock: PhaseClock::new(),
+        }
     }
 
     /// Execute the classification phase
     ///
     /// This method:
     /// 1. Creates a `ModuleClassifier` with the current bundler state
-    /// 2. Classifies modules into inlinable vs wrapper categories
+    /// 2. Classifies modules into inlinable vs wrapper categories (all wrapper when `no_inline`)
     /// 3. Stores classification results in the bundler
     /// 4. Tracks inlined modules and their exports
     /// 5. Registers wrapper modules with synthetic names
@@ -43,6 +52,7 @@ impl ClassificationPhase {
         bundler: &mut Bundler<'_>,
         modules: &FxIndexMap<ModuleId, (Arc<ModModule>, PathBuf, String)>,
         python_version: u8,
+        no_inline: bool,
     ) -> ClassificationResult {
         // Classify modules into inlinable and wrapper modules
         let classifier = ModuleClassifier::new(
@@ -52,7 +62,10 @@ impl ClassificationPhase {
             bundler.circular_modules.clone(),
         );
 
-        let classification = classifier.classify_modules(modules, python_version);
+        let mut classification = classifier.classify_modules(modules, python_version);
+        if no_inline {
+            Self::force_wrapper_modules(&mut classification);
+        }
 
         // Store modules with explicit __all__ declarations
         bundler
@@ -68,6 +81,19 @@ impl ClassificationPhase {
         classification
     }
 
+    /// Move every inlinable module to the wrapper path, disabling the inliner
+    fn force_wrapper_modules(classification: &mut ClassificationResult) {
+        if classification.inlinable_modules.is_empty() {
+            return;
+        }
+        log::info!(
+            "--no-inline: bundling {} inlinable modules as wrapper modules",
+            classification.inlinable_modules.len()
+        );
+        let inlinable = std::mem::take(&mut classification.inlinable_modules);
+        classification.wrapper_modules.extend(inlinable);
+    }
+
     /// Track inlined modules and store their exports
     fn track_inlined_modules(bundler: &mut Bundler<'_>, classification: &ClassificationResult) {
         for (module_id, _, _, _) in &classification.inlinable_modules {
@@ -252,4 +278,38 @@ mod tests {
         let wrapper_id = result.wrapper_modules[0].0;
         assert_ne!(inlinable_id, wrapper_id);
     }
+
+    #[test]
+    fn test_force_wrapper_modules_moves_inlinable_modules() {
+        use ruff_python_ast::AtomicNodeIndex;
+        use ruff_text_size::TextRange;
+
+        let module = |id: u32| {
+            (
+                ModuleId::new(id),
+                Arc::new(ModModule {
+                    node_index: AtomicNodeIndex::NONE,
+                    range: TextRange::default(),
+                    body: vec![],
+                }),
+                PathBuf::new(),
+                format!("hash{id}"),
+            )
+        };
+        let mut result = ClassificationResult {
+            inlinable_modules: vec![module(1), module(2)],
+            wrapper_modules: vec![module(3)],
+            module_exports_map: FxIndexMap::default(),
+            modules_with_explicit_all: FxIndexSet::default(),
+        };
+
+        ClassificationPhase::force_wrapper_modules(&mut result);
+
+        assert!(result.inlinable_modules.is_empty());
+        let wrapper_ids: Vec<ModuleId> = result.wrapper_modules.iter().map(|m| m.0).collect();
+        assert_eq!(
+            wrapper_ids,
+            [ModuleId::new(3), ModuleId::new(1), ModuleId::new(2)]
+        );
+    }
 }
@@ -11,14 +11,22 @@ use std::sync::Arc;
 use ruff_python_ast::{ModModule, Stmt};
 
 use crate::{
-    code_generator::{bundler::Bundler, context::BundleParams},
+    code_generator::{
+        bundler::Bundler,
+        context::BundleParams,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
 };
 
 /// Entry module phase handler (stateless)
 #[derive(Default)]
-pub(crate) struct EntryModulePhase;
+pub(crate) struct EntryModulePhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(EntryModulePhase, "Entry module");
 
 /// Result from processing the entry module
 #[derive(Debug, Clone)]
@@ -34,7 +42,9 @@ pub(crate) struct EntryModuleProcessingResult {
 impl EntryModulePhase {
     /// Create a new entry module phase
     pub(crate) const fn new() -> Self {
-        Self
+        Self {
+            clock: PhaseClock::new(),
+        }
     }
 
     /// Execute the entry module processing phase
@@ -101,7 +111,7 @@ impl EntryModulePhase {
             &entry_module_symbols,
             &entry_module_renames,
             final_body,
-            params.python_version,
+            !params.no_deduplicate_imports,
             &mut entry_statements,
         );
 
@@ -227,18 +237,20 @@ impl EntryModulePhase {
     }
 
     /// Process entry module statements with deduplication
-    #[expect(clippy::too_many_arguments)]
+    ///
+    /// Imports already in `final_body` are only skipped with `deduplicate_imports`.
     fn process_entry_statements(
         bundler: &Bundler<'_>,
         ast: &ModModule,
         locally_defined_symbols: &FxIndexSet<String>,
         entry_module_renames: &FxIndexMap<String, String>,
         final_body: &[Stmt],
-        python_version: u8,
+        deduplicate_imports: bool,
         entry_statements: &mut Vec<Stmt>,
     ) {
         use crate::code_generator::import_deduplicator;
 
+        let earlier_imports: &[Stmt] = if deduplicate_imports { final_body } else { &[] };
         for stmt in &ast.body {
             // Check if this import was hoisted
             let is_hoisted = import_deduplicator::is_hoisted_import(bundler, stmt);
@@ -251,8 +263,7 @@ impl EntryModulePhase {
                     let duplicate = import_deduplicator::is_duplicate_import_from(
                         bundler,
                         import_from,
-                        final_body,
-                        python_version,
+                        earlier_imports,
                     );
 
                     if duplicate {
@@ -265,8 +276,11 @@ impl EntryModulePhase {
                     }
                 }
                 Stmt::Import(import_stmt) => {
-                    let duplicate =
-                        import_deduplicator::is_duplicate_import(bundler, import_stmt, final_body);
+                    let duplicate = import_deduplicator::is_duplicate_import(
+                        bundler,
+                        import_stmt,
+                        earlier_imports,
+                    );
 
                     if !duplicate {
                         entry_statements.push(stmt.clone());
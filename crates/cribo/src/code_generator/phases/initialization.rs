@@ -10,16 +10,23 @@ use ruff_python_ast::Stmt;
 use crate::code_generator::{
     bundler::Bundler,
     context::{BundleParams, InitializationResult},
+    profiling::{PhaseClock, impl_phase_timer},
 };
 
 /// Initialization phase handler (stateless)
 #[derive(Default)]
-pub(crate) struct InitializationPhase;
+pub(crate) struct InitializationPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(InitializationPhase, "Initialization");
 
 impl InitializationPhase {
     /// Create a new initialization phase
     pub(crate) const fn new() -> Self {
-        Self
+        Self {
+            clock: PhaseClock::new(),
+        }
     }
 
     /// Execute the initialization phase
@@ -115,6 +122,27 @@ mod tests {
         assert!(matches!(stmts[0], Stmt::ImportFrom(_)));
     }
 
+    #[test]
+    fn test_generate_future_import_statements_passes_every_feature_through() {
+        let source = "from __future__ import generator_stop\n";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = ruff_python_codegen::Stylist::from_tokens(parsed.tokens(), source);
+
+        let mut future_imports = FxIndexSet::default();
+        future_imports.insert("generator_stop".to_owned());
+        future_imports.insert("barry_as_FLUFL".to_owned());
+        future_imports.insert("annotations".to_owned());
+        let result = InitializationResult { future_imports };
+
+        let stmts = generate_future_import_statements(&result);
+
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(
+            ruff_python_codegen::Generator::from(&stylist).stmt(&stmts[0]),
+            "from __future__ import annotations, barry_as_FLUFL, generator_stop"
+        );
+    }
+
     #[test]
     fn test_future_imports_deterministic_ordering() {
         let mut future_imports = FxIndexSet::default();
@@ -14,6 +14,7 @@ use crate::{
     code_generator::{
         bundler::Bundler,
         context::{BundleParams, SemanticContext},
+        namespace_flattening::flatten_namespaces,
         phases::{
             classification::ClassificationPhase,
             entry_module::EntryModulePhase,
@@ -21,6 +22,7 @@ use crate::{
             post_processing::PostProcessingPhase,
             processing::ProcessingPhase,
         },
+        profiling::PhaseTimer,
     },
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
@@ -52,8 +54,11 @@ impl PhaseOrchestrator {
 
         // Phase 1: Initialization
         log::debug!("[Orchestrator] Phase 1: Initialization");
-        let init_phase = InitializationPhase::new();
+        let mut init_phase = InitializationPhase::new();
+        init_phase.start();
         let init_result = init_phase.execute(bundler, params);
+        init_phase.end();
+        bundler.record_phase(&init_phase);
 
         // Add future imports to final body
         let future_import_stmts = generate_future_import_statements(&init_result);
@@ -65,8 +70,16 @@ impl PhaseOrchestrator {
 
         // Phase 3: Classification
         log::debug!("[Orchestrator] Phase 3: Classification");
-        let classification_phase = ClassificationPhase::new();
-        let classification = classification_phase.execute(bundler, &modules, params.python_version);
+        let mut classification_phase = ClassificationPhase::new();
+        classification_phase.start();
+        let classification = classification_phase.execute(
+            bundler,
+            &modules,
+            params.python_version,
+            params.no_inline,
+        );
+        classification_phase.end();
+        bundler.record_phase(&classification_phase);
 
         // Phase 4: Symbol Rename Collection
         log::debug!("[Orchestrator] Phase 4: Symbol Rename Collection");
@@ -80,6 +93,11 @@ impl PhaseOrchestrator {
         // Handle entry module symbol renaming to avoid namespace collisions
         Self::handle_entry_symbol_renaming(bundler, &modules, &mut symbol_renames);
 
+        // Surface plan inconsistencies before they turn into broken generated code
+        for error in bundler.validate_plan(&symbol_renames) {
+            log::warn!("Inconsistent bundling plan: {error}");
+        }
+
         // Phase 5: Global Symbol Collection
         log::debug!("[Orchestrator] Phase 5: Global Symbol Collection");
         let modules_vec: Vec<(ModuleId, &ModModule, &std::path::Path, &str)> = modules
@@ -90,7 +108,8 @@ impl PhaseOrchestrator {
 
         // Phase 6: Main Processing Loop
         log::debug!("[Orchestrator] Phase 6: Processing");
-        let processing_phase = ProcessingPhase::new();
+        let mut processing_phase = ProcessingPhase::new();
+        processing_phase.start();
         let (processing_stmts, _processed_modules) = processing_phase.execute(
             bundler,
             params,
@@ -99,13 +118,18 @@ impl PhaseOrchestrator {
             &mut symbol_renames,
             &mut global_symbols,
         );
+        processing_phase.end();
+        bundler.record_phase(&processing_phase);
         final_body.extend(processing_stmts);
 
         // Phase 7: Entry Module Processing
         log::debug!("[Orchestrator] Phase 7: Entry Module");
-        let entry_phase = EntryModulePhase::new();
+        let mut entry_phase = EntryModulePhase::new();
+        entry_phase.start();
         let entry_result =
             entry_phase.execute(bundler, params, &mut modules, &symbol_renames, &final_body);
+        entry_phase.end();
+        bundler.record_phase(&entry_phase);
 
         let (entry_symbols, entry_renames) = if let Some(result) = entry_result {
             final_body.extend(result.statements);
@@ -116,9 +140,12 @@ impl PhaseOrchestrator {
 
         // Phase 8: Post-Processing
         log::debug!("[Orchestrator] Phase 8: Post-Processing");
-        let post_processing_phase = PostProcessingPhase::new();
+        let mut post_processing_phase = PostProcessingPhase::new();
+        post_processing_phase.start();
         let post_processing_output =
             post_processing_phase.execute(bundler, &entry_symbols, &entry_renames, &final_body);
+        post_processing_phase.end();
+        bundler.record_phase(&post_processing_phase);
 
         // Insert proxy statements after __future__ imports
         PostProcessingPhase::insert_proxy_statements(
@@ -132,8 +159,14 @@ impl PhaseOrchestrator {
         // Add namespace attachments (if any)
         final_body.extend(post_processing_output.namespace_attachments);
 
+        if params.no_namespace_objects {
+            flatten_namespaces(&mut final_body);
+        }
+
         // Phase 9: Finalization
         log::debug!("[Orchestrator] Phase 9: Finalization");
+        // Generated preamble must never precede `__future__` imports
+        PostProcessingPhase::hoist_future_imports(&mut final_body);
         Self::finalize_bundle(bundler, final_body)
     }
 
@@ -8,18 +8,28 @@
 use ruff_python_ast::Stmt;
 
 use crate::{
-    code_generator::{bundler::Bundler, context::PostProcessingResult},
+    code_generator::{
+        bundler::Bundler,
+        context::PostProcessingResult,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
     types::{FxIndexMap, FxIndexSet},
 };
 
 /// Post-processing phase handler (stateless)
 #[derive(Default)]
-pub(crate) struct PostProcessingPhase;
+pub(crate) struct PostProcessingPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(PostProcessingPhase, "Post-processing");
 
 impl PostProcessingPhase {
     /// Create a new post-processing phase
     pub(crate) const fn new() -> Self {
-        Self
+        Self {
+            clock: PhaseClock::new(),
+        }
     }
 
     /// Execute the post-processing phase
@@ -208,6 +218,38 @@ impl PostProcessingPhase {
             final_body.insert(insert_position + i, stmt);
         }
     }
+
+    /// Move every top-level `__future__` import to the front of the bundle
+    ///
+    /// Python rejects `from __future__` imports that follow any statement other than the module
+    /// docstring, so this runs after all generated preamble has been assembled. The relative
+    /// order of the moved imports and of the remaining statements is preserved.
+    pub(crate) fn hoist_future_imports(final_body: &mut Vec<Stmt>) {
+        let is_future_import = |stmt: &Stmt| {
+            matches!(
+                stmt,
+                Stmt::ImportFrom(import_from)
+                    if import_from.level == 0
+                        && import_from.module.as_deref() == Some("__future__")
+            )
+        };
+        let docstring_len = usize::from(matches!(
+            final_body.first(),
+            Some(Stmt::Expr(expr)) if expr.value.is_string_literal_expr()
+        ));
+
+        let (future_imports, rest): (Vec<_>, Vec<_>) = final_body
+            .drain(docstring_len..)
+            .partition(|stmt| is_future_import(stmt));
+        if !future_imports.is_empty() {
+            log::debug!(
+                "Hoisting {} __future__ import(s) to the top of the bundle",
+                future_imports.len()
+            );
+        }
+        final_body.extend(future_imports);
+        final_body.extend(rest);
+    }
 }
 
 #[cfg(test)]
@@ -302,4 +344,27 @@ mod tests {
         // Proxy should be inserted at position 0 (no __future__ imports)
         assert_eq!(final_body.len(), 2);
     }
+
+    #[test]
+    fn test_hoist_future_imports_after_docstring() {
+        let source = "\"\"\"Docstring.\"\"\"\nimport types\nfrom __future__ import annotations\nx \
+                      = 1\nfrom __future__ import division\n";
+        let mut final_body = ruff_python_parser::parse_module(source)
+            .expect("Test code should parse successfully")
+            .into_syntax()
+            .body;
+
+        PostProcessingPhase::hoist_future_imports(&mut final_body);
+
+        let future_names: Vec<&str> = final_body[1..3]
+            .iter()
+            .filter_map(Stmt::as_import_from_stmt)
+            .filter(|import_from| import_from.module.as_deref() == Some("__future__"))
+            .map(|import_from| import_from.names[0].name.as_str())
+            .collect();
+        assert_eq!(future_names, ["annotations", "division"]);
+        assert!(final_body[0].is_expr_stmt());
+        assert!(final_body[3].is_import_stmt());
+        assert!(final_body[4].is_assign_stmt());
+    }
 }
@@ -16,14 +16,23 @@ use ruff_python_ast::Stmt;
 
 use crate::{
     analyzers::module_classifier::ClassificationResult,
-    code_generator::{bundler::Bundler, context::BundleParams},
+    code_generator::{
+        bundler::Bundler,
+        context::BundleParams,
+        profiling::{PhaseClock, impl_phase_timer},
+    },
+    dependency_graph::ItemType,
     resolver::ModuleId,
     types::{FxIndexMap, FxIndexSet},
 };
 
 /// Processing phase handler (stateless)
 #[derive(Default)]
-pub(crate) struct ProcessingPhase;
+pub(crate) struct ProcessingPhase {
+    clock: PhaseClock,
+}
+
+impl_phase_timer!(ProcessingPhase, "Processing");
 
 /// Context for SCC group processing
 #[derive(Debug)]
@@ -35,7 +44,9 @@ struct CircularGroupContext {
 impl ProcessingPhase {
     /// Create a new processing phase
     pub(crate) const fn new() -> Self {
-        Self
+        Self {
+            clock: PhaseClock::new(),
+        }
     }
 
     /// Execute the processing phase
@@ -58,6 +69,13 @@ impl ProcessingPhase {
         symbol_renames: &mut FxIndexMap<ModuleId, FxIndexMap<String, String>>,
         global_symbols: &mut FxIndexSet<String>,
     ) -> (Vec<Stmt>, FxIndexSet<ModuleId>) {
+        // Drop modules that tree-shaking emptied before anything is emitted for them
+        let pruned_modules = if params.prune_unused_modules {
+            Self::prune_unused_modules(bundler, params)
+        } else {
+            FxIndexSet::default()
+        };
+
         // Analyze wrapper dependencies
         Self::analyze_wrapper_dependencies(bundler, classification, modules);
 
@@ -69,11 +87,13 @@ impl ProcessingPhase {
             .inlinable_modules
             .iter()
             .map(|(id, _, _, _)| *id)
+            .filter(|id| !pruned_modules.contains(id))
             .collect();
         let wrapper_set: FxIndexSet<ModuleId> = classification
             .wrapper_modules
             .iter()
             .map(|(id, _, _, _)| *id)
+            .filter(|id| !pruned_modules.contains(id))
             .collect();
 
         let mut all_inlined_stmts = Vec::new();
@@ -164,6 +184,125 @@ impl ProcessingPhase {
         (all_inlined_stmts, processed_modules)
     }
 
+    /// Remove modules whose every symbol was tree-shaken from the bundle
+    ///
+    /// A module is only pruned when leaving it out is unobservable: it has no side effects, is
+    /// not part of a cycle, has no bundled submodules and no module imports it as a module
+    /// object. Pruned modules are dropped from the module sets and the wrapper registry, so no
+    /// empty namespace or init function is emitted for them.
+    fn prune_unused_modules(
+        bundler: &mut Bundler<'_>,
+        params: &BundleParams<'_>,
+    ) -> FxIndexSet<ModuleId> {
+        let (Some(shaker), Some(keep_symbols)) =
+            (params.tree_shaker, &bundler.tree_shaking_keep_symbols)
+        else {
+            return FxIndexSet::default();
+        };
+        let module_objects = Self::modules_imported_as_objects(bundler, params);
+
+        let candidates: FxIndexSet<ModuleId> = bundler
+            .inlined_modules
+            .iter()
+            .chain(&bundler.wrapper_modules)
+            .copied()
+            .filter(|module_id| {
+                !module_id.is_entry()
+                    && keep_symbols.get(module_id).is_none_or(FxIndexSet::is_empty)
+                    && !shaker.module_has_side_effects(*module_id)
+                    && !bundler.circular_modules.contains(module_id)
+                    && !module_objects.contains(module_id)
+            })
+            .collect();
+
+        // A package stays as long as one of its submodules is bundled
+        let is_kept = |module_id: &ModuleId| {
+            !candidates.contains(module_id)
+                && (bundler.inlined_modules.contains(module_id)
+                    || bundler.wrapper_modules.contains(module_id))
+        };
+        let pruned: FxIndexSet<ModuleId> = candidates
+            .iter()
+            .copied()
+            .filter(|&module_id| {
+                let Some(module_name) = params.resolver.get_module_name(module_id) else {
+                    return false;
+                };
+                !params
+                    .resolver
+                    .find_submodules(&module_name)
+                    .iter()
+                    .any(|(submodule_id, _)| is_kept(submodule_id))
+            })
+            .collect();
+
+        for module_id in &pruned {
+            bundler.bundled_modules.shift_remove(module_id);
+            bundler.inlined_modules.shift_remove(module_id);
+            bundler.wrapper_modules.shift_remove(module_id);
+            bundler.module_synthetic_names.shift_remove(module_id);
+            bundler.module_init_functions.shift_remove(module_id);
+            log::info!(
+                "Pruning module '{}': all of its symbols were tree-shaken",
+                params
+                    .resolver
+                    .get_module_name(*module_id)
+                    .unwrap_or_else(|| format!("module_{}", module_id.as_u32()))
+            );
+        }
+        pruned
+    }
+
+    /// Modules bound as module objects by `import a.b` or `from a import b`
+    fn modules_imported_as_objects(
+        bundler: &Bundler<'_>,
+        params: &BundleParams<'_>,
+    ) -> FxIndexSet<ModuleId> {
+        let mut module_objects = FxIndexSet::default();
+        for module in params.graph.modules.values() {
+            for item in module.items.values() {
+                match &item.item_type {
+                    ItemType::Import {
+                        module: imported, ..
+                    } => {
+                        // `import a.b.c` makes `a`, `a.b` and `a.b.c` reachable
+                        let mut name = String::new();
+                        for part in imported.split('.') {
+                            if !name.is_empty() {
+                                name.push('.');
+                            }
+                            name.push_str(part);
+                            module_objects.extend(bundler.get_module_id(&name));
+                        }
+                    }
+                    ItemType::FromImport {
+                        module: imported,
+                        names,
+                        level,
+                        ..
+                    } => {
+                        let resolved = params.tree_shaker.map_or_else(
+                            || imported.clone(),
+                            |shaker| {
+                                shaker.resolve_import_module_name(
+                                    module.module_id,
+                                    imported,
+                                    *level,
+                                )
+                            },
+                        );
+                        for (name, _) in names {
+                            module_objects
+                                .extend(bundler.get_module_id(&format!("{resolved}.{name}")));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        module_objects
+    }
+
     /// Analyze wrapper module dependencies
     ///
     /// This method performs critical dependency analysis to determine which wrapper
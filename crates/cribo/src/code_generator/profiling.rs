@@ -0,0 +1,206 @@
+//! Phase Profiling
+//!
+//! Every bundling phase (the phases in `phases/` and the init function phases in
+//! `init_function/`) implements `PhaseTimer`, so `--profile` can time them uniformly.
+//! Phases that run once per wrapper module accumulate their runs, and the
+//! `ProfileCollector` merges the reports of all phase instances by name into one summary.
+
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::types::FxIndexMap;
+
+/// Timing of a single phase, accumulated over all of its runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PhaseReport {
+    /// Human readable phase name
+    pub name: &'static str,
+    /// Number of completed runs
+    pub runs: u32,
+    /// Total time spent in the phase across all runs
+    pub elapsed: Duration,
+}
+
+/// Stopwatch state embedded in every phase struct
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PhaseClock {
+    started: Option<Instant>,
+    runs: u32,
+    elapsed: Duration,
+}
+
+impl PhaseClock {
+    /// Create a clock that has not been started yet
+    pub(crate) const fn new() -> Self {
+        Self {
+            started: None,
+            runs: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+/// Common profiling interface of the bundling phases
+///
+/// Implementors only expose their name and clock; `start`, `end` and `report` are shared.
+pub(crate) trait PhaseTimer {
+    /// Name the phase is reported under
+    const NAME: &'static str;
+
+    /// The phase's clock
+    fn clock(&self) -> &PhaseClock;
+
+    /// The phase's clock, mutably
+    fn clock_mut(&mut self) -> &mut PhaseClock;
+
+    /// Start timing a run of the phase
+    fn start(&mut self) {
+        self.clock_mut().started = Some(Instant::now());
+    }
+
+    /// Stop timing the current run and return its duration
+    ///
+    /// Returns `Duration::ZERO` if the phase was not started.
+    fn end(&mut self) -> Duration {
+        let clock = self.clock_mut();
+        let Some(started) = clock.started.take() else {
+            return Duration::ZERO;
+        };
+        let elapsed = started.elapsed();
+        clock.runs += 1;
+        clock.elapsed += elapsed;
+        elapsed
+    }
+
+    /// Summarize the runs timed so far
+    fn report(&self) -> PhaseReport {
+        let clock = self.clock();
+        PhaseReport {
+            name: Self::NAME,
+            runs: clock.runs,
+            elapsed: clock.elapsed,
+        }
+    }
+
+    /// Time a single run of the phase around `run`
+    fn time<R>(&mut self, run: impl FnOnce() -> R) -> R {
+        self.start();
+        let result = run();
+        self.end();
+        result
+    }
+}
+
+/// Implement `PhaseTimer` for a phase struct with a `clock: PhaseClock` field
+macro_rules! impl_phase_timer {
+    ($phase:ty, $name:literal) => {
+        impl $crate::code_generator::profiling::PhaseTimer for $phase {
+            const NAME: &'static str = $name;
+
+            fn clock(&self) -> &$crate::code_generator::profiling::PhaseClock {
+                &self.clock
+            }
+
+            fn clock_mut(&mut self) -> &mut $crate::code_generator::profiling::PhaseClock {
+                &mut self.clock
+            }
+        }
+    };
+}
+
+pub(crate) use impl_phase_timer;
+
+/// Collects the reports of all phases for the `--profile` summary
+#[derive(Debug, Default)]
+pub(crate) struct ProfileCollector {
+    /// Reports merged by phase name, in the order phases first reported
+    reports: FxIndexMap<&'static str, PhaseReport>,
+}
+
+impl ProfileCollector {
+    /// Merge the runs of `phase` into the summary
+    ///
+    /// Instances of the same phase (e.g. one per wrapper module) share a row.
+    pub(crate) fn collect<P: PhaseTimer>(&mut self, phase: &P) {
+        let report = phase.report();
+        if report.runs == 0 {
+            return;
+        }
+        self.reports
+            .entry(report.name)
+            .and_modify(|existing| {
+                existing.runs += report.runs;
+                existing.elapsed += report.elapsed;
+            })
+            .or_insert(report);
+    }
+
+    /// Render the summary as a table with one row per phase
+    pub(crate) fn render(&self) -> String {
+        let name_width = self
+            .reports
+            .keys()
+            .map(|name| name.len())
+            .chain(std::iter::once("Phase".len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "{:<name_width$}  {:>6}  {:>12}  {:>12}",
+            "Phase", "Runs", "Total (ms)", "Avg (ms)"
+        );
+        for report in self.reports.values() {
+            let total_ms = report.elapsed.as_secs_f64() * 1000.0;
+            let _ = writeln!(
+                output,
+                "{:<name_width$}  {:>6}  {:>12.3}  {:>12.3}",
+                report.name,
+                report.runs,
+                total_ms,
+                total_ms / f64::from(report.runs)
+            );
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestPhase {
+        clock: PhaseClock,
+    }
+
+    impl_phase_timer!(TestPhase, "Test");
+
+    #[test]
+    fn test_end_without_start_is_not_counted() {
+        let mut phase = TestPhase::default();
+        assert_eq!(phase.end(), Duration::ZERO);
+        assert_eq!(phase.report().runs, 0);
+    }
+
+    #[test]
+    fn test_collector_merges_runs_by_name() {
+        let mut collector = ProfileCollector::default();
+        for _ in 0..2 {
+            let mut phase = TestPhase::default();
+            phase.time(|| ());
+            collector.collect(&phase);
+        }
+        collector.collect(&TestPhase::default());
+
+        let rendered = collector.render();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Phase"));
+        assert!(lines[1].starts_with("Test"));
+        assert_eq!(lines[1].split_whitespace().nth(1), Some("2"));
+    }
+}
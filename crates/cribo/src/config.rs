@@ -1,5 +1,5 @@
 use std::{
-    env,
+    env, fmt,
     path::{Path, PathBuf},
 };
 
@@ -12,8 +12,150 @@ use crate::{
     dirs::{system_config_file, user_cribo_config_dir},
 };
 
+/// How circular dependencies between bundled modules are resolved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CycleStrategy {
+    /// Pick a resolution based on the kind of cycle
+    #[default]
+    Auto,
+    /// Move imports that take part in the cycle into the functions using them
+    FunctionScope,
+    /// Rely only on the lazy initialization of wrapper modules
+    Lazy,
+    /// Fail the build when the cycle is detected
+    Error,
+}
+
+impl std::str::FromStr for CycleStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "function-scope" => Ok(Self::FunctionScope),
+            "lazy" => Ok(Self::Lazy),
+            "error" => Ok(Self::Error),
+            _ => Err(anyhow!(
+                "Invalid cycle strategy '{value}'. Supported strategies: auto, function-scope, \
+                 lazy, error"
+            )),
+        }
+    }
+}
+
+/// Cycle strategy for the cycles containing all of the given modules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CycleOverride {
+    pub modules: Vec<String>,
+    pub strategy: CycleStrategy,
+}
+
+/// An invalid setting in a config file, located by file and 1-based line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// `target-version` is not one of the supported Python versions
+    InvalidPythonVersion {
+        path: PathBuf,
+        line: Option<usize>,
+        value: String,
+    },
+    /// A key that is not a known setting
+    UnknownKey {
+        path: PathBuf,
+        line: Option<usize>,
+        key: String,
+    },
+    /// A setting whose value has the wrong type or is not one of the allowed values
+    TypeMismatch {
+        path: PathBuf,
+        line: Option<usize>,
+        key: Option<String>,
+        message: String,
+    },
+}
+
+impl ConfigError {
+    /// Classify a deserialization error of the config file at `path` with `content`
+    fn from_toml(path: &Path, content: &str, error: &toml::de::Error) -> Self {
+        let line = error.span().map(|span| line_of_offset(content, span.start));
+        let message = error.message().trim_end();
+        if let Some(rest) = message.strip_prefix("unknown field `")
+            && let Some((key, _)) = rest.split_once('`')
+        {
+            return Self::UnknownKey {
+                path: path.to_path_buf(),
+                line,
+                key: key.to_owned(),
+            };
+        }
+        Self::TypeMismatch {
+            path: path.to_path_buf(),
+            line,
+            key: line.and_then(|line| key_on_line(content, line)),
+            message: message.to_owned(),
+        }
+    }
+
+    fn location(path: &Path, line: Option<usize>) -> String {
+        match line {
+            Some(line) => format!("{}:{line}", path.display()),
+            None => path.display().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPythonVersion { path, line, value } => write!(
+                f,
+                "{}: invalid target-version '{value}'. Supported versions: py38, py39, py310, \
+                 py311, py312, py313",
+                Self::location(path, *line)
+            ),
+            Self::UnknownKey { path, line, key } => write!(
+                f,
+                "{}: unknown setting '{key}'",
+                Self::location(path, *line)
+            ),
+            Self::TypeMismatch {
+                path,
+                line,
+                key: Some(key),
+                message,
+            } => write!(
+                f,
+                "{}: invalid value for '{key}': {message}",
+                Self::location(path, *line)
+            ),
+            Self::TypeMismatch {
+                path,
+                line,
+                key: None,
+                message,
+            } => write!(f, "{}: {message}", Self::location(path, *line)),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// 1-based line containing the byte `offset` of `content`
+fn line_of_offset(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Key assigned on the 1-based `line` of `content` (`key = value`)
+fn key_on_line(content: &str, line: usize) -> Option<String> {
+    let (key, _) = content.lines().nth(line - 1)?.split_once('=')?;
+    let key = key.trim().trim_matches('"');
+    (!key.is_empty()).then(|| key.to_owned())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     /// Source directories to scan for first-party modules
     pub src: Vec<PathBuf>,
@@ -38,6 +180,46 @@ pub struct Config {
 
     /// Whether to enable tree-shaking to remove unused code
     pub tree_shake: bool,
+
+    /// Whether dynamic imports that cannot be resolved statically are errors instead of warnings
+    pub fail_on_dynamic_imports: bool,
+
+    /// Whether every first-party module is bundled through a wrapper init function instead of
+    /// being inlined (debugging aid for isolating inliner issues)
+    pub no_inline: bool,
+
+    /// Whether modules whose every symbol was tree-shaken are left out of the bundle entirely
+    pub prune_unused_modules: bool,
+
+    /// Whether inlined modules accessed as modules are bound to flat names instead of
+    /// namespace objects
+    pub no_namespace_objects: bool,
+
+    /// Whether imports the entry module repeats from earlier in the bundle are kept instead
+    /// of being removed (debugging aid for isolating import deduplication issues)
+    pub no_deduplicate_imports: bool,
+
+    /// Resolution strategy for circular dependencies
+    pub cycle_strategy: CycleStrategy,
+
+    /// Per-cycle strategies, taking precedence over `cycle_strategy`
+    pub cycle_overrides: Vec<CycleOverride>,
+
+    /// Fixed strategy (`lazy` or `function-scope`) applied to every circular dependency
+    /// without classifying the cycles first
+    pub ignore_circular_deps: Option<CycleStrategy>,
+
+    /// Standard library directory whose pure Python modules are bundled instead of imported
+    /// from the running interpreter
+    pub embed_stdlib: Option<PathBuf>,
+
+    /// Whether `sys.version_info` guards are replaced with the branch taken on the target
+    /// version instead of being kept in the bundle
+    pub resolve_version_guards: bool,
+
+    /// Python version (e.g. "py38") whose unsupported syntax is lowered in the bundle
+    /// Supports "py37" in addition to the target versions
+    pub output_python_version: Option<String>,
 }
 
 impl Default for Config {
@@ -50,6 +232,17 @@ impl Default for Config {
             preserve_type_hints: true,
             target_version: "py310".to_owned(),
             tree_shake: true, // Tree-shaking enabled by default
+            fail_on_dynamic_imports: false,
+            no_inline: false,
+            prune_unused_modules: false,
+            no_namespace_objects: false,
+            no_deduplicate_imports: false,
+            cycle_strategy: CycleStrategy::Auto,
+            cycle_overrides: Vec::new(),
+            ignore_circular_deps: None,
+            embed_stdlib: None,
+            resolve_version_guards: false,
+            output_python_version: None,
         }
     }
 }
@@ -79,6 +272,21 @@ impl Combine for Config {
             preserve_type_hints: self.preserve_type_hints,
             target_version: self.target_version,
             tree_shake: self.tree_shake,
+            fail_on_dynamic_imports: self.fail_on_dynamic_imports,
+            no_inline: self.no_inline,
+            prune_unused_modules: self.prune_unused_modules,
+            no_namespace_objects: self.no_namespace_objects,
+            no_deduplicate_imports: self.no_deduplicate_imports,
+            cycle_strategy: self.cycle_strategy,
+            cycle_overrides: if self.cycle_overrides.is_empty() {
+                other.cycle_overrides
+            } else {
+                self.cycle_overrides
+            },
+            ignore_circular_deps: self.ignore_circular_deps.or(other.ignore_circular_deps),
+            embed_stdlib: self.embed_stdlib.or(other.embed_stdlib),
+            resolve_version_guards: self.resolve_version_guards,
+            output_python_version: self.output_python_version.or(other.output_python_version),
         }
     }
 }
@@ -93,6 +301,16 @@ pub(crate) struct EnvConfig {
     pub preserve_type_hints: Option<bool>,
     pub target_version: Option<String>,
     pub tree_shake: Option<bool>,
+    pub fail_on_dynamic_imports: Option<bool>,
+    pub no_inline: Option<bool>,
+    pub prune_unused_modules: Option<bool>,
+    pub no_namespace_objects: Option<bool>,
+    pub no_deduplicate_imports: Option<bool>,
+    pub cycle_strategy: Option<CycleStrategy>,
+    pub ignore_circular_deps: Option<CycleStrategy>,
+    pub embed_stdlib: Option<PathBuf>,
+    pub resolve_version_guards: Option<bool>,
+    pub output_python_version: Option<String>,
 }
 
 impl EnvConfig {
@@ -159,6 +377,58 @@ impl EnvConfig {
             config.tree_shake = parse_bool(&tree_shake_str);
         }
 
+        // CRIBO_FAIL_ON_DYNAMIC_IMPORTS - boolean flag
+        if let Ok(fail_str) = env::var("CRIBO_FAIL_ON_DYNAMIC_IMPORTS") {
+            config.fail_on_dynamic_imports = parse_bool(&fail_str);
+        }
+
+        // CRIBO_NO_INLINE - boolean flag
+        if let Ok(no_inline_str) = env::var("CRIBO_NO_INLINE") {
+            config.no_inline = parse_bool(&no_inline_str);
+        }
+
+        // CRIBO_PRUNE_UNUSED_MODULES - boolean flag
+        if let Ok(prune_str) = env::var("CRIBO_PRUNE_UNUSED_MODULES") {
+            config.prune_unused_modules = parse_bool(&prune_str);
+        }
+
+        // CRIBO_NO_NAMESPACE_OBJECTS - boolean flag
+        if let Ok(no_namespace_objects_str) = env::var("CRIBO_NO_NAMESPACE_OBJECTS") {
+            config.no_namespace_objects = parse_bool(&no_namespace_objects_str);
+        }
+
+        // CRIBO_NO_DEDUPLICATE_IMPORTS - boolean flag
+        if let Ok(no_deduplicate_imports_str) = env::var("CRIBO_NO_DEDUPLICATE_IMPORTS") {
+            config.no_deduplicate_imports = parse_bool(&no_deduplicate_imports_str);
+        }
+
+        // CRIBO_CYCLE_STRATEGY - circular dependency resolution strategy
+        if let Ok(cycle_strategy_str) = env::var("CRIBO_CYCLE_STRATEGY") {
+            config.cycle_strategy = cycle_strategy_str.parse().ok();
+        }
+
+        // CRIBO_IGNORE_CIRCULAR_DEPS - fixed strategy for all circular dependencies
+        if let Ok(ignore_circular_deps_str) = env::var("CRIBO_IGNORE_CIRCULAR_DEPS") {
+            config.ignore_circular_deps = ignore_circular_deps_str.parse().ok();
+        }
+
+        // CRIBO_EMBED_STDLIB - standard library directory to bundle pure Python modules from
+        if let Ok(embed_stdlib_str) = env::var("CRIBO_EMBED_STDLIB")
+            && !embed_stdlib_str.trim().is_empty()
+        {
+            config.embed_stdlib = Some(PathBuf::from(embed_stdlib_str.trim()));
+        }
+
+        // CRIBO_RESOLVE_VERSION_GUARDS - boolean flag
+        if let Ok(resolve_version_guards_str) = env::var("CRIBO_RESOLVE_VERSION_GUARDS") {
+            config.resolve_version_guards = parse_bool(&resolve_version_guards_str);
+        }
+
+        // CRIBO_OUTPUT_PYTHON_VERSION - Python version the bundle's syntax is lowered for
+        if let Ok(output_python_version) = env::var("CRIBO_OUTPUT_PYTHON_VERSION") {
+            config.output_python_version = Some(output_python_version);
+        }
+
         config
     }
 
@@ -185,6 +455,36 @@ impl EnvConfig {
         if let Some(tree_shake) = self.tree_shake {
             config.tree_shake = tree_shake;
         }
+        if let Some(fail_on_dynamic_imports) = self.fail_on_dynamic_imports {
+            config.fail_on_dynamic_imports = fail_on_dynamic_imports;
+        }
+        if let Some(no_inline) = self.no_inline {
+            config.no_inline = no_inline;
+        }
+        if let Some(prune_unused_modules) = self.prune_unused_modules {
+            config.prune_unused_modules = prune_unused_modules;
+        }
+        if let Some(no_namespace_objects) = self.no_namespace_objects {
+            config.no_namespace_objects = no_namespace_objects;
+        }
+        if let Some(no_deduplicate_imports) = self.no_deduplicate_imports {
+            config.no_deduplicate_imports = no_deduplicate_imports;
+        }
+        if let Some(cycle_strategy) = self.cycle_strategy {
+            config.cycle_strategy = cycle_strategy;
+        }
+        if let Some(ignore_circular_deps) = self.ignore_circular_deps {
+            config.ignore_circular_deps = Some(ignore_circular_deps);
+        }
+        if let Some(embed_stdlib) = self.embed_stdlib {
+            config.embed_stdlib = Some(embed_stdlib);
+        }
+        if let Some(resolve_version_guards) = self.resolve_version_guards {
+            config.resolve_version_guards = resolve_version_guards;
+        }
+        if let Some(output_python_version) = self.output_python_version {
+            config.output_python_version = Some(output_python_version);
+        }
         config
     }
 }
@@ -231,22 +531,81 @@ impl Config {
         Ok(())
     }
 
+    /// Parse an output version string to u8 version number
+    /// Supports "py37" -> 7 in addition to the target versions, since the lowered syntax of
+    /// Python 3.8 is what makes a bundle run on 3.7
+    pub fn parse_output_python_version(version_str: &str) -> Result<u8> {
+        if version_str == "py37" {
+            return Ok(7);
+        }
+        Self::parse_target_version(version_str).map_err(|_| {
+            anyhow!(
+                "Invalid output Python version '{version_str}'. Supported versions: py37, py38, \
+                 py39, py310, py311, py312, py313"
+            )
+        })
+    }
+
+    /// Get the output Python version as u8, if syntax lowering was requested
+    pub fn output_python_version(&self) -> Result<Option<u8>> {
+        self.output_python_version
+            .as_deref()
+            .map(Self::parse_output_python_version)
+            .transpose()
+    }
+
+    /// Set the output Python version from a string value
+    pub fn set_output_python_version(&mut self, version: String) -> Result<()> {
+        // Validate the version string
+        Self::parse_output_python_version(&version)?;
+        self.output_python_version = Some(version);
+        Ok(())
+    }
+
+    /// Strategy for a cycle made of `modules`: the first override whose modules all take part
+    /// in the cycle, otherwise the global strategy
+    pub fn cycle_strategy_for(&self, modules: &[String]) -> CycleStrategy {
+        self.cycle_overrides
+            .iter()
+            .find(|cycle_override| {
+                cycle_override
+                    .modules
+                    .iter()
+                    .all(|module| modules.contains(module))
+            })
+            .map_or(self.cycle_strategy, |cycle_override| {
+                cycle_override.strategy
+            })
+    }
+
     /// Load a single config file from a path
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        let config: Self = toml::from_str(&content)
+        // Syntax errors are reported as they are; settings are validated below
+        toml::from_str::<toml::Table>(&content)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        let config: Self =
+            toml::from_str(&content).map_err(|err| ConfigError::from_toml(path, &content, &err))?;
 
         // Validate the target version
-        config.python_version().with_context(|| {
-            format!(
-                "Invalid target-version in config file: {}",
-                config.target_version
-            )
-        })?;
+        if config.python_version().is_err() {
+            let line = content
+                .lines()
+                .position(|line| {
+                    line.split_once('=')
+                        .is_some_and(|(key, _)| key.trim() == "target-version")
+                })
+                .map(|index| index + 1);
+            return Err(ConfigError::InvalidPythonVersion {
+                path: path.to_path_buf(),
+                line,
+                value: config.target_version,
+            }
+            .into());
+        }
 
         Ok(config)
     }
@@ -316,6 +675,204 @@ impl Config {
     }
 }
 
+/// A config file setting with the CLI flag and environment variable setting it too
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SettingInfo {
+    /// Key in `cribo.toml`
+    pub key: &'static str,
+    pub value_type: &'static str,
+    pub cli_flag: Option<&'static str>,
+    pub env_var: Option<&'static str>,
+    pub description: &'static str,
+    /// The setting as it is written in `cribo.toml`
+    pub example: &'static str,
+}
+
+/// Every setting of the config file, in the order of `Config`'s fields (`--help-all`)
+pub(crate) const SETTINGS: &[SettingInfo] = &[
+    SettingInfo {
+        key: "src",
+        value_type: "list of paths",
+        cli_flag: None,
+        env_var: Some("CRIBO_SRC"),
+        description: "Source directories to scan for first-party modules; the entry's directory \
+                      is always added",
+        example: r#"src = ["src", "lib"]"#,
+    },
+    SettingInfo {
+        key: "known_first_party",
+        value_type: "list of module names",
+        cli_flag: None,
+        env_var: Some("CRIBO_KNOWN_FIRST_PARTY"),
+        description: "Modules that are always bundled",
+        example: r#"known_first_party = ["mypackage"]"#,
+    },
+    SettingInfo {
+        key: "known_third_party",
+        value_type: "list of module names",
+        cli_flag: None,
+        env_var: Some("CRIBO_KNOWN_THIRD_PARTY"),
+        description: "Modules that are always imported at runtime instead of bundled",
+        example: r#"known_third_party = ["requests"]"#,
+    },
+    SettingInfo {
+        key: "preserve_comments",
+        value_type: "boolean",
+        cli_flag: None,
+        env_var: Some("CRIBO_PRESERVE_COMMENTS"),
+        description: "Keep comments in the bundled output",
+        example: "preserve_comments = false",
+    },
+    SettingInfo {
+        key: "preserve_type_hints",
+        value_type: "boolean",
+        cli_flag: None,
+        env_var: Some("CRIBO_PRESERVE_TYPE_HINTS"),
+        description: "Keep type hints in the bundled output",
+        example: "preserve_type_hints = false",
+    },
+    SettingInfo {
+        key: "target-version",
+        value_type: "string",
+        cli_flag: Some("--target-version"),
+        env_var: Some("CRIBO_TARGET_VERSION"),
+        description: "Target Python version: py38, py39, py310, py311, py312 or py313",
+        example: r#"target-version = "py312""#,
+    },
+    SettingInfo {
+        key: "tree_shake",
+        value_type: "boolean",
+        cli_flag: Some("--no-tree-shake"),
+        env_var: Some("CRIBO_TREE_SHAKE"),
+        description: "Remove module-level symbols the bundle never uses",
+        example: "tree_shake = false",
+    },
+    SettingInfo {
+        key: "fail_on_dynamic_imports",
+        value_type: "boolean",
+        cli_flag: Some("--fail-on-dynamic-imports"),
+        env_var: Some("CRIBO_FAIL_ON_DYNAMIC_IMPORTS"),
+        description: "Fail instead of warning on dynamic imports that cannot be resolved \
+                      statically",
+        example: "fail_on_dynamic_imports = true",
+    },
+    SettingInfo {
+        key: "no_inline",
+        value_type: "boolean",
+        cli_flag: Some("--no-inline"),
+        env_var: Some("CRIBO_NO_INLINE"),
+        description: "Bundle every first-party module through a wrapper init function",
+        example: "no_inline = true",
+    },
+    SettingInfo {
+        key: "prune_unused_modules",
+        value_type: "boolean",
+        cli_flag: Some("--prune-unused-modules"),
+        env_var: Some("CRIBO_PRUNE_UNUSED_MODULES"),
+        description: "Leave out modules whose every symbol was removed by tree-shaking",
+        example: "prune_unused_modules = true",
+    },
+    SettingInfo {
+        key: "no_namespace_objects",
+        value_type: "boolean",
+        cli_flag: Some("--no-namespace-objects"),
+        env_var: Some("CRIBO_NO_NAMESPACE_OBJECTS"),
+        description: "Bind the symbols of inlined modules to flat names instead of namespace \
+                      objects",
+        example: "no_namespace_objects = true",
+    },
+    SettingInfo {
+        key: "no_deduplicate_imports",
+        value_type: "boolean",
+        cli_flag: Some("--no-deduplicate-imports"),
+        env_var: Some("CRIBO_NO_DEDUPLICATE_IMPORTS"),
+        description: "Keep imports the entry module repeats from earlier in the bundle",
+        example: "no_deduplicate_imports = true",
+    },
+    SettingInfo {
+        key: "cycle_strategy",
+        value_type: "string",
+        cli_flag: Some("--cycle-strategy"),
+        env_var: Some("CRIBO_CYCLE_STRATEGY"),
+        description: "Resolution of circular dependencies: auto, function-scope, lazy or error",
+        example: r#"cycle_strategy = "lazy""#,
+    },
+    SettingInfo {
+        key: "cycle_overrides",
+        value_type: "list of tables",
+        cli_flag: None,
+        env_var: None,
+        description: "Strategies for the cycles containing all of the given modules, taking \
+                      precedence over cycle_strategy",
+        example: r#"cycle_overrides = [{ modules = ["pkg.a", "pkg.b"], strategy = "error" }]"#,
+    },
+    SettingInfo {
+        key: "ignore_circular_deps",
+        value_type: "string",
+        cli_flag: Some("--ignore-circular-deps"),
+        env_var: Some("CRIBO_IGNORE_CIRCULAR_DEPS"),
+        description: "Apply this strategy (lazy or function-scope) to every circular dependency \
+                      without classifying the cycles",
+        example: r#"ignore_circular_deps = "lazy""#,
+    },
+    SettingInfo {
+        key: "embed_stdlib",
+        value_type: "path",
+        cli_flag: Some("--embed-stdlib"),
+        env_var: Some("CRIBO_EMBED_STDLIB"),
+        description: "Standard library directory whose pure Python modules are bundled",
+        example: r#"embed_stdlib = "/usr/lib/python3.12""#,
+    },
+    SettingInfo {
+        key: "resolve_version_guards",
+        value_type: "boolean",
+        cli_flag: None,
+        env_var: Some("CRIBO_RESOLVE_VERSION_GUARDS"),
+        description: "Keep only the branch of sys.version_info guards taken on the target version \
+                      (implied by --target-version)",
+        example: "resolve_version_guards = true",
+    },
+    SettingInfo {
+        key: "output_python_version",
+        value_type: "string",
+        cli_flag: Some("--output-python-version"),
+        env_var: Some("CRIBO_OUTPUT_PYTHON_VERSION"),
+        description: "Lower the syntax this Python version (py37 to py313) does not support in \
+                      the bundle",
+        example: r#"output_python_version = "py37""#,
+    },
+];
+
+/// Describe every setting of the config file with its type, default, CLI flag, environment
+/// variable and an example
+pub(crate) fn render_settings_help() -> String {
+    use std::fmt::Write;
+
+    let defaults = toml::Value::try_from(Config::default()).ok();
+    let mut output = String::from(
+        "Config file settings (cribo.toml; environment variables and CLI flags take precedence):\n",
+    );
+    for setting in SETTINGS {
+        let default = defaults
+            .as_ref()
+            .and_then(|defaults| defaults.get(setting.key))
+            .map_or_else(|| "unset".to_owned(), ToString::to_string);
+        let _ = write!(
+            output,
+            "\n  {}\n      {}\n      Type: {}; default: {default}\n",
+            setting.key, setting.description, setting.value_type
+        );
+        if let Some(cli_flag) = setting.cli_flag {
+            let _ = writeln!(output, "      CLI: {cli_flag}");
+        }
+        if let Some(env_var) = setting.env_var {
+            let _ = writeln!(output, "      Environment: {env_var}");
+        }
+        let _ = writeln!(output, "      Example: {}", setting.example);
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -382,6 +939,36 @@ mod tests {
         assert!(Config::parse_target_version("3.10").is_err()); // wrong format
     }
 
+    #[test]
+    fn test_output_python_version_configuration() {
+        // Lowering is off unless an output version is set
+        assert_eq!(
+            Config::default()
+                .output_python_version()
+                .expect("default config should have a valid output version"),
+            None
+        );
+
+        let mut config = Config::default();
+        config
+            .set_output_python_version("py37".to_owned())
+            .expect("py37 should be a valid output version");
+        assert_eq!(
+            config
+                .output_python_version()
+                .expect("config should have a valid output version after setting"),
+            Some(7)
+        );
+
+        assert_eq!(
+            Config::parse_output_python_version("py38").expect("py38 should parse to version 8"),
+            8
+        );
+        assert!(Config::parse_output_python_version("py36").is_err()); // too old
+        assert!(Config::parse_output_python_version("3.8").is_err()); // wrong format
+        assert!(config.set_output_python_version("3.8".to_owned()).is_err());
+    }
+
     #[test]
     fn test_toml_config_loading() {
         // Test loading target-version from TOML config
@@ -409,6 +996,78 @@ src = ["src", "lib"]
         assert!(!config.preserve_comments);
     }
 
+    #[test]
+    fn test_cycle_strategy_overrides_from_toml() {
+        let toml_content = r#"
+cycle_strategy = "function-scope"
+
+[[cycle_overrides]]
+modules = ["pkg.models", "pkg.views"]
+strategy = "error"
+        "#;
+
+        let mut temp_file =
+            NamedTempFile::new().expect("should be able to create temp file for test");
+        temp_file
+            .write_all(toml_content.as_bytes())
+            .expect("should be able to write test config to temp file");
+
+        let config = Config::load_from_file(temp_file.path())
+            .expect("should be able to load valid config from temp file");
+        let cycle = |modules: &[&str]| -> Vec<String> {
+            modules.iter().map(|module| (*module).to_owned()).collect()
+        };
+        assert_eq!(
+            config.cycle_strategy_for(&cycle(&["pkg.views", "pkg.models", "pkg.utils"])),
+            CycleStrategy::Error
+        );
+        assert_eq!(
+            config.cycle_strategy_for(&cycle(&["pkg.models", "pkg.utils"])),
+            CycleStrategy::FunctionScope
+        );
+        assert!("lazy".parse::<CycleStrategy>().is_ok());
+        assert!("eager".parse::<CycleStrategy>().is_err());
+    }
+
+    fn load_error(toml_content: &str) -> ConfigError {
+        let mut temp_file =
+            NamedTempFile::new().expect("should be able to create temp file for config test");
+        temp_file
+            .write_all(toml_content.as_bytes())
+            .expect("should be able to write test config to temp file");
+        Config::load_from_file(temp_file.path())
+            .expect_err("config should be rejected")
+            .downcast::<ConfigError>()
+            .expect("error should be a ConfigError")
+    }
+
+    #[test]
+    fn test_config_errors_report_setting_and_line() {
+        let error = load_error("tree_shake = true\ntarget-version = \"py27\"\n");
+        assert!(matches!(
+            &error,
+            ConfigError::InvalidPythonVersion { line: Some(2), value, .. } if value == "py27"
+        ));
+
+        let error = load_error("tree_shake = true\n\ntree_shaking = false\n");
+        assert!(matches!(
+            &error,
+            ConfigError::UnknownKey { line: Some(3), key, .. } if key == "tree_shaking"
+        ));
+
+        let error = load_error("src = [\"src\"]\npreserve_comments = \"yes\"\n");
+        assert!(matches!(
+            &error,
+            ConfigError::TypeMismatch { line: Some(2), key: Some(key), .. }
+                if key == "preserve_comments"
+        ));
+        assert!(
+            error
+                .to_string()
+                .contains(":2: invalid value for 'preserve_comments'")
+        );
+    }
+
     #[test]
     fn test_invalid_toml_config() {
         // Test invalid target-version in TOML config
@@ -432,4 +1091,40 @@ src = ["src", "lib"]
                 || error_message.contains("Invalid target-version")
         );
     }
+
+    #[test]
+    fn test_settings_describe_every_config_key() {
+        let config = Config {
+            ignore_circular_deps: Some(CycleStrategy::Lazy),
+            embed_stdlib: Some(PathBuf::from("/usr/lib/python3.12")),
+            output_python_version: Some("py37".to_owned()),
+            ..Config::default()
+        };
+        let serialized = toml::Value::try_from(config).expect("config should serialize");
+        let mut keys: Vec<&str> = serialized
+            .as_table()
+            .expect("config should serialize to a table")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        let mut described: Vec<&str> = SETTINGS.iter().map(|setting| setting.key).collect();
+        described.sort_unstable();
+        assert_eq!(keys, described);
+
+        for setting in SETTINGS {
+            let example: toml::Table = toml::from_str(setting.example)
+                .unwrap_or_else(|e| panic!("example of {} should parse: {e}", setting.key));
+            assert!(example.contains_key(setting.key));
+        }
+
+        let help = render_settings_help();
+        assert!(help.contains(
+            "\n  target-version\n      Target Python version: py38, py39, py310, py311, py312 or \
+             py313\n      Type: string; default: \"py310\"\n      CLI: --target-version\n      \
+             Environment: CRIBO_TARGET_VERSION\n      Example: target-version = \"py312\"\n"
+        ));
+        assert!(help.contains("\n  embed_stdlib\n"));
+        assert!(help.contains("Type: path; default: unset\n"));
+    }
 }
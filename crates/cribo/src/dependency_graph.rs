@@ -164,6 +164,12 @@ pub(crate) struct ModuleDepGraph {
     explicit_all_names: FxIndexSet<String>,
     /// Whether the module defines `__all__`, even if it is empty.
     has_explicit_all: bool,
+    /// Module-level descriptor classes, i.e. classes that define or inherit a local
+    /// `__set_name__` hook that runs when an owning class body is executed.
+    descriptor_classes: FxIndexSet<String>,
+    /// Names read by the `dataclasses.KW_ONLY` sentinel field (`_: KW_ONLY`) of module-level
+    /// classes, keyed by class name.
+    kw_only_sentinels: FxIndexMap<String, FxIndexSet<String>>,
     /// Next item ID to allocate
     next_item_id: u32,
 }
@@ -184,6 +190,8 @@ impl ModuleDepGraph {
             non_private_defined_symbol_names: FxIndexSet::default(),
             explicit_all_names: FxIndexSet::default(),
             has_explicit_all: false,
+            descriptor_classes: FxIndexSet::default(),
+            kw_only_sentinels: FxIndexMap::default(),
             next_item_id: 0,
         }
     }
@@ -344,6 +352,38 @@ impl ModuleDepGraph {
         self.has_explicit_all
     }
 
+    /// Record a module-level class as a descriptor class with a `__set_name__` hook.
+    pub(crate) fn mark_descriptor_class(&mut self, class_name: String) {
+        self.descriptor_classes.insert(class_name);
+    }
+
+    /// Get the module-level descriptor classes of this module.
+    pub(crate) const fn descriptor_classes(&self) -> &FxIndexSet<String> {
+        &self.descriptor_classes
+    }
+
+    /// Record the names read by the `KW_ONLY` sentinel field of a module-level class.
+    pub(crate) fn mark_kw_only_sentinel(&mut self, class_name: String, reads: FxIndexSet<String>) {
+        self.kw_only_sentinels.insert(class_name, reads);
+    }
+
+    /// Get the names read by the `KW_ONLY` sentinel field of a class, if it declares one.
+    pub(crate) fn kw_only_sentinel(&self, class_name: &str) -> Option<&FxIndexSet<String>> {
+        self.kw_only_sentinels.get(class_name)
+    }
+
+    /// Names read while the bodies of module-level classes execute (bases, decorators and
+    /// class-level assignments), which is when `__set_name__` hooks of descriptors run.
+    fn class_body_reads(&self) -> impl Iterator<Item = &str> {
+        self.items
+            .values()
+            .filter(|item| {
+                item.containing_scope.is_none()
+                    && matches!(item.item_type, ItemType::ClassDef { .. })
+            })
+            .flat_map(|item| item.read_vars.iter().map(String::as_str))
+    }
+
     /// Get all non-private symbols defined by this module.
     pub(crate) const fn non_private_defined_symbol_names(&self) -> &FxIndexSet<String> {
         &self.non_private_defined_symbol_names
@@ -605,6 +645,19 @@ impl DependencyGraph {
         }
     }
 
+    /// Get all modules that depend on a given module
+    pub(crate) fn get_dependents(&self, module_id: ModuleId) -> Vec<ModuleId> {
+        if let Some(&node_idx) = self.node_indices.get(&module_id) {
+            // Since edges go from dependency to dependent, outgoing edges are dependents
+            self.graph
+                .neighbors_directed(node_idx, petgraph::Direction::Outgoing)
+                .map(|idx| self.graph[idx])
+                .collect()
+        } else {
+            vec![]
+        }
+    }
+
     /// Find all strongly connected components (circular dependencies) using Tarjan's algorithm
     /// This is more efficient than Kosaraju for our use case and provides components in
     /// reverse topological order
@@ -735,21 +788,104 @@ impl DependencyGraph {
             }
 
             // DFS post-order traversal ensures dependency-first ordering within the SCC
+            let mut scc_order = Vec::with_capacity(comp_sorted.len());
             for &nx in &comp_sorted {
                 if let Some(&start) = mini_map.get(&nx) {
                     let mut dfs = DfsPostOrder::new(&mini, start);
                     while let Some(nid) = dfs.next(&mini) {
                         let mid = mini[nid];
                         if visited.insert(mid) {
-                            result.push(mid);
+                            scc_order.push(mid);
                         }
                     }
                 }
             }
+            result.extend(self.order_descriptor_definitions_first(scc_order));
         }
 
         result
     }
+
+    /// Reorder the modules of a cycle so descriptor classes are defined before the classes
+    /// that use them.
+    ///
+    /// A descriptor's `__set_name__` hook runs while the owning class body executes, so the
+    /// module defining the descriptor class must come first even though the import cycle gives
+    /// no preferred order. Modules without such a constraint keep their relative order.
+    fn order_descriptor_definitions_first(&self, scc_order: Vec<ModuleId>) -> Vec<ModuleId> {
+        let constraints = self.descriptor_constraints(&scc_order);
+        if constraints.is_empty() {
+            return scc_order;
+        }
+
+        let mut remaining = scc_order;
+        let mut ordered = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            // Pick the first module whose descriptor definers are all placed; fall back to
+            // the original order if descriptors are used in both directions
+            let next = remaining
+                .iter()
+                .position(|module_id| {
+                    !constraints
+                        .iter()
+                        .any(|(definer, user)| user == module_id && remaining.contains(definer))
+                })
+                .unwrap_or(0);
+            let module_id = remaining.remove(next);
+            if next > 0
+                && let Some(module) = self.modules.get(&module_id)
+            {
+                log::debug!(
+                    "Moving '{}' ahead in its cycle: it defines descriptors used by class bodies \
+                     of modules ordered before it",
+                    module.module_name
+                );
+            }
+            ordered.push(module_id);
+        }
+        ordered
+    }
+
+    /// Collect `(definer, user)` pairs among `module_ids` where a class body in `user` uses a
+    /// descriptor class imported from `definer`
+    fn descriptor_constraints(&self, module_ids: &[ModuleId]) -> Vec<(ModuleId, ModuleId)> {
+        let mut constraints = Vec::new();
+        for &user_id in module_ids {
+            let Some(user) = self.modules.get(&user_id) else {
+                continue;
+            };
+            for name in user.class_body_reads() {
+                let Some(bindings) = user.named_import_bindings_for(name) else {
+                    continue;
+                };
+                for binding in bindings {
+                    let source = binding.module.trim_start_matches('.');
+                    for &definer_id in module_ids {
+                        let Some(definer) = self.modules.get(&definer_id) else {
+                            continue;
+                        };
+                        let from_definer = if binding.level == 0 {
+                            definer.module_name == source
+                        } else {
+                            source.is_empty()
+                                || definer.module_name == source
+                                || definer.module_name.ends_with(&format!(".{source}"))
+                        };
+                        if definer_id != user_id
+                            && from_definer
+                            && definer
+                                .descriptor_classes()
+                                .contains(&binding.original_name)
+                            && !constraints.contains(&(definer_id, user_id))
+                        {
+                            constraints.push((definer_id, user_id));
+                        }
+                    }
+                }
+            }
+        }
+        constraints
+    }
 }
 
 impl Default for DependencyGraph {
@@ -825,6 +961,44 @@ mod tests {
         assert!(!analysis.resolvable_cycles.is_empty());
     }
 
+    #[test]
+    fn test_ignored_cycles_use_fixed_strategy() {
+        let mut graph = DependencyGraph::new();
+
+        let module_a = graph.add_module(
+            ModuleId::new(0),
+            "module_a".to_owned(),
+            &PathBuf::from("module_a.py"),
+        );
+        let module_b = graph.add_module(
+            ModuleId::new(1),
+            "module_b".to_owned(),
+            &PathBuf::from("module_b.py"),
+        );
+        graph.add_module_dependency(module_a, module_b);
+        graph.add_module_dependency(module_b, module_a);
+
+        let analysis = crate::analyzers::dependency_analyzer::assume_cycles_resolvable(
+            &graph,
+            crate::config::CycleStrategy::Lazy,
+        )
+        .expect("lazy strategy should be accepted");
+        assert!(analysis.unresolvable_cycles.is_empty());
+        assert_eq!(analysis.resolvable_cycles.len(), 1);
+        assert!(matches!(
+            analysis.resolvable_cycles[0].suggested_resolution,
+            ResolutionStrategy::Lazy
+        ));
+
+        assert!(
+            crate::analyzers::dependency_analyzer::assume_cycles_resolvable(
+                &graph,
+                crate::config::CycleStrategy::Error,
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_cross_cycle_module_level_reads_classified_as_unresolvable() {
         // Simulate: constants_a.py: `from constants_b import B_VALUE; A_VALUE = B_VALUE + 1`
@@ -947,6 +1121,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cycle_order_places_descriptor_definitions_first() {
+        // descriptors.py: `from owner import Model; class Field: def __set_name__(...): ...`
+        // owner.py:       `from descriptors import Field; class Model: name = Field()`
+        let mut graph = DependencyGraph::new();
+
+        let descriptors = graph.add_module(
+            ModuleId::new(0),
+            "descriptors".to_owned(),
+            &PathBuf::from("descriptors.py"),
+        );
+        let owner = graph.add_module(
+            ModuleId::new(1),
+            "owner".to_owned(),
+            &PathBuf::from("owner.py"),
+        );
+
+        let item = |item_type: ItemType, read_vars: &[&str]| ItemData {
+            item_type,
+            var_decls: FxIndexSet::default(),
+            read_vars: read_vars.iter().map(|&name| name.to_owned()).collect(),
+            eventual_read_vars: FxIndexSet::default(),
+            write_vars: FxIndexSet::default(),
+            eventual_write_vars: FxIndexSet::default(),
+            has_side_effects: false,
+            imported_names: FxIndexSet::default(),
+            reexported_names: FxIndexSet::default(),
+            defined_symbols: FxIndexSet::default(),
+            symbol_dependencies: FxIndexMap::default(),
+            attribute_accesses: FxIndexMap::default(),
+            containing_scope: None,
+        };
+        let from_import = |module: &str, name: &str| ItemType::FromImport {
+            module: module.to_owned(),
+            names: vec![(name.to_owned(), None)],
+            level: 0,
+            is_star: false,
+        };
+
+        if let Some(module) = graph.modules.get_mut(&descriptors) {
+            module.add_item(item(from_import("owner", "Model"), &[]));
+            module.add_item(item(
+                ItemType::ClassDef {
+                    name: "Field".to_owned(),
+                },
+                &[],
+            ));
+            module.mark_descriptor_class("Field".to_owned());
+        }
+        if let Some(module) = graph.modules.get_mut(&owner) {
+            module.add_item(item(from_import("descriptors", "Field"), &[]));
+            module.add_item(item(
+                ItemType::ClassDef {
+                    name: "Model".to_owned(),
+                },
+                &["Field"],
+            ));
+        }
+
+        graph.add_module_dependency(descriptors, owner);
+        graph.add_module_dependency(owner, descriptors);
+
+        assert_eq!(
+            graph.topological_sort_with_cycles(),
+            vec![descriptors, owner]
+        );
+    }
+
     #[test]
     fn test_file_based_deduplication() {
         let mut graph = DependencyGraph::new();
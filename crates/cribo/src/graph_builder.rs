@@ -6,7 +6,9 @@ use ruff_python_ast::{self as ast, Expr, ModModule, Stmt};
 use crate::{
     dependency_graph::{ItemData, ItemType, ModuleDepGraph},
     types::{FxIndexMap, FxIndexSet},
-    visitors::{ExpressionSideEffectDetector, utils::extract_string_list_from_expr},
+    visitors::{
+        ExpressionSideEffectDetector, namedtuple_factory_of, utils::extract_string_list_from_expr,
+    },
 };
 
 /// Context for for statement variable collection
@@ -21,9 +23,9 @@ struct ForStmtContext<'a, 'b> {
 pub(crate) struct GraphBuilder<'a> {
     graph: &'a mut ModuleDepGraph,
     current_scope: ScopeType,
-    /// Track import aliases for importlib detection
+    /// Track import aliases to recognize well-known stdlib callables
     /// Maps local name -> module path (e.g., "il" -> "importlib", "im" ->
-    /// "`importlib.import_module`")
+    /// "`importlib.import_module`", "nt" -> "`collections.namedtuple`")
     import_aliases: FxIndexMap<String, String>,
     python_version: u8,
     /// When inside a function or class, track the scope name
@@ -138,10 +140,10 @@ impl<'a> GraphBuilder<'a> {
 
             log::trace!("Processing import: {module_name} as {local_name}");
 
-            // Track importlib aliases for later detection
-            if module_name == "importlib" {
+            // Track module aliases for later detection (importlib, named tuple factories)
+            if alias.asname.is_some() || !module_name.contains('.') {
                 self.import_aliases
-                    .insert(local_name.to_owned(), "importlib".to_owned());
+                    .insert(local_name.to_owned(), module_name.to_owned());
             }
 
             let mut imported_names = FxIndexSet::default();
@@ -255,10 +257,12 @@ impl<'a> GraphBuilder<'a> {
                     reexported_names.insert(local_name.to_owned());
                 }
 
-                // Track import_module from importlib
-                if module_name == "importlib" && imported_name == "import_module" {
-                    self.import_aliases
-                        .insert(local_name.to_owned(), "importlib.import_module".to_owned());
+                // Track imported names for later detection (import_module, named tuple factories)
+                if import_from.level == 0 {
+                    self.import_aliases.insert(
+                        local_name.to_owned(),
+                        format!("{module_name}.{imported_name}"),
+                    );
                 }
             }
         }
@@ -301,9 +305,15 @@ impl<'a> GraphBuilder<'a> {
         // Collect variables from decorators and type annotations
         let mut read_vars = FxIndexSet::default();
 
-        // Process decorators
+        // Process decorators, keeping attribute accesses so that decorators referenced through
+        // a module (e.g. `@decorators.traced`) keep their definition alive
+        let mut decorator_attribute_accesses = FxIndexMap::default();
         for decorator in &func_def.decorator_list {
-            self.collect_vars_in_expr(&decorator.expression, &mut read_vars);
+            self.collect_vars_in_expr_with_attrs(
+                &decorator.expression,
+                &mut read_vars,
+                &mut decorator_attribute_accesses,
+            );
         }
 
         // Process parameter type annotations and defaults
@@ -324,6 +334,12 @@ impl<'a> GraphBuilder<'a> {
             &mut eventual_write_vars,
             &mut eventual_attribute_accesses,
         );
+        for (base, attrs) in decorator_attribute_accesses {
+            eventual_attribute_accesses
+                .entry(base)
+                .or_default()
+                .extend(attrs);
+        }
 
         // Build symbol dependencies - the function depends on all variables it reads
         let mut symbol_dependencies = FxIndexMap::default();
@@ -505,6 +521,21 @@ impl<'a> GraphBuilder<'a> {
 
         self.graph.add_item(item_data);
 
+        if matches!(self.current_scope, ScopeType::Module) && self.is_descriptor_class(class_def) {
+            log::debug!("Class {class_name} is a descriptor class with a __set_name__ hook");
+            self.graph.mark_descriptor_class(class_name.clone());
+        }
+
+        if matches!(self.current_scope, ScopeType::Module)
+            && let Some(sentinel) = Self::find_kw_only_sentinel(class_def)
+        {
+            let mut sentinel_reads = FxIndexSet::default();
+            self.collect_vars_in_expr(sentinel, &mut sentinel_reads);
+            log::debug!("Class {class_name} declares a KW_ONLY sentinel field");
+            self.graph
+                .mark_kw_only_sentinel(class_name.clone(), sentinel_reads);
+        }
+
         // Process the class body in class scope
         let old_scope = self.current_scope;
         let old_scope_name = self.scope_name.clone();
@@ -519,6 +550,40 @@ impl<'a> GraphBuilder<'a> {
         Ok(())
     }
 
+    /// Check whether a class has a `__set_name__` hook: it defines one or derives from a
+    /// descriptor class defined earlier in the same module
+    fn is_descriptor_class(&self, class_def: &ast::StmtClassDef) -> bool {
+        let defines_set_name = class_def.body.iter().any(|stmt| {
+            matches!(stmt, Stmt::FunctionDef(method) if method.name.as_str() == "__set_name__")
+        });
+        defines_set_name
+            || class_def.arguments.as_ref().is_some_and(|arguments| {
+                arguments.args.iter().any(|base| {
+                    matches!(base, Expr::Name(name)
+                        if self.graph.descriptor_classes().contains(name.id.as_str()))
+                })
+            })
+    }
+
+    /// Find the annotation of a `dataclasses.KW_ONLY` sentinel field (`_: KW_ONLY` or
+    /// `_: dataclasses.KW_ONLY`) in a class body
+    ///
+    /// The sentinel makes all following fields keyword-only, so it is part of the class
+    /// definition even though the field itself is never read.
+    fn find_kw_only_sentinel(class_def: &ast::StmtClassDef) -> Option<&Expr> {
+        class_def.body.iter().find_map(|stmt| {
+            let Stmt::AnnAssign(ann_assign) = stmt else {
+                return None;
+            };
+            let is_sentinel = match ann_assign.annotation.as_ref() {
+                Expr::Name(name) => name.id.as_str() == "KW_ONLY",
+                Expr::Attribute(attr) => attr.attr.as_str() == "KW_ONLY",
+                _ => false,
+            };
+            (is_sentinel && ann_assign.value.is_none()).then_some(ann_assign.annotation.as_ref())
+        })
+    }
+
     /// Process an assignment statement
     fn process_assign(&mut self, assign: &ast::StmtAssign) {
         let mut targets = Vec::new();
@@ -610,7 +675,7 @@ impl<'a> GraphBuilder<'a> {
                                                                * read" */
                 write_vars: FxIndexSet::default(),
                 eventual_write_vars: FxIndexSet::default(),
-                has_side_effects: Self::expression_has_side_effects(&assign.value),
+                has_side_effects: self.value_has_side_effects(&assign.value),
                 imported_names: FxIndexSet::default(),
                 reexported_names,
                 defined_symbols: var_decls,
@@ -653,7 +718,7 @@ impl<'a> GraphBuilder<'a> {
             has_side_effects: ann_assign
                 .value
                 .as_ref()
-                .is_some_and(|v| Self::expression_has_side_effects(v)),
+                .is_some_and(|v| self.value_has_side_effects(v)),
             imported_names: FxIndexSet::default(),
             reexported_names: FxIndexSet::default(),
             defined_symbols: var_decls,
@@ -1152,6 +1217,18 @@ impl<'a> GraphBuilder<'a> {
                 }
             }
             Expr::Call(call) => {
+                // `getattr(module, "name", default)` and `hasattr(module, "name")` read the
+                // attribute like `module.name` does; a name the module does not define is
+                // simply absent from its namespace, so the lookup falls back as unbundled
+                if let Expr::Name(func) = call.func.as_ref()
+                    && matches!(func.id.as_str(), "getattr" | "hasattr")
+                    && let [Expr::Name(base), Expr::StringLiteral(attr), ..] = &*call.arguments.args
+                {
+                    attribute_accesses
+                        .entry(base.id.to_string())
+                        .or_default()
+                        .insert(attr.value.to_str().to_owned());
+                }
                 self.collect_vars_in_expr_with_attrs(&call.func, vars, attribute_accesses);
                 for arg in &call.arguments.args {
                     self.collect_vars_in_expr_with_attrs(arg, vars, attribute_accesses);
@@ -1502,6 +1579,47 @@ impl<'a> GraphBuilder<'a> {
         ExpressionSideEffectDetector::check(expr)
     }
 
+    /// Check if an assigned value has side effects
+    ///
+    /// Creating a named tuple class only evaluates the factory's arguments, so the class stays
+    /// in the bundle exactly when something live reads it (an instance created in live code).
+    fn value_has_side_effects(&self, value: &Expr) -> bool {
+        match value {
+            Expr::Call(call) if self.is_namedtuple_factory_call(call) => {
+                call.arguments
+                    .args
+                    .iter()
+                    .any(Self::expression_has_side_effects)
+                    || call
+                        .arguments
+                        .keywords
+                        .iter()
+                        .any(|keyword| Self::expression_has_side_effects(&keyword.value))
+            }
+            _ => Self::expression_has_side_effects(value),
+        }
+    }
+
+    /// Check if a call goes to `collections.namedtuple` or `typing.NamedTuple` through the
+    /// module's imports
+    fn is_namedtuple_factory_call(&self, call: &ast::ExprCall) -> bool {
+        let qualified_name = match call.func.as_ref() {
+            Expr::Name(name) => self.import_aliases.get(name.id.as_str()).cloned(),
+            Expr::Attribute(attr) => match attr.value.as_ref() {
+                Expr::Name(base) => self
+                    .import_aliases
+                    .get(base.id.as_str())
+                    .map(|module| format!("{module}.{}", attr.attr)),
+                _ => None,
+            },
+            _ => None,
+        };
+        qualified_name
+            .as_deref()
+            .and_then(|name| name.rsplit_once('.'))
+            .is_some_and(|(module, name)| namedtuple_factory_of(module) == Some(name))
+    }
+
     /// Extract a dotted name from an attribute expression
     /// e.g., xml.etree.ElementTree.__name__ -> Some("xml.etree.ElementTree")
     fn extract_dotted_name(&self, attr: &ast::ExprAttribute) -> Option<String> {
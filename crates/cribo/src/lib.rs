@@ -16,6 +16,7 @@ pub mod resolver;
 // Internal modules - these MUST be declared for orchestrator to compile,
 // but since they're pub(crate) and used transitively, no dead_code warnings
 pub(crate) mod analyzers;
+pub(crate) mod artifacts;
 pub(crate) mod ast_builder;
 pub(crate) mod ast_indexer;
 pub(crate) mod code_generator;
@@ -26,10 +27,14 @@ pub(crate) mod import_alias_tracker;
 pub(crate) mod import_rewriter;
 pub(crate) mod module_facts;
 pub(crate) mod python;
+pub(crate) mod self_version;
 pub(crate) mod side_effects;
 pub(crate) mod symbol_conflict_resolver;
+pub(crate) mod syntax_lowering;
 pub(crate) mod transformation_context;
 pub(crate) mod tree_shaking;
 pub(crate) mod types;
 pub(crate) mod util;
+pub(crate) mod version_guards;
 pub(crate) mod visitors;
+pub(crate) mod zoneinfo;
@@ -1,12 +1,13 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Mutex};
 
 use anyhow::anyhow;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use env_logger::Env;
-use log::{debug, info};
+use log::{Level, LevelFilter, Log, Metadata, Record, debug, info};
 
 // Module declarations - keeping only what's needed for the binary
 mod analyzers;
+mod artifacts;
 mod ast_builder;
 mod ast_indexer;
 mod code_generator;
@@ -19,25 +20,34 @@ mod import_alias_tracker;
 mod import_rewriter;
 mod module_facts;
 mod orchestrator;
+mod order_fuzz;
 mod python;
 mod resolver;
+mod self_version;
 mod side_effects;
 mod symbol_conflict_resolver;
+mod syntax_lowering;
 mod transformation_context;
 mod tree_shaking;
 mod types;
 mod util;
+mod version_guards;
 mod visitors;
+mod zoneinfo;
 
+use analyzers::dependency_tree::TreeDirection;
 use config::Config;
-use orchestrator::BundleOrchestrator;
+use orchestrator::{BundleOrchestrator, EmitOptions};
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Entry point Python script
-    #[arg(short, long)]
-    entry: PathBuf,
+    #[arg(short, long, required_unless_present = "help_all")]
+    entry: Option<PathBuf>,
 
     /// Output bundled Python file
     #[arg(short, long, conflicts_with = "stdout")]
@@ -55,6 +65,11 @@ struct Cli {
     #[arg(short, long)]
     config: Option<PathBuf>,
 
+    /// Print help followed by every config file setting with its type, default, CLI flag,
+    /// environment variable and an example
+    #[arg(long)]
+    help_all: bool,
+
     /// Emit requirements.txt with third-party dependencies
     #[arg(long)]
     emit_requirements: bool,
@@ -66,11 +81,295 @@ struct Cli {
     /// Disable tree-shaking optimization (tree-shaking is enabled by default)
     #[arg(long = "no-tree-shake", default_value_t = true, action = clap::ArgAction::SetFalse)]
     tree_shake: bool,
+
+    /// Bundle every first-party module through a wrapper init function instead of inlining it
+    /// (useful for isolating issues caused by inlining)
+    #[arg(long)]
+    no_inline: bool,
+
+    /// Leave out modules whose every symbol was removed by tree-shaking instead of emitting them
+    /// as empty namespaces or init functions
+    #[arg(long)]
+    prune_unused_modules: bool,
+
+    /// Bind the symbols of inlined modules to flat names (`mymod_foo`) instead of namespace
+    /// objects wherever a module is only used through attribute access
+    #[arg(long)]
+    no_namespace_objects: bool,
+
+    /// Keep imports the entry module repeats from earlier in the bundle instead of removing them
+    /// (useful for isolating issues caused by import deduplication)
+    #[arg(long)]
+    no_deduplicate_imports: bool,
+
+    /// Resolution strategy for circular dependencies: auto, function-scope, lazy or error
+    /// (per-cycle overrides can be set with `cycle_overrides` in the config file)
+    #[arg(long, value_name = "STRATEGY")]
+    cycle_strategy: Option<String>,
+
+    /// Skip circular dependency analysis and resolve every cycle with a fixed strategy: lazy
+    /// (default) or function-scope
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        num_args = 0..=1,
+        default_missing_value = "lazy"
+    )]
+    ignore_circular_deps: Option<String>,
+
+    /// Fail when dynamic imports with non-literal module names are found (warn by default)
+    #[arg(long)]
+    fail_on_dynamic_imports: bool,
+
+    /// Bundle the pure Python standard library modules the code imports from the given
+    /// standard library directory (e.g. /usr/lib/python3.12), so the bundle runs on a bare
+    /// interpreter; builtin and C extension modules are still imported
+    #[arg(long, value_name = "PATH")]
+    embed_stdlib: Option<PathBuf>,
+
+    /// Write an annotated HTML view of the bundle to the given path
+    #[arg(long, value_name = "PATH")]
+    emit_html: Option<PathBuf>,
+
+    /// Write an HTML view of the bundle side by side with the original sources to the given
+    /// path; clicking a line on either side highlights its counterpart
+    #[arg(long, value_name = "PATH")]
+    emit_sourcemap_html: Option<PathBuf>,
+
+    /// Write a JSON report of module classification, symbol conflicts, circular dependencies
+    /// and tree-shaking statistics to the given path
+    #[arg(long, value_name = "PATH")]
+    emit_analysis_report: Option<PathBuf>,
+
+    /// Write a stub that imports the entry module's public API from the bundle to the given
+    /// path (requires --output)
+    #[arg(long, value_name = "PATH")]
+    emit_imports_only: Option<PathBuf>,
+
+    /// Write a JSON cross-reference of every public symbol of the bundled modules (origin, name
+    /// in the bundle, importing modules, tree-shaking outcome) to the given path
+    #[arg(long, value_name = "PATH")]
+    emit_symbol_table: Option<PathBuf>,
+
+    /// Write a JSON map of every import statement (file and line) to the module it resolved
+    /// to, its path, whether it is first-party, stdlib or third-party, and how it was bundled
+    #[arg(long, value_name = "PATH")]
+    emit_import_map: Option<PathBuf>,
+
+    /// Write every original import statement (file, line and import) that is not preserved
+    /// verbatim in the bundle, e.g. because the module was inlined or the import tree-shaken
+    #[arg(long, value_name = "PATH")]
+    emit_unused_imports: Option<PathBuf>,
+
+    /// Write a JSON map of each bundled module's path to the SHA-256 of its source and the
+    /// hashes of its direct dependencies, for deciding whether a rebuild is needed
+    #[arg(long, value_name = "PATH")]
+    emit_checksums: Option<PathBuf>,
+
+    /// Write the third-party packages the bundle imports at runtime, one per line, to the given
+    /// path; unlike `--emit-requirements`, imports removed by tree-shaking are not listed
+    #[arg(long, value_name = "PATH")]
+    emit_third_party_list: Option<PathBuf>,
+
+    /// Write a JSON call graph (node-link format) of the module-level functions in the bundle,
+    /// linking each function to the bundled functions it calls
+    #[arg(long, value_name = "PATH")]
+    emit_call_graph: Option<PathBuf>,
+
+    /// Write the final bundled AST as JSON to the given path (the AST the Python code is
+    /// generated from)
+    #[arg(long, value_name = "PATH")]
+    emit_ast: Option<PathBuf>,
+
+    /// Precede each bundled statement with a `# [cribo]` comment naming the file and line it was
+    /// inlined or wrapped from
+    #[arg(long)]
+    debug_output: bool,
+
+    /// Append `# pragma: no cover` to statements generated by the bundler (namespace creation,
+    /// symbol assignments, init function boilerplate) so they do not count against coverage
+    #[arg(long)]
+    emit_coverage_pragma: bool,
+
+    /// Append `# noqa: <CODES>` to every line generated by the bundler (namespace creation,
+    /// symbol assignments, init function boilerplate) so linters skip bundling glue
+    #[arg(
+        long,
+        value_name = "CODES",
+        num_args = 0..=1,
+        default_missing_value = "E501,F401"
+    )]
+    emit_noqa: Option<String>,
+
+    /// Print a table of each bundled module's original size, the bytes of bundle code generated
+    /// from it and its share of the bundle to stderr
+    #[arg(long)]
+    emit_size_breakdown: bool,
+
+    /// Print the raw size of the bundle and its gzip-compressed size at levels 1, 6 and 9 to
+    /// stderr
+    #[arg(long)]
+    emit_size_stats: bool,
+
+    /// Print the import tree of the bundle, starting at the entry module, as indented file paths
+    /// to stderr
+    #[arg(long)]
+    emit_dependency_tree: bool,
+
+    /// Print a JSON object with the bundle, the bundled modules, the warnings logged while
+    /// bundling and size statistics to stdout instead of the bare bundle
+    #[arg(long, conflicts_with_all = ["output", "stdout"])]
+    json_output: bool,
+
+    /// Module name the bundle assigns to its own `__name__` (e.g. when imported as a library
+    /// under a name that differs from the output filename); ignored when the entry module has an
+    /// `if __name__ == "__main__":` guard
+    #[arg(long, value_name = "NAME")]
+    output_module_name: Option<String>,
+
+    /// Interpreter named on the shebang line of the bundle (default `/usr/bin/env python3`);
+    /// the output file is made executable
+    #[arg(long, value_name = "INTERPRETER")]
+    shebang: Option<String>,
+
+    /// Lower syntax this Python version (e.g. `py37`) does not support: assignment expressions,
+    /// `match` statements and self-documenting f-strings
+    #[arg(long, value_name = "VERSION")]
+    output_python_version: Option<String>,
+
+    /// Embed the timezone data of these IANA zones (e.g. `America/New_York`) so that
+    /// `zoneinfo.ZoneInfo` works without a timezone database on the target machine
+    #[arg(long, value_name = "ZONE", num_args = 1..)]
+    embed_zoneinfo: Vec<String>,
+
+    /// Print the time spent in each bundling phase to stderr once the bundle is written
+    #[arg(long)]
+    profile: bool,
+
+    /// Log what happens to a module-level symbol (e.g. `pkg.utils.helper`) while bundling:
+    /// its definition, tree-shaking, renames, the imports referencing it and where the bundle
+    /// binds it
+    #[arg(long, value_name = "SYMBOL")]
+    trace_symbol: Option<String>,
+
+    /// Write N variants of the bundle with top-level statements shuffled within their
+    /// dependencies, run each and the bundle with `python3` and fail if any variant's exit
+    /// status or stdout differs (a developer tool for ordering bugs)
+    #[arg(long, value_name = "N", conflicts_with_all = ["stdout", "json_output"])]
+    fuzz_test_output: Option<usize>,
+
+    /// Seed of the first `--fuzz-test-output` variant; variant i uses SEED + i
+    #[arg(
+        long,
+        value_name = "SEED",
+        default_value_t = 0,
+        requires = "fuzz_test_output"
+    )]
+    fuzz_seed: u64,
+
+    /// Parse modules on N threads while discovering them (0 = one thread per CPU)
+    #[arg(long, value_name = "N")]
+    parallel: Option<usize>,
+
+    /// Arguments the bundle sees as `sys.argv[1:]` when executed (pass them after `--`)
+    #[arg(last = true, value_name = "ARGS")]
+    entry_args: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the import dependency tree of a module in the bundle
+    Deps {
+        /// Entry point Python script
+        #[arg(short, long)]
+        entry: PathBuf,
+
+        /// Module whose dependencies are shown (e.g. `pkg.utils`)
+        module: String,
+
+        /// Maximum depth of the tree
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+
+        /// Modules imported by the module (upstream), modules importing it (downstream) or both
+        #[arg(long, default_value = "both")]
+        direction: String,
+    },
+    /// Check the entry module and its dependencies for patterns known to break bundling
+    Lint {
+        /// Entry point Python script
+        #[arg(short, long)]
+        entry: PathBuf,
+    },
+    /// Show where a module name resolves to, as seen from the entry point
+    ResolvePath {
+        /// Entry point Python script
+        #[arg(short, long)]
+        entry: PathBuf,
+
+        /// Module to resolve (e.g. `pkg.utils.helpers`)
+        module: String,
+    },
+    /// List the functions and classes that appear in more than one bundle
+    ReportDuplication {
+        /// Bundles to compare
+        #[arg(required = true, num_args = 2..)]
+        bundles: Vec<PathBuf>,
+    },
+}
+
+/// Logger forwarding to `env_logger` while recording warnings for `--json-output`
+struct WarningRecorder {
+    inner: env_logger::Logger,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl WarningRecorder {
+    fn take_warnings(&self) -> Vec<String> {
+        self.warnings
+            .lock()
+            .map(|mut warnings| std::mem::take(&mut *warnings))
+            .unwrap_or_default()
+    }
+}
+
+impl Log for WarningRecorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= Level::Warn || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if record.level() == Level::Warn
+            && let Ok(mut warnings) = self.warnings.lock()
+        {
+            warnings.push(record.args().to_string());
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    if cli.help_all {
+        use std::io::Write;
+
+        use clap::CommandFactory;
+        let help = format!(
+            "{}\n{}",
+            Cli::command().render_long_help(),
+            config::render_settings_help()
+        );
+        std::io::stdout()
+            .write_all(help.as_bytes())
+            .map_err(|e| anyhow!("Failed to write help to stdout: {e}"))?;
+        return Ok(());
+    }
+
     // Initialize logging based on verbosity level
     let log_level = match cli.verbose {
         0 => "warn",  // Default: warnings and errors only
@@ -78,7 +377,27 @@ fn main() -> anyhow::Result<()> {
         2 => "debug", // -vv: debug messages
         _ => "trace", // -vvv or more: trace messages
     };
-    env_logger::Builder::from_env(Env::default().default_filter_or(log_level)).init();
+    let mut logger_builder =
+        env_logger::Builder::from_env(Env::default().default_filter_or(log_level));
+    if cli.trace_symbol.is_some() {
+        // The trace is shown whatever the verbosity level
+        logger_builder.filter_module(analyzers::symbol_trace::LOG_TARGET, LevelFilter::Info);
+    }
+    let warning_recorder = if cli.json_output {
+        // Warnings are recorded even when RUST_LOG hides them on stderr
+        let inner = logger_builder.build();
+        let max_level = inner.filter().max(LevelFilter::Warn);
+        let recorder: &'static WarningRecorder = Box::leak(Box::new(WarningRecorder {
+            inner,
+            warnings: Mutex::new(Vec::new()),
+        }));
+        log::set_logger(recorder).map_err(|e| anyhow!("Failed to initialize logging: {e}"))?;
+        log::set_max_level(max_level);
+        Some(recorder)
+    } else {
+        logger_builder.init();
+        None
+    };
 
     debug!(
         "Verbosity level: {} (log level: {})",
@@ -86,7 +405,9 @@ fn main() -> anyhow::Result<()> {
     );
     info!("Starting Cribo Python bundler");
 
-    debug!("Entry point: {}", cli.entry.display());
+    if let Some(entry) = &cli.entry {
+        debug!("Entry point: {}", entry.display());
+    }
     if cli.stdout {
         debug!("Output mode: stdout");
     } else {
@@ -96,14 +417,61 @@ fn main() -> anyhow::Result<()> {
     // Load configuration
     let mut config = Config::load(cli.config.as_deref())?;
 
-    // Override target-version from CLI if provided
+    // Override target-version from CLI if provided; targeting a version explicitly also
+    // resolves `sys.version_info` guards for it
     if let Some(target_version) = cli.target_version {
         config.set_target_version(target_version)?;
+        config.resolve_version_guards = true;
+    }
+
+    // Override output-python-version from CLI if provided
+    if let Some(output_python_version) = cli.output_python_version {
+        config.set_output_python_version(output_python_version)?;
     }
 
     // Override tree-shake from CLI
     config.tree_shake = cli.tree_shake;
 
+    // Inlining can be disabled from CLI or config
+    if cli.no_inline {
+        config.no_inline = true;
+    }
+
+    // Pruning of fully tree-shaken modules can be enabled from CLI or config
+    if cli.prune_unused_modules {
+        config.prune_unused_modules = true;
+    }
+
+    // Flat name binding for namespaces can be enabled from CLI or config
+    if cli.no_namespace_objects {
+        config.no_namespace_objects = true;
+    }
+
+    // Import deduplication can be disabled from CLI or config
+    if cli.no_deduplicate_imports {
+        config.no_deduplicate_imports = true;
+    }
+
+    // Override the circular dependency strategy from CLI
+    if let Some(cycle_strategy) = cli.cycle_strategy {
+        config.cycle_strategy = cycle_strategy.parse()?;
+    }
+
+    // Bypass the cycle analysis with a fixed strategy from CLI
+    if let Some(strategy) = cli.ignore_circular_deps {
+        config.ignore_circular_deps = Some(strategy.parse()?);
+    }
+
+    // Strict dynamic import handling can be enabled from CLI or config
+    if cli.fail_on_dynamic_imports {
+        config.fail_on_dynamic_imports = true;
+    }
+
+    // Standard library embedding directory from CLI
+    if let Some(stdlib_dir) = cli.embed_stdlib {
+        config.embed_stdlib = Some(stdlib_dir);
+    }
+
     debug!("Configuration: {config:?}");
 
     // Display target version for troubleshooting
@@ -113,18 +481,127 @@ fn main() -> anyhow::Result<()> {
         config.python_version().unwrap_or(10)
     );
 
+    match cli.command {
+        Some(Command::Deps {
+            entry,
+            module,
+            depth,
+            direction,
+        }) => {
+            let direction: TreeDirection = direction.parse()?;
+            let mut bundler = BundleOrchestrator::new(config);
+            let tree = bundler.dependency_tree(&entry, &module, direction, depth)?;
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(tree.as_bytes())
+                .map_err(|e| anyhow!("Failed to write dependency tree to stdout: {e}"))?;
+            return Ok(());
+        }
+        Some(Command::Lint { entry }) => {
+            let mut bundler = BundleOrchestrator::new(config);
+            let diagnostics = bundler.lint(&entry)?;
+            use std::io::Write;
+            let mut stdout = std::io::stdout().lock();
+            for diagnostic in &diagnostics {
+                writeln!(stdout, "{diagnostic}")
+                    .map_err(|e| anyhow!("Failed to write diagnostics to stdout: {e}"))?;
+            }
+            if !diagnostics.is_empty() {
+                return Err(anyhow!(
+                    "Found {} bundling issue(s); add `# cribo: ignore` to a line to accept it",
+                    diagnostics.len()
+                ));
+            }
+            return Ok(());
+        }
+        Some(Command::ResolvePath { entry, module }) => {
+            let mut bundler = BundleOrchestrator::new(config);
+            let report = bundler.resolve_path(&entry, &module)?;
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(report.as_bytes())
+                .map_err(|e| anyhow!("Failed to write resolution to stdout: {e}"))?;
+            return Ok(());
+        }
+        Some(Command::ReportDuplication { bundles }) => {
+            let report = analyzers::duplication::render(&bundles)?;
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(report.as_bytes())
+                .map_err(|e| anyhow!("Failed to write duplication report to stdout: {e}"))?;
+            return Ok(());
+        }
+        None => {}
+    }
+    let entry = cli
+        .entry
+        .ok_or_else(|| anyhow!("--entry must be specified"))?;
+
     // Validate arguments
-    if !cli.stdout && cli.output.is_none() {
+    if !cli.stdout && !cli.json_output && cli.output.is_none() {
         return Err(anyhow::anyhow!(
-            "Either --output or --stdout must be specified"
+            "Either --output, --stdout or --json-output must be specified"
+        ));
+    }
+    if (cli.stdout || cli.json_output) && cli.emit_imports_only.is_some() {
+        return Err(anyhow::anyhow!(
+            "--emit-imports-only requires --output to name the bundle module"
         ));
     }
 
     let mut bundler = BundleOrchestrator::new(config);
+    bundler.set_emit_options(EmitOptions {
+        html: cli.emit_html,
+        sourcemap_html: cli.emit_sourcemap_html,
+        analysis_report: cli.emit_analysis_report,
+        imports_only: cli.emit_imports_only,
+        ast: cli.emit_ast,
+        symbol_table: cli.emit_symbol_table,
+        import_map: cli.emit_import_map,
+        unused_imports: cli.emit_unused_imports,
+        checksums: cli.emit_checksums,
+        third_party_list: cli.emit_third_party_list,
+        call_graph: cli.emit_call_graph,
+        debug_output: cli.debug_output,
+        json_output: cli.json_output,
+        coverage_pragma: cli.emit_coverage_pragma,
+        noqa: cli.emit_noqa,
+        size_breakdown: cli.emit_size_breakdown,
+        size_stats: cli.emit_size_stats,
+        dependency_tree: cli.emit_dependency_tree,
+    });
+    bundler.set_entry_args(cli.entry_args);
+    if let Some(module_name) = cli.output_module_name {
+        bundler.set_output_module_name(module_name)?;
+    }
+    if let Some(threads) = cli.parallel {
+        bundler.set_parallelism(threads)?;
+    }
+    if let Some(interpreter) = cli.shebang {
+        bundler.set_shebang(&interpreter)?;
+    }
+    if !cli.embed_zoneinfo.is_empty() {
+        bundler.set_embedded_zoneinfo(&cli.embed_zoneinfo)?;
+    }
+    if cli.profile {
+        bundler.enable_profiling();
+    }
+    if let Some(symbol) = &cli.trace_symbol {
+        bundler.set_trace_symbol(symbol)?;
+    }
 
-    if cli.stdout {
+    if let Some(recorder) = warning_recorder {
+        let (bundled_code, metadata) =
+            bundler.bundle_with_metadata(&entry, cli.emit_requirements)?;
+        let json =
+            artifacts::json_output::render(&bundled_code, &metadata, &recorder.take_warnings())?;
+        use std::io::Write;
+        writeln!(std::io::stdout(), "{json}")
+            .map_err(|e| anyhow!("Failed to write JSON output to stdout: {e}"))?;
+        info!("Bundle output to stdout as JSON");
+    } else if cli.stdout {
         // Output to stdout - use write_all for explicit I/O control and error handling
-        let bundled_code = bundler.bundle_to_string(&cli.entry, cli.emit_requirements)?;
+        let bundled_code = bundler.bundle_to_string(&entry, cli.emit_requirements)?;
         use std::io::Write;
         std::io::stdout()
             .write_all(bundled_code.as_bytes())
@@ -136,8 +613,50 @@ fn main() -> anyhow::Result<()> {
             .output
             .as_ref()
             .expect("Output path should be present when not using stdout");
-        bundler.bundle(&cli.entry, output_path, cli.emit_requirements)?;
+        bundler.bundle(&entry, output_path, cli.emit_requirements)?;
         info!("Bundle created successfully at {}", output_path.display());
+
+        if let Some(count) = cli.fuzz_test_output {
+            let outcome = order_fuzz::verify(output_path, count, cli.fuzz_seed)?;
+            use std::io::Write;
+            std::io::stderr()
+                .write_all(outcome.report.as_bytes())
+                .map_err(|e| anyhow!("Failed to write fuzz test report to stderr: {e}"))?;
+            if !outcome.mismatched.is_empty() {
+                return Err(anyhow!(
+                    "{} of {count} reordered bundle variants changed the program's output",
+                    outcome.mismatched.len()
+                ));
+            }
+        }
+    }
+
+    if let Some(tree) = bundler.import_tree() {
+        use std::io::Write;
+        std::io::stderr()
+            .write_all(tree.as_bytes())
+            .map_err(|e| anyhow!("Failed to write dependency tree to stderr: {e}"))?;
+    }
+
+    if let Some(breakdown) = bundler.size_breakdown() {
+        use std::io::Write;
+        std::io::stderr()
+            .write_all(breakdown.as_bytes())
+            .map_err(|e| anyhow!("Failed to write size breakdown to stderr: {e}"))?;
+    }
+
+    if let Some(stats) = bundler.size_stats() {
+        use std::io::Write;
+        std::io::stderr()
+            .write_all(stats.as_bytes())
+            .map_err(|e| anyhow!("Failed to write size stats to stderr: {e}"))?;
+    }
+
+    if let Some(summary) = bundler.profile_summary() {
+        use std::io::Write;
+        std::io::stderr()
+            .write_all(summary.as_bytes())
+            .map_err(|e| anyhow!("Failed to write profile summary to stderr: {e}"))?;
     }
 
     Ok(())
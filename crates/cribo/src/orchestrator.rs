@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     fmt::Write,
     fs,
     path::{Path, PathBuf},
@@ -8,24 +9,53 @@ use std::{
 use anyhow::{Context, Result, anyhow};
 use indexmap::IndexSet;
 use log::{debug, info, trace, warn};
-use ruff_python_ast::ModModule;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use ruff_python_ast::{CmpOp, Expr, ExprContext, ModModule, Operator, Stmt};
 
 use crate::{
-    analyzers::types::{
-        CircularDependencyAnalysis, CircularDependencyGroup, CircularDependencyType,
-        ResolutionStrategy,
+    analyzers::{
+        dependency_tree::TreeDirection,
+        lint::{self, Diagnostic},
+        symbol_trace::{self, TraceInputs, TracedSymbol},
+        types::{
+            CircularDependencyAnalysis, CircularDependencyGroup, CircularDependencyType,
+            ResolutionStrategy,
+        },
+    },
+    artifacts::{
+        attribution::{
+            AttributedStatement, SourceAttribution, SourceLocation, StatementOrigin, line_number,
+            line_starts,
+        },
+        call_graph::CallGraph,
+        checksums::ModuleChecksum,
+        import_map::ImportMapEntry,
+        json_output::BundleMetadata,
+        report::{AnalysisReport, AnalysisResults},
+        size_breakdown::ModuleSize,
+        sourcemap_html::OriginalSource,
+        symbol_table::{ModuleSource, SymbolTable},
+        unused_imports::RemovedImport,
+    },
+    ast_builder::{expressions, other, statements},
+    code_generator::{
+        Bundler, import_transformer::handlers::dynamic::DynamicHandler,
+        phases::orchestrator::PhaseOrchestrator, profiling::ProfileCollector,
     },
-    code_generator::{Bundler, phases::orchestrator::PhaseOrchestrator},
     config::Config,
     dependency_graph::DependencyGraph,
     import_rewriter::{ImportDeduplicationStrategy, ImportRewriter},
     module_facts::ModuleFacts,
     resolver::{ImportType, ModuleId, ModuleResolver},
+    self_version::ProjectVersion,
     symbol_conflict_resolver::SymbolConflictResolver,
+    syntax_lowering::SyntaxLowerer,
     tree_shaking::TreeShaker,
     types::FxIndexMap,
     util::{module_name_from_relative, normalize_line_endings},
+    version_guards,
     visitors::{DiscoveredImport, ImportLocation, ScopeElement},
+    zoneinfo::{self, EmbeddedZone},
 };
 
 /// Static empty parsed module for creating Stylist instances
@@ -119,6 +149,25 @@ fn get_empty_parsed_module() -> &'static ruff_python_parser::Parsed<ModModule> {
         .get_or_init(|| ruff_python_parser::parse_module("").expect("Failed to parse empty module"))
 }
 
+/// Add the executable bits to the permissions of the file at `path` (no-op outside Unix)
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)
+        .with_context(|| format!("Failed to read permissions of {}", path.display()))?
+        .permissions();
+    permissions.set_mode(permissions.mode() | 0o111);
+    fs::set_permissions(path, permissions)
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+#[expect(clippy::unnecessary_wraps)]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
 /// Type alias for module processing queue
 type ModuleQueue = Vec<(ModuleId, PathBuf)>;
 /// Type alias for processed modules set
@@ -179,6 +228,88 @@ struct ProcessedModule {
     module_id: Option<ModuleId>,
 }
 
+/// Optional artifacts written alongside the bundle
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmitOptions {
+    /// Path for an annotated HTML view of the bundle
+    pub html: Option<PathBuf>,
+    /// Path for an HTML view of the bundle side by side with the original sources
+    pub sourcemap_html: Option<PathBuf>,
+    /// Path for a JSON report of the analysis decisions
+    pub analysis_report: Option<PathBuf>,
+    /// Path for a stub that only imports the entry module's public API from the bundle
+    pub imports_only: Option<PathBuf>,
+    /// Path for a JSON dump of the final bundled AST
+    pub ast: Option<PathBuf>,
+    /// Path for a JSON cross-reference of every public symbol of the bundled modules
+    pub symbol_table: Option<PathBuf>,
+    /// Path for a JSON map of every import to its resolved module and bundling decision
+    pub import_map: Option<PathBuf>,
+    /// Path for a list of the original import statements not preserved in the bundle
+    pub unused_imports: Option<PathBuf>,
+    /// Path for a JSON map of each bundled module to its content hash and its dependencies
+    pub checksums: Option<PathBuf>,
+    /// Path for a list of the third-party packages the bundle still imports at runtime
+    pub third_party_list: Option<PathBuf>,
+    /// Path for a JSON call graph of the module-level functions in the bundle
+    pub call_graph: Option<PathBuf>,
+    /// Annotate each bundled statement with a comment explaining its transformation
+    pub debug_output: bool,
+    /// Collect the bundle metadata printed by `--json-output`
+    pub json_output: bool,
+    /// Exclude statements generated by the bundler from code coverage
+    pub coverage_pragma: bool,
+    /// Rule codes to suppress with `# noqa` on lines generated by the bundler
+    pub noqa: Option<String>,
+    /// Tabulate the size each module contributes to the bundle
+    pub size_breakdown: bool,
+    /// Report the raw and gzip-compressed size of the bundle
+    pub size_stats: bool,
+    /// Render the import tree of the bundle as indented file paths
+    pub dependency_tree: bool,
+}
+
+impl EmitOptions {
+    /// Whether any requested artifact needs per-statement source attribution
+    const fn needs_attribution(&self) -> bool {
+        self.html.is_some()
+            || self.sourcemap_html.is_some()
+            || self.debug_output
+            || self.coverage_pragma
+            || self.noqa.is_some()
+            || self.size_breakdown
+    }
+}
+
+/// Generated bundle code together with optional per-statement source attribution
+struct EmittedBundle {
+    code: String,
+    /// Top-level statements with their origins (only collected when an artifact needs them)
+    statements: Vec<AttributedStatement>,
+    /// Summary of the analysis decisions (only collected when requested)
+    report: Option<AnalysisReport>,
+    /// Public API of the entry module (only collected when an import stub is requested)
+    public_api: Option<Vec<String>>,
+    /// The final bundled AST serialized as JSON (only collected when an AST dump is requested)
+    ast_json: Option<String>,
+    /// Cross-reference of the bundled symbols (only collected when requested)
+    symbol_table: Option<SymbolTable>,
+    /// Resolution of every import (only collected when requested)
+    import_map: Option<Vec<ImportMapEntry>>,
+    /// Original imports missing from the bundle (only collected when requested)
+    unused_imports: Option<Vec<RemovedImport>>,
+    /// Content hashes of the bundled modules (only collected when requested)
+    checksums: Option<FxIndexMap<String, ModuleChecksum>>,
+    /// Third-party packages imported by the bundle (only collected when requested)
+    third_party_list: Option<Vec<String>>,
+    /// Calls between the bundled functions (only collected when requested)
+    call_graph: Option<CallGraph>,
+    /// Sources of the bundled modules (only collected for the side-by-side HTML view)
+    original_sources: Option<Vec<OriginalSource>>,
+    /// Bundled modules and statistics (only collected for JSON output)
+    metadata: Option<BundleMetadata>,
+}
+
 /// Main orchestrator for bundling operations
 /// Note: Made `pub` for benchmark access via lib.rs (benchmarks are part of public API surface)
 #[derive(Debug)]
@@ -190,6 +321,27 @@ pub struct BundleOrchestrator {
     module_registry: ModuleRegistry,
     /// Cache of processed modules to ensure we only parse and transform once
     module_cache: std::sync::Mutex<FxIndexMap<PathBuf, ProcessedModule>>,
+    /// Optional artifacts to write alongside the bundle
+    emit_options: EmitOptions,
+    /// Arguments exposed to the bundled program as `sys.argv[1:]`
+    entry_args: Vec<String>,
+    /// Module name the bundle assigns to its own `__name__`
+    output_module_name: Option<String>,
+    /// Timezone data embedded into the bundle for `zoneinfo.ZoneInfo`
+    embedded_zones: Vec<EmbeddedZone>,
+    /// Interpreter on the bundle's shebang line (`--shebang`); the output file is made executable
+    shebang: Option<String>,
+    /// Thread pool for parsing modules concurrently (`--parallel`)
+    thread_pool: Option<rayon::ThreadPool>,
+    /// Timings of the bundling phases (`--profile`)
+    profile: Option<ProfileCollector>,
+    /// Size of each module in the last bundle (`--emit-size-breakdown`)
+    size_breakdown: Option<String>,
+    size_stats: Option<String>,
+    /// Import tree of the last bundle (`--emit-dependency-tree`)
+    import_tree: Option<String>,
+    /// Symbol whose path through the bundling pipeline is logged (`--trace-symbol`)
+    trace_symbol: Option<TracedSymbol>,
 }
 
 impl BundleOrchestrator {
@@ -200,11 +352,119 @@ impl BundleOrchestrator {
             conflict_resolver: SymbolConflictResolver::new(),
             module_registry: ModuleRegistry::new(),
             module_cache: std::sync::Mutex::new(FxIndexMap::default()),
+            emit_options: EmitOptions::default(),
+            entry_args: Vec::new(),
+            output_module_name: None,
+            embedded_zones: Vec::new(),
+            shebang: None,
+            thread_pool: None,
+            profile: None,
+            size_breakdown: None,
+            size_stats: None,
+            import_tree: None,
+            trace_symbol: None,
+        }
+    }
+
+    /// Set the optional artifacts to write alongside the bundle
+    pub(crate) fn set_emit_options(&mut self, emit_options: EmitOptions) {
+        self.emit_options = emit_options;
+    }
+
+    /// Set arguments the bundle should see as `sys.argv[1:]` when executed
+    pub(crate) fn set_entry_args(&mut self, entry_args: Vec<String>) {
+        self.entry_args = entry_args;
+    }
+
+    /// Set the module name the bundle reports as its `__name__`, independent of its filename
+    pub(crate) fn set_output_module_name(&mut self, module_name: String) -> Result<()> {
+        if !module_name
+            .split('.')
+            .all(ruff_python_stdlib::identifiers::is_identifier)
+        {
+            return Err(anyhow!(
+                "Invalid output module name '{module_name}': expected a dotted Python module name"
+            ));
+        }
+        self.output_module_name = Some(module_name);
+        Ok(())
+    }
+
+    /// Embed the timezone data of `keys` (IANA names) so the bundle does not need a timezone
+    /// database at runtime
+    pub(crate) fn set_embedded_zoneinfo(&mut self, keys: &[String]) -> Result<()> {
+        let python_version = self.config.python_version()?;
+        if python_version < 9 {
+            return Err(anyhow!(
+                "--embed-zoneinfo requires a target version of py39 or newer, since the zoneinfo \
+                 module was added in Python 3.9"
+            ));
+        }
+        self.embedded_zones = zoneinfo::load_zones(keys)?;
+        Ok(())
+    }
+
+    /// Name `interpreter` (e.g. `/usr/bin/env python3`) on the shebang line of the bundle and
+    /// make the output file executable
+    pub(crate) fn set_shebang(&mut self, interpreter: &str) -> Result<()> {
+        let interpreter = interpreter.strip_prefix("#!").unwrap_or(interpreter).trim();
+        if interpreter.is_empty() || interpreter.contains(['\n', '\r']) {
+            return Err(anyhow!(
+                "Invalid shebang '{interpreter}': expected an interpreter command on a single line"
+            ));
         }
+        self.shebang = Some(interpreter.to_owned());
+        Ok(())
+    }
+
+    /// Log what happens to `symbol` (e.g. `pkg.utils.helper`) while bundling
+    pub(crate) fn set_trace_symbol(&mut self, symbol: &str) -> Result<()> {
+        self.trace_symbol = Some(symbol.parse()?);
+        Ok(())
+    }
+
+    /// Parse modules on `threads` threads (0 = one per CPU) while discovering them
+    ///
+    /// Only reading, parsing and fact extraction run concurrently; module registration and
+    /// semantic analysis stay sequential, so the bundle is identical to a sequential run.
+    pub(crate) fn set_parallelism(&mut self, threads: usize) -> Result<()> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("cribo-parse-{index}"))
+            .build()
+            .context("Failed to create the parsing thread pool")?;
+        info!("Parsing modules on {} threads", pool.current_num_threads());
+        self.thread_pool = Some(pool);
+        Ok(())
+    }
+
+    /// Time each bundling phase, for the summary returned by `profile_summary`
+    pub(crate) fn enable_profiling(&mut self) {
+        self.profile = Some(ProfileCollector::default());
+    }
+
+    /// Table of the time spent in each bundling phase, if profiling was enabled
+    pub(crate) fn profile_summary(&self) -> Option<String> {
+        self.profile.as_ref().map(ProfileCollector::render)
+    }
+
+    /// Table of the size each module contributes to the bundle, if it was requested
+    pub(crate) fn size_breakdown(&self) -> Option<&str> {
+        self.size_breakdown.as_deref()
+    }
+
+    /// Table of the raw and compressed size of the bundle, if it was requested
+    pub(crate) fn size_stats(&self) -> Option<&str> {
+        self.size_stats.as_deref()
+    }
+
+    /// Import tree of the bundled modules, if it was requested
+    pub(crate) fn import_tree(&self) -> Option<&str> {
+        self.import_tree.as_deref()
     }
 
     /// Single entry point for parsing and processing modules
-    /// This is THE ONLY place where `ruff_python_parser::parse_module` should be called
+    /// Modules are parsed through `parse_module_file` here, unless prefetched into the cache
     ///
     /// Pipeline:
     /// 1. Check cache
@@ -279,16 +539,17 @@ impl BundleOrchestrator {
             module_path.display()
         );
 
-        // Step 1: Read and parse (ONLY place where parse_module is called)
-        let source = fs::read_to_string(module_path)
-            .with_context(|| format!("Failed to read file: {}", module_path.display()))?;
-        let source = normalize_line_endings(&source);
-
-        let parsed = ruff_python_parser::parse_module(&source)
-            .with_context(|| format!("Failed to parse Python file: {}", module_path.display()))?;
-        let ast = parsed.into_syntax();
+        // Step 1: Read and parse
         let python_version = self.config.python_version().unwrap_or(10);
-        let facts = Arc::new(ModuleFacts::from_ast(&ast, python_version)?);
+        let search_dirs = resolver
+            .map(ModuleResolver::get_search_directories)
+            .unwrap_or_default();
+        let (source, ast, facts) = Self::parse_module_file(
+            module_path,
+            python_version,
+            self.config.resolve_version_guards,
+            &search_dirs,
+        )?;
 
         // Step 2: Add to graph and perform semantic analysis (if graph provided)
         let module_id = if let Some(graph) = graph {
@@ -339,12 +600,111 @@ impl BundleOrchestrator {
         })
     }
 
-    /// Format error message for unresolvable cycles
-    fn format_unresolvable_cycles_error(
+    /// Read and parse a module file and extract its facts
+    ///
+    /// This is THE ONLY place where `ruff_python_parser::parse_module` is called for module
+    /// files. It touches no shared state, so modules can be parsed concurrently. Modules loaded
+    /// through `importlib.util.spec_from_file_location` from a file inside `search_dirs` are
+    /// rewritten to `importlib.import_module` before the facts are extracted, so discovery
+    /// picks them up. With `resolve_version_guards`, `sys.version_info` guards are replaced
+    /// with the branch taken on `python_version` first, so discovery skips the other branches.
+    fn parse_module_file(
+        module_path: &Path,
+        python_version: u8,
+        resolve_version_guards: bool,
+        search_dirs: &[PathBuf],
+    ) -> Result<(String, ModModule, Arc<ModuleFacts>)> {
+        let source = fs::read_to_string(module_path)
+            .with_context(|| format!("Failed to read file: {}", module_path.display()))?;
+        let source = normalize_line_endings(&source);
+
+        let parsed = ruff_python_parser::parse_module(&source)
+            .with_context(|| format!("Failed to parse Python file: {}", module_path.display()))?;
+        let mut ast = parsed.into_syntax();
+        if resolve_version_guards {
+            let resolved = version_guards::resolve_version_guards(&mut ast, python_version);
+            if resolved > 0 {
+                debug!(
+                    "Resolved {resolved} sys.version_info condition(s) in {} for Python \
+                     3.{python_version}",
+                    module_path.display()
+                );
+            }
+        }
+        DynamicHandler::rewrite_spec_file_loads(&mut ast, search_dirs);
+        let facts = Arc::new(ModuleFacts::from_ast(&ast, python_version)?);
+        Ok((source, ast, facts))
+    }
+
+    /// Parse the queued modules that are not cached yet on the thread pool, if one is set
+    ///
+    /// Results go into the module cache, where `process_module` picks them up in discovery
+    /// order. Failures are dropped here; `process_module` parses those modules again and
+    /// reports the error.
+    fn prefetch_modules(&self, queue: &ModuleQueue, search_dirs: &[PathBuf]) {
+        let Some(pool) = &self.thread_pool else {
+            return;
+        };
+        let uncached: Vec<PathBuf> = {
+            let cache = self
+                .module_cache
+                .lock()
+                .expect("Failed to acquire module cache lock");
+            queue
+                .iter()
+                .map(|(_, path)| path)
+                .filter(|path| !path.is_dir())
+                .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+                .filter(|path| !cache.contains_key(path))
+                .collect()
+        };
+        if uncached.len() < 2 {
+            return;
+        }
+
+        let python_version = self.config.python_version().unwrap_or(10);
+        let resolve_version_guards = self.config.resolve_version_guards;
+        let parsed: Vec<(PathBuf, ProcessedModule)> = pool.install(|| {
+            uncached
+                .into_par_iter()
+                .filter_map(|path| {
+                    let (source, ast, facts) = Self::parse_module_file(
+                        &path,
+                        python_version,
+                        resolve_version_guards,
+                        search_dirs,
+                    )
+                    .ok()?;
+                    Some((
+                        path,
+                        ProcessedModule {
+                            ast,
+                            source,
+                            facts,
+                            module_id: None,
+                        },
+                    ))
+                })
+                .collect()
+        });
+        debug!("Parsed {} queued modules concurrently", parsed.len());
+
+        let mut cache = self
+            .module_cache
+            .lock()
+            .expect("Failed to acquire module cache lock");
+        for (path, processed) in parsed {
+            cache.entry(path).or_insert(processed);
+        }
+    }
+
+    /// Format error message listing `cycles` under `heading`
+    fn format_cycles_error(
+        heading: &str,
         cycles: &[CircularDependencyGroup],
         resolver: &ModuleResolver,
     ) -> String {
-        let mut error_msg = String::from("Unresolvable circular dependencies detected:\n\n");
+        let mut error_msg = format!("{heading}\n\n");
 
         for (i, cycle) in cycles.iter().enumerate() {
             // Convert ModuleIds to names for display
@@ -368,21 +728,13 @@ impl BundleOrchestrator {
         error_msg
     }
 
-    /// Core bundling logic shared between file and string output modes
-    /// Returns the entry module name, parsed modules, circular dependency analysis, and optional
-    /// tree shaker, with graph and resolver populated via mutable references
-    fn bundle_core(
-        &mut self,
-        entry_path: &Path,
-        graph: &mut DependencyGraph,
-        resolver_opt: &mut Option<ModuleResolver>,
-    ) -> Result<(
-        String,
-        Vec<ParsedModuleData>,
-        Option<CircularDependencyAnalysis>,
-    )> {
+    /// Resolve the entry point and create a resolver whose search path starts at it
+    ///
+    /// A directory entry resolves to its `__init__.py` or `__main__.py`; the entry's source
+    /// root is added to the configured `src` directories.
+    fn entry_resolver(&mut self, entry_path: &Path) -> Result<(ModuleResolver, PathBuf)> {
         // Store the original entry path before transformation
-        let original_entry_path = entry_path.to_path_buf();
+        let original_entry_path = entry_path;
 
         // Handle directory as entry point
         let entry_path = if entry_path.is_dir() {
@@ -434,9 +786,6 @@ impl BundleOrchestrator {
             ));
         };
 
-        // Use a reference to the resolved entry_path for the rest of the function
-        let entry_path = &entry_path;
-
         debug!("Entry: {}", entry_path.display());
         debug!(
             "Using target Python version: {} (Python 3.{})",
@@ -475,7 +824,22 @@ impl BundleOrchestrator {
         let mut resolver = ModuleResolver::new(self.config.clone());
 
         // Set the entry file to establish the primary search path
-        resolver.set_entry_file(entry_path, &original_entry_path);
+        resolver.set_entry_file(&entry_path, original_entry_path);
+
+        Ok((resolver, entry_path))
+    }
+
+    /// Resolve the entry point, then discover and parse every module it depends on
+    ///
+    /// Returns the resolver, the entry module name and the parsed modules, with `graph`
+    /// populated.
+    fn discover_modules(
+        &mut self,
+        entry_path: &Path,
+        graph: &mut DependencyGraph,
+    ) -> Result<(ModuleResolver, String, Vec<ParsedModuleData>)> {
+        let (resolver, entry_path) = self.entry_resolver(entry_path)?;
+        let entry_path = &entry_path;
 
         // Find the entry module name
         let entry_module_name = self.find_entry_module_name(entry_path, &resolver)?;
@@ -496,19 +860,65 @@ impl BundleOrchestrator {
             graph,
         };
         let parsed_modules = self.build_dependency_graph(&mut build_params)?;
+        Ok((resolver, entry_module_name, parsed_modules))
+    }
+
+    /// Core bundling logic shared between file and string output modes
+    /// Returns the entry module name, parsed modules, circular dependency analysis, and optional
+    /// tree shaker, with graph and resolver populated via mutable references
+    fn bundle_core(
+        &mut self,
+        entry_path: &Path,
+        graph: &mut DependencyGraph,
+        resolver_opt: &mut Option<ModuleResolver>,
+    ) -> Result<(
+        String,
+        Vec<ParsedModuleData>,
+        Option<CircularDependencyAnalysis>,
+    )> {
+        let (resolver, entry_module_name, parsed_modules) =
+            self.discover_modules(entry_path, graph)?;
+        self.check_dynamic_imports(&parsed_modules, &resolver)?;
 
         // In DependencyGraph, we track all modules but focus on reachable ones
         debug!("Graph has {} modules", graph.modules.len());
 
         // Enhanced circular dependency detection and analysis
         let mut circular_dep_analysis = None;
-        if graph.has_cycles() {
+        if let Some(strategy) = self.config.ignore_circular_deps
+            && graph.has_cycles()
+        {
+            // The user vouches for the cycles: apply the fixed strategy without classifying them
             let analysis =
+                crate::analyzers::dependency_analyzer::assume_cycles_resolvable(graph, strategy)?;
+            info!(
+                "Ignoring {} circular dependencies, resolving them with the {strategy:?} strategy",
+                analysis.resolvable_cycles.len()
+            );
+            circular_dep_analysis = Some(analysis);
+        } else if graph.has_cycles() {
+            let mut analysis =
                 crate::analyzers::dependency_analyzer::analyze_circular_dependencies(graph);
 
+            // Apply user-selected strategies; cycles with the `error` strategy fail the build
+            let forbidden_cycles = crate::analyzers::dependency_analyzer::apply_cycle_strategies(
+                &mut analysis,
+                &self.config,
+                |module_id| resolver.get_module_name(module_id),
+            );
+            if !forbidden_cycles.is_empty() {
+                let error_msg = Self::format_cycles_error(
+                    "Circular dependencies are not allowed by the configured cycle strategy:",
+                    &forbidden_cycles,
+                    &resolver,
+                );
+                return Err(anyhow!(error_msg));
+            }
+
             // Check if we have unresolvable cycles - these we must fail on
             if !analysis.unresolvable_cycles.is_empty() {
-                let error_msg = Self::format_unresolvable_cycles_error(
+                let error_msg = Self::format_cycles_error(
+                    "Unresolvable circular dependencies detected:",
                     &analysis.unresolvable_cycles,
                     &resolver,
                 );
@@ -578,6 +988,51 @@ impl BundleOrchestrator {
         Ok((entry_module_name, parsed_modules, circular_dep_analysis))
     }
 
+    /// Report dynamic imports whose target module cannot be resolved statically
+    ///
+    /// Such imports are left untouched in the bundle and will fail at runtime if they refer to a
+    /// bundled module. They are reported as warnings, or as an error when
+    /// `fail_on_dynamic_imports` is set.
+    fn check_dynamic_imports(
+        &self,
+        parsed_modules: &[ParsedModuleData],
+        resolver: &ModuleResolver,
+    ) -> Result<()> {
+        let mut locations = Vec::new();
+        for (module_id, _, ast, source) in parsed_modules {
+            let ranges = DynamicHandler::find_unresolvable_dynamic_imports(ast);
+            if ranges.is_empty() {
+                continue;
+            }
+            let path = resolver.get_module_path(*module_id).unwrap_or_default();
+            let starts = line_starts(source);
+            for range in ranges {
+                let line = line_number(&starts, range.start());
+                let column = usize::from(range.start()) - starts[line - 1] + 1;
+                locations.push(format!(
+                    "{}:{line}:{column}: {}",
+                    path.display(),
+                    &source[range]
+                ));
+            }
+        }
+
+        if locations.is_empty() {
+            return Ok(());
+        }
+        if self.config.fail_on_dynamic_imports {
+            return Err(anyhow!(
+                "Found {} dynamic import(s) that cannot be resolved statically:\n  {}",
+                locations.len(),
+                locations.join("\n  ")
+            ));
+        }
+        for location in &locations {
+            warn!("Dynamic import cannot be resolved statically: {location}");
+        }
+        Ok(())
+    }
+
     /// Helper to get sorted modules from graph
     fn get_sorted_modules_from_graph(
         &self,
@@ -642,12 +1097,85 @@ impl BundleOrchestrator {
         Ok(module_ids)
     }
 
+    /// Check the entry module and the modules it depends on for patterns known to break
+    /// bundling, without bundling them
+    ///
+    /// Diagnostics on a line with a `# cribo: ignore` comment are left out.
+    pub(crate) fn lint(&mut self, entry_path: &Path) -> Result<Vec<Diagnostic>> {
+        let mut graph = DependencyGraph::new();
+        let (resolver, _, parsed_modules) = self.discover_modules(entry_path, &mut graph)?;
+        // Cycles are checked against the analysis alone: a configured strategy does not make
+        // an unresolvable cycle work
+        let unresolvable_cycles = if graph.has_cycles() {
+            crate::analyzers::dependency_analyzer::analyze_circular_dependencies(&graph)
+                .unresolvable_cycles
+        } else {
+            Vec::new()
+        };
+        let module_sources: Vec<ModuleSource<'_>> = parsed_modules
+            .iter()
+            .map(|(module_id, _, ast, source)| ModuleSource {
+                module_id: *module_id,
+                ast,
+                source,
+            })
+            .collect();
+        Ok(lint::check(
+            &module_sources,
+            &unresolvable_cycles,
+            &resolver,
+        ))
+    }
+
+    /// Render the import dependency tree of `module_name` in the bundle for `entry_path`
+    pub(crate) fn dependency_tree(
+        &mut self,
+        entry_path: &Path,
+        module_name: &str,
+        direction: TreeDirection,
+        max_depth: usize,
+    ) -> Result<String> {
+        let mut graph = DependencyGraph::new();
+        let mut resolver_opt = None;
+        self.bundle_core(entry_path, &mut graph, &mut resolver_opt)?;
+        crate::analyzers::dependency_tree::render(&graph, module_name, direction, max_depth)
+    }
+
+    /// Describe how `module_name` resolves from `entry_path`, without discovering any modules
+    pub(crate) fn resolve_path(&mut self, entry_path: &Path, module_name: &str) -> Result<String> {
+        let (resolver, _) = self.entry_resolver(entry_path)?;
+        crate::analyzers::module_resolution::render(&resolver, module_name)
+    }
+
     /// Bundle to string for stdout output
     pub(crate) fn bundle_to_string(
         &mut self,
         entry_path: &Path,
         emit_requirements: bool,
     ) -> Result<String> {
+        Ok(self.emit_to_memory(entry_path, emit_requirements)?.code)
+    }
+
+    /// Bundle to string together with the metadata printed by `--json-output`
+    pub(crate) fn bundle_with_metadata(
+        &mut self,
+        entry_path: &Path,
+        emit_requirements: bool,
+    ) -> Result<(String, BundleMetadata)> {
+        self.emit_options.json_output = true;
+        let emitted = self.emit_to_memory(entry_path, emit_requirements)?;
+        let metadata = emitted
+            .metadata
+            .ok_or_else(|| anyhow!("Bundle metadata was not collected"))?;
+        Ok((emitted.code, metadata))
+    }
+
+    /// Run the bundling pipeline without writing the bundle to a file
+    fn emit_to_memory(
+        &mut self,
+        entry_path: &Path,
+        emit_requirements: bool,
+    ) -> Result<EmittedBundle> {
         info!("Starting bundle process for stdout output");
 
         // Initialize empty graph - resolver will be created in bundle_core
@@ -683,7 +1211,7 @@ impl BundleOrchestrator {
 
         // Generate bundled code
         info!("Using hybrid static bundler");
-        let bundled_code = self.emit_static_bundle(&StaticBundleParams {
+        let emitted = self.emit_static_bundle(&StaticBundleParams {
             sorted_module_ids: &sorted_module_ids,
             parsed_modules: Some(&parsed_modules),
             resolver: &resolver,
@@ -697,7 +1225,9 @@ impl BundleOrchestrator {
             self.write_requirements_file_for_stdout(&sorted_module_ids, &resolver, &graph)?;
         }
 
-        Ok(bundled_code)
+        self.write_artifacts(entry_path, None, &emitted)?;
+
+        Ok(emitted)
     }
 
     /// Main bundling function
@@ -742,7 +1272,7 @@ impl BundleOrchestrator {
 
         // Generate bundled code
         info!("Using hybrid static bundler");
-        let bundled_code = self.emit_static_bundle(&StaticBundleParams {
+        let emitted = self.emit_static_bundle(&StaticBundleParams {
             sorted_module_ids: &sorted_module_ids,
             parsed_modules: Some(&parsed_modules), // Use pre-parsed modules to avoid double parsing
             resolver: &resolver,
@@ -757,11 +1287,16 @@ impl BundleOrchestrator {
         }
 
         // Write output file
-        fs::write(output_path, bundled_code)
+        fs::write(output_path, &emitted.code)
             .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
+        if self.shebang.is_some() {
+            make_executable(output_path)?;
+        }
 
         info!("Bundle written to: {}", output_path.display());
 
+        self.write_artifacts(entry_path, Some(output_path), &emitted)?;
+
         Ok(())
     }
 
@@ -911,7 +1446,12 @@ impl BundleOrchestrator {
 
         // PHASE 1: Discover and collect all modules
         info!("Phase 1: Discovering all modules...");
-        while let Some((module_id, module_path)) = modules_to_process.pop() {
+        let search_dirs = params.resolver.get_search_directories();
+        loop {
+            self.prefetch_modules(&modules_to_process, &search_dirs);
+            let Some((module_id, module_path)) = modules_to_process.pop() else {
+                break;
+            };
             let module_name = params
                 .resolver
                 .get_module_name(module_id)
@@ -1737,8 +2277,278 @@ impl BundleOrchestrator {
         Ok(())
     }
 
+    /// Write the optional artifacts requested via `EmitOptions`
+    fn write_artifacts(
+        &self,
+        entry_path: &Path,
+        output_path: Option<&Path>,
+        emitted: &EmittedBundle,
+    ) -> Result<()> {
+        if let Some(html_path) = &self.emit_options.html {
+            let title = entry_path.display().to_string();
+            let html = crate::artifacts::html::render(&title, &emitted.statements);
+            fs::write(html_path, html)
+                .with_context(|| format!("Failed to write HTML view: {}", html_path.display()))?;
+            info!("HTML view written to: {}", html_path.display());
+        }
+        if let (Some(html_path), Some(sources)) =
+            (&self.emit_options.sourcemap_html, &emitted.original_sources)
+        {
+            let title = entry_path.display().to_string();
+            let html =
+                crate::artifacts::sourcemap_html::render(&title, &emitted.statements, sources);
+            fs::write(html_path, html).with_context(|| {
+                format!(
+                    "Failed to write source map HTML view: {}",
+                    html_path.display()
+                )
+            })?;
+            info!("Source map HTML view written to: {}", html_path.display());
+        }
+        if let (Some(report_path), Some(report)) =
+            (&self.emit_options.analysis_report, &emitted.report)
+        {
+            let json = serde_json::to_string_pretty(report)
+                .context("Failed to serialize analysis report")?;
+            fs::write(report_path, json).with_context(|| {
+                format!("Failed to write analysis report: {}", report_path.display())
+            })?;
+            info!("Analysis report written to: {}", report_path.display());
+        }
+        if let (Some(table_path), Some(symbol_table)) =
+            (&self.emit_options.symbol_table, &emitted.symbol_table)
+        {
+            let json = serde_json::to_string_pretty(symbol_table)
+                .context("Failed to serialize symbol table")?;
+            fs::write(table_path, json).with_context(|| {
+                format!("Failed to write symbol table: {}", table_path.display())
+            })?;
+            info!("Symbol table written to: {}", table_path.display());
+        }
+        if let (Some(map_path), Some(import_map)) =
+            (&self.emit_options.import_map, &emitted.import_map)
+        {
+            let json = serde_json::to_string_pretty(import_map)
+                .context("Failed to serialize import map")?;
+            fs::write(map_path, json)
+                .with_context(|| format!("Failed to write import map: {}", map_path.display()))?;
+            info!("Import map written to: {}", map_path.display());
+        }
+        if let (Some(unused_path), Some(unused_imports)) =
+            (&self.emit_options.unused_imports, &emitted.unused_imports)
+        {
+            fs::write(
+                unused_path,
+                crate::artifacts::unused_imports::render(unused_imports),
+            )
+            .with_context(|| {
+                format!(
+                    "Failed to write unused imports report: {}",
+                    unused_path.display()
+                )
+            })?;
+            info!(
+                "Unused imports report written to: {}",
+                unused_path.display()
+            );
+        }
+        if let (Some(checksums_path), Some(checksums)) =
+            (&self.emit_options.checksums, &emitted.checksums)
+        {
+            let json = serde_json::to_string_pretty(checksums)
+                .context("Failed to serialize module checksums")?;
+            fs::write(checksums_path, json).with_context(|| {
+                format!(
+                    "Failed to write module checksums: {}",
+                    checksums_path.display()
+                )
+            })?;
+            info!("Module checksums written to: {}", checksums_path.display());
+        }
+        if let (Some(list_path), Some(packages)) = (
+            &self.emit_options.third_party_list,
+            &emitted.third_party_list,
+        ) {
+            fs::write(list_path, crate::artifacts::third_party::render(packages)).with_context(
+                || format!("Failed to write third-party list: {}", list_path.display()),
+            )?;
+            info!("Third-party list written to: {}", list_path.display());
+        }
+        if let (Some(graph_path), Some(call_graph)) =
+            (&self.emit_options.call_graph, &emitted.call_graph)
+        {
+            let json = serde_json::to_string_pretty(call_graph)
+                .context("Failed to serialize call graph")?;
+            fs::write(graph_path, json)
+                .with_context(|| format!("Failed to write call graph: {}", graph_path.display()))?;
+            info!("Call graph written to: {}", graph_path.display());
+        }
+        if let (Some(ast_path), Some(ast_json)) = (&self.emit_options.ast, &emitted.ast_json) {
+            fs::write(ast_path, ast_json)
+                .with_context(|| format!("Failed to write AST dump: {}", ast_path.display()))?;
+            info!("Bundled AST written to: {}", ast_path.display());
+        }
+        if let (Some(stub_path), Some(public_api)) =
+            (&self.emit_options.imports_only, &emitted.public_api)
+        {
+            // The stub imports the bundle by module name, which only exists for file output
+            let bundle_module = output_path
+                .and_then(Path::file_stem)
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| {
+                    anyhow!("--emit-imports-only requires --output to name the bundle module")
+                })?;
+            let stub = crate::artifacts::stub::render(bundle_module, public_api);
+            fs::write(stub_path, stub)
+                .with_context(|| format!("Failed to write import stub: {}", stub_path.display()))?;
+            info!("Import stub written to: {}", stub_path.display());
+        }
+        Ok(())
+    }
+
+    /// Attribute each top-level bundled statement to the module it was generated from
+    fn attribute_statements(
+        bundled_ast: &ModModule,
+        code_parts: &[String],
+        attribution: &SourceAttribution,
+        params: &StaticBundleParams<'_>,
+    ) -> Vec<AttributedStatement> {
+        // Line tables are built lazily, only for modules that actually own statements
+        let mut line_tables: FxIndexMap<ModuleId, Vec<usize>> = FxIndexMap::default();
+        let sources: FxIndexMap<ModuleId, &str> = params
+            .parsed_modules
+            .unwrap_or_default()
+            .iter()
+            .map(|(id, _, _, source)| (*id, source.as_str()))
+            .collect();
+
+        bundled_ast
+            .body
+            .iter()
+            .zip(code_parts)
+            .map(|(stmt, code)| {
+                let origin = attribution.attribute(stmt);
+                let location = match origin {
+                    StatementOrigin::Source { module_id, offset }
+                    | StatementOrigin::Wrapped { module_id, offset } => {
+                        let starts = line_tables.entry(module_id).or_insert_with(|| {
+                            sources
+                                .get(&module_id)
+                                .copied()
+                                .map_or_else(Vec::new, line_starts)
+                        });
+                        Some(SourceLocation {
+                            module_name: params
+                                .resolver
+                                .get_module_name(module_id)
+                                .unwrap_or_else(|| format!("module_{}", module_id.as_u32())),
+                            path: params
+                                .resolver
+                                .get_module_path(module_id)
+                                .unwrap_or_default(),
+                            line: line_number(starts, offset),
+                        })
+                    }
+                    StatementOrigin::Generated => None,
+                };
+                AttributedStatement {
+                    code: code.clone(),
+                    origin,
+                    location,
+                }
+            })
+            .collect()
+    }
+
+    /// Insert `import sys; sys.argv = sys.argv[:1] + [...]` at the top of the bundle body
+    ///
+    /// The preamble goes after the module docstring and `__future__` imports, which must stay
+    /// first in the file.
+    fn insert_entry_args_preamble(body: &mut Vec<Stmt>, entry_args: &[String]) {
+        let position = Self::preamble_position(body);
+
+        let argv = || expressions::name_attribute("sys", "argv", ExprContext::Load);
+        let program_name = expressions::subscript(
+            argv(),
+            expressions::slice(None, Some(expressions::int_literal(1))),
+            ExprContext::Load,
+        );
+        let args = expressions::list(
+            entry_args
+                .iter()
+                .map(|arg| expressions::string_literal(arg.as_str()))
+                .collect(),
+            ExprContext::Load,
+        );
+        let preamble = [
+            statements::import(vec![other::alias("sys", None)]),
+            statements::assign(
+                vec![expressions::name_attribute(
+                    "sys",
+                    "argv",
+                    ExprContext::Store,
+                )],
+                expressions::bin_op(program_name, Operator::Add, args),
+            ),
+        ];
+        body.splice(position..position, preamble);
+    }
+
+    /// Insert `__name__ = "<module_name>"` at the top of the bundle body
+    ///
+    /// Code inlined into the bundle reads the global `__name__`, so functions and classes defined
+    /// afterwards report `module_name` as their `__module__`.
+    fn insert_module_name_preamble(body: &mut Vec<Stmt>, module_name: &str) {
+        let position = Self::preamble_position(body);
+        body.insert(
+            position,
+            statements::simple_assign("__name__", expressions::string_literal(module_name)),
+        );
+    }
+
+    /// Check whether a module body has a top-level `if __name__ == "__main__":` guard
+    fn has_main_guard(body: &[Stmt]) -> bool {
+        let is_name = |expr: &Expr| {
+            expr.as_name_expr()
+                .is_some_and(|name| name.id.as_str() == "__name__")
+        };
+        let is_main = |expr: &Expr| {
+            expr.as_string_literal_expr()
+                .is_some_and(|literal| literal.value.to_str() == "__main__")
+        };
+
+        body.iter().any(|stmt| {
+            let Stmt::If(if_stmt) = stmt else {
+                return false;
+            };
+            let Expr::Compare(compare) = if_stmt.test.as_ref() else {
+                return false;
+            };
+            let [CmpOp::Eq] = compare.ops.as_ref() else {
+                return false;
+            };
+            let [right] = compare.comparators.as_ref() else {
+                return false;
+            };
+            (is_name(&compare.left) && is_main(right)) || (is_main(&compare.left) && is_name(right))
+        })
+    }
+
+    /// Index of the first statement after the module docstring and `__future__` imports
+    fn preamble_position(body: &[Stmt]) -> usize {
+        body.iter()
+            .position(|stmt| match stmt {
+                Stmt::ImportFrom(import_from) => {
+                    import_from.module.as_deref() != Some("__future__")
+                }
+                Stmt::Expr(expr_stmt) => !expr_stmt.value.is_string_literal_expr(),
+                _ => true,
+            })
+            .unwrap_or(body.len())
+    }
+
     /// Emit bundle using static bundler (no exec calls)
-    fn emit_static_bundle(&mut self, params: &StaticBundleParams<'_>) -> Result<String> {
+    fn emit_static_bundle(&mut self, params: &StaticBundleParams<'_>) -> Result<EmittedBundle> {
         // First, detect and resolve conflicts after all modules have been analyzed
         let conflicts = self.conflict_resolver.detect_and_resolve_conflicts();
         if !conflicts.is_empty() {
@@ -1755,6 +2565,7 @@ impl BundleOrchestrator {
         }
 
         let mut static_bundler = Bundler::new(Some(&self.module_registry), params.resolver);
+        static_bundler.profile = self.profile.take().map(RefCell::new);
 
         // Parse all modules and prepare them for bundling
         let mut module_asts = Vec::new();
@@ -1784,10 +2595,20 @@ impl BundleOrchestrator {
             ));
         }
 
-        // Apply import rewriting if we have resolvable circular dependencies
-        if let Some(analysis) = params.circular_dep_analysis
-            && !analysis.resolvable_cycles.is_empty()
-        {
+        // Apply import rewriting if we have resolvable circular dependencies (cycles with the
+        // lazy strategy are left to the wrapper modules' lazy initialization)
+        let function_scoped_cycles: Vec<CircularDependencyGroup> = params
+            .circular_dep_analysis
+            .map(|analysis| {
+                analysis
+                    .resolvable_cycles
+                    .iter()
+                    .filter(|cycle| !matches!(cycle.suggested_resolution, ResolutionStrategy::Lazy))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !function_scoped_cycles.is_empty() {
             info!("Applying function-scoped import rewriting to resolve circular dependencies");
 
             // Create import rewriter
@@ -1800,7 +2621,7 @@ impl BundleOrchestrator {
             // Analyze movable imports using semantic analysis
             let movable_imports = import_rewriter.analyze_movable_imports_semantic(
                 params.graph,
-                &analysis.resolvable_cycles,
+                &function_scoped_cycles,
                 &self.conflict_resolver,
                 &module_ast_map,
             );
@@ -1816,8 +2637,30 @@ impl BundleOrchestrator {
             }
         }
 
+        // Pin `importlib.metadata.version("<this project>")` to the version in pyproject.toml,
+        // since the bundle ships without the distribution metadata
+        if let Some(project) = params
+            .resolver
+            .get_module_path(ModuleId::ENTRY)
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .and_then(|dir| ProjectVersion::discover(&dir))
+        {
+            for (module_id, ast, _) in &mut module_asts {
+                let replaced = project.rewrite_module(ast);
+                if replaced > 0 {
+                    info!(
+                        "Replaced {replaced} self-version lookup(s) in {}",
+                        params
+                            .resolver
+                            .get_module_name(*module_id)
+                            .unwrap_or_else(|| format!("module_{}", module_id.as_u32()))
+                    );
+                }
+            }
+        }
+
         // Bundle all modules using the phase-based orchestrator
-        let bundled_ast = PhaseOrchestrator::bundle(
+        let mut bundled_ast = PhaseOrchestrator::bundle(
             &mut static_bundler,
             &crate::code_generator::BundleParams {
                 modules: &module_asts,
@@ -1828,12 +2671,165 @@ impl BundleOrchestrator {
                 circular_dep_analysis: params.circular_dep_analysis,
                 tree_shaker: params.tree_shaker,
                 python_version: self.config.python_version().unwrap_or(10),
+                no_inline: self.config.no_inline,
+                prune_unused_modules: self.config.prune_unused_modules,
+                no_namespace_objects: self.config.no_namespace_objects,
+                no_deduplicate_imports: self.config.no_deduplicate_imports,
             },
         );
+        self.profile = static_bundler.profile.take().map(RefCell::into_inner);
 
-        // Generate Python code from AST
+        if !self.embedded_zones.is_empty() {
+            let position = Self::preamble_position(&bundled_ast.body);
+            bundled_ast.body.splice(
+                position..position,
+                zoneinfo::preamble(&self.embedded_zones)?,
+            );
+        }
+        if !self.entry_args.is_empty() {
+            Self::insert_entry_args_preamble(&mut bundled_ast.body, &self.entry_args);
+        }
+        if let Some(module_name) = &self.output_module_name {
+            // Assigning `__name__` would make the entry's `__main__` guard dead code when the
+            // bundle is run as a script
+            let entry_has_main_guard = module_asts
+                .iter()
+                .find(|(module_id, _, _)| *module_id == ModuleId::ENTRY)
+                .is_some_and(|(_, ast, _)| Self::has_main_guard(&ast.body));
+            if entry_has_main_guard {
+                warn!(
+                    "Ignoring --output-module-name {module_name}: the entry module has an `if \
+                     __name__ == \"__main__\":` guard that would never run"
+                );
+            } else {
+                Self::insert_module_name_preamble(&mut bundled_ast.body, module_name);
+            }
+        }
+
+        let analysis_results = AnalysisResults {
+            resolver: params.resolver,
+            module_ids: params.sorted_module_ids,
+            inlined_modules: &static_bundler.inlined_modules,
+            wrapper_modules: &static_bundler.wrapper_modules,
+            conflict_resolver: &self.conflict_resolver,
+            circular_dep_analysis: params.circular_dep_analysis,
+            tree_shaking_keep_symbols: static_bundler.tree_shaking_keep_symbols.as_ref(),
+        };
+        let module_sources: Vec<ModuleSource<'_>> = params
+            .parsed_modules
+            .unwrap_or_default()
+            .iter()
+            .map(|(module_id, _, ast, source)| ModuleSource {
+                module_id: *module_id,
+                ast,
+                source,
+            })
+            .collect();
+        let symbol_table = self
+            .emit_options
+            .symbol_table
+            .is_some()
+            .then(|| crate::artifacts::symbol_table::build(&analysis_results, &module_sources));
+        let import_map = self
+            .emit_options
+            .import_map
+            .is_some()
+            .then(|| crate::artifacts::import_map::build(&analysis_results, &module_sources));
         let empty_parsed = get_empty_parsed_module();
         let stylist = ruff_python_codegen::Stylist::from_tokens(empty_parsed.tokens(), "");
+        if let Some(target_minor) = self.config.output_python_version()? {
+            SyntaxLowerer::new(target_minor, &stylist).lower_module(&mut bundled_ast)?;
+        }
+        if let Some(traced) = &self.trace_symbol {
+            let inputs = TraceInputs {
+                graph: params.graph,
+                resolver: params.resolver,
+                tree_shaker: params.tree_shaker,
+                conflict_resolver: &self.conflict_resolver,
+                inlined_modules: &static_bundler.inlined_modules,
+                wrapper_modules: &static_bundler.wrapper_modules,
+                bundled_ast: &bundled_ast,
+                stylist: &stylist,
+            };
+            for step in symbol_trace::trace(traced, &inputs) {
+                info!(target: symbol_trace::LOG_TARGET, "{traced}: {step}");
+            }
+        }
+        let unused_imports = self.emit_options.unused_imports.is_some().then(|| {
+            crate::artifacts::unused_imports::build(
+                &analysis_results,
+                &module_sources,
+                &bundled_ast,
+                &stylist,
+            )
+        });
+        let checksums = self.emit_options.checksums.is_some().then(|| {
+            let content_hashes: FxIndexMap<ModuleId, String> = module_asts
+                .iter()
+                .map(|(module_id, _, content_hash)| (*module_id, content_hash.clone()))
+                .collect();
+            crate::artifacts::checksums::build(&analysis_results, params.graph, &content_hashes)
+        });
+        let third_party_list = self
+            .emit_options
+            .third_party_list
+            .is_some()
+            .then(|| crate::artifacts::third_party::build(params.resolver, &bundled_ast));
+        let call_graph = self.emit_options.call_graph.is_some().then(|| {
+            crate::artifacts::call_graph::build(&analysis_results, params.graph, params.tree_shaker)
+        });
+        let original_sources = self.emit_options.sourcemap_html.is_some().then(|| {
+            params
+                .parsed_modules
+                .unwrap_or_default()
+                .iter()
+                .map(|(module_id, _, _, source)| OriginalSource {
+                    module_name: params
+                        .resolver
+                        .get_module_name(*module_id)
+                        .unwrap_or_else(|| format!("module_{}", module_id.as_u32())),
+                    path: params
+                        .resolver
+                        .get_module_path(*module_id)
+                        .unwrap_or_default(),
+                    source: source.clone(),
+                })
+                .collect()
+        });
+        let mut metadata = self.emit_options.json_output.then(|| {
+            let input_size = params
+                .parsed_modules
+                .unwrap_or_default()
+                .iter()
+                .map(|(_, _, _, source)| source.len())
+                .sum();
+            crate::artifacts::json_output::metadata(&analysis_results, input_size)
+        });
+        let report = self
+            .emit_options
+            .analysis_report
+            .is_some()
+            .then(|| analysis_results.into_report());
+
+        let public_api = self.emit_options.imports_only.is_some().then(|| {
+            params
+                .parsed_modules
+                .unwrap_or_default()
+                .iter()
+                .find(|(module_id, ..)| module_id.is_entry())
+                .map(|(_, _, ast, _)| crate::artifacts::stub::public_api(ast))
+                .unwrap_or_default()
+        });
+
+        let ast_json = self
+            .emit_options
+            .ast
+            .is_some()
+            .then(|| serde_json::to_string_pretty(&bundled_ast))
+            .transpose()
+            .context("Failed to serialize bundled AST")?;
+
+        // Generate Python code from AST
 
         log::trace!("Bundled AST has {} statements", bundled_ast.body.len());
         if !bundled_ast.body.is_empty() {
@@ -1857,16 +2853,86 @@ impl BundleOrchestrator {
             code_parts.push(stmt_code);
         }
 
+        let mut statements = if self.emit_options.needs_attribution() {
+            let attribution = SourceAttribution::new(static_bundler.indexed_module_order.clone());
+            Self::attribute_statements(&bundled_ast, &code_parts, &attribution, params)
+        } else {
+            Vec::new()
+        };
+        if self.emit_options.coverage_pragma {
+            crate::artifacts::coverage_pragma::mark_generated(&mut statements);
+        }
+        if let Some(codes) = &self.emit_options.noqa {
+            crate::artifacts::noqa::mark_generated(&mut statements, codes);
+        }
+        if self.emit_options.size_breakdown {
+            let modules: Vec<ModuleSize> = params
+                .parsed_modules
+                .unwrap_or_default()
+                .iter()
+                .map(|(module_id, _, _, source)| ModuleSize {
+                    module_id: *module_id,
+                    name: params
+                        .resolver
+                        .get_module_name(*module_id)
+                        .unwrap_or_else(|| format!("module_{}", module_id.as_u32())),
+                    original: source.len(),
+                })
+                .collect();
+            self.size_breakdown = Some(crate::artifacts::size_breakdown::render(
+                &statements,
+                &modules,
+            ));
+        }
+
+        if self.emit_options.dependency_tree {
+            self.import_tree = Some(crate::analyzers::dependency_tree::render_import_tree(
+                params.graph,
+                params.resolver,
+            ));
+        }
+
         // Add shebang and header
         let mut final_output = vec![
-            "#!/usr/bin/env python3".to_owned(),
+            format!(
+                "#!{}",
+                self.shebang.as_deref().unwrap_or("/usr/bin/env python3")
+            ),
             "# Generated by Cribo - Python Source Bundler".to_owned(),
             "# https://github.com/ophidiarium/cribo".to_owned(),
             String::new(), // Empty line
         ];
-        final_output.extend(code_parts);
+        if self.emit_options.debug_output {
+            final_output.extend(crate::artifacts::debug_comments::annotate(&statements));
+        } else if self.emit_options.coverage_pragma || self.emit_options.noqa.is_some() {
+            final_output.extend(statements.iter().map(|statement| statement.code.clone()));
+        } else {
+            final_output.extend(code_parts);
+        }
 
-        Ok(final_output.join("\n"))
+        let code = final_output.join("\n");
+        if let Some(metadata) = &mut metadata {
+            metadata.statistics.output_size = code.len();
+        }
+        if self.emit_options.size_stats {
+            self.size_stats = Some(crate::artifacts::size_stats::render(&code));
+        }
+
+        Ok(EmittedBundle {
+            code,
+            statements,
+            report,
+            public_api,
+            ast_json,
+            symbol_table,
+            import_map,
+            unused_imports,
+            checksums,
+            third_party_list,
+            call_graph,
+            original_sources,
+            metadata,
+        })
     }
 
     /// Generate requirements.txt content from third-party imports
@@ -1905,3 +2971,111 @@ impl BundleOrchestrator {
         requirements.join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+
+    use super::*;
+
+    #[test]
+    fn test_entry_args_preamble_follows_docstring_and_future_imports() {
+        let source = "\"\"\"Doc.\"\"\"\nfrom __future__ import annotations\nimport os\n";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.into_syntax();
+
+        BundleOrchestrator::insert_entry_args_preamble(
+            &mut module.body,
+            &["--my-flag".to_owned(), "it's".to_owned()],
+        );
+
+        let code: Vec<String> = module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect();
+        assert_eq!(
+            code,
+            [
+                "\"\"\"Doc.\"\"\"",
+                "from __future__ import annotations",
+                "import sys",
+                "sys.argv = sys.argv[:1] + [\"--my-flag\", \"it's\"]",
+                "import os",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_name_preamble_precedes_entry_args() {
+        let source = "\"\"\"Doc.\"\"\"\nimport os\n";
+        let parsed = ruff_python_parser::parse_module(source).expect("test module should parse");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.into_syntax();
+
+        BundleOrchestrator::insert_entry_args_preamble(&mut module.body, &["-v".to_owned()]);
+        BundleOrchestrator::insert_module_name_preamble(&mut module.body, "mylib");
+
+        let code: Vec<String> = module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect();
+        assert_eq!(
+            code,
+            [
+                "\"\"\"Doc.\"\"\"",
+                "__name__ = \"mylib\"",
+                "import sys",
+                "sys.argv = sys.argv[:1] + [\"-v\"]",
+                "import os",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_main_guard() {
+        let parse = |source: &str| {
+            ruff_python_parser::parse_module(source)
+                .expect("test module should parse")
+                .into_syntax()
+        };
+
+        assert!(BundleOrchestrator::has_main_guard(
+            &parse("def main(): ...\nif __name__ == \"__main__\":\n    main()\n").body
+        ));
+        assert!(BundleOrchestrator::has_main_guard(
+            &parse("if '__main__' == __name__:\n    pass\n").body
+        ));
+        assert!(!BundleOrchestrator::has_main_guard(
+            &parse("if __name__ != \"__main__\":\n    pass\n").body
+        ));
+        assert!(!BundleOrchestrator::has_main_guard(
+            &parse("def main():\n    if __name__ == \"__main__\":\n        pass\n").body
+        ));
+    }
+
+    #[test]
+    fn test_set_shebang_accepts_single_line_interpreters() {
+        let mut bundler = BundleOrchestrator::new(Config::default());
+        assert!(bundler.set_shebang("").is_err());
+        assert!(bundler.set_shebang("python3\nimport os").is_err());
+        bundler
+            .set_shebang("#!/usr/bin/env python3.12")
+            .expect("a leading #! should be accepted");
+        assert_eq!(bundler.shebang.as_deref(), Some("/usr/bin/env python3.12"));
+    }
+
+    #[test]
+    fn test_set_output_module_name_rejects_invalid_names() {
+        let mut bundler = BundleOrchestrator::new(Config::default());
+        assert!(bundler.set_output_module_name("my-lib".to_owned()).is_err());
+        assert!(bundler.set_output_module_name("pkg.".to_owned()).is_err());
+        assert!(
+            bundler
+                .set_output_module_name("pkg.mylib".to_owned())
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Differential testing of statement order (`--fuzz-test-output`).
+//!
+//! Bugs in the bundler's ordering often only show once statements land in a slightly different
+//! order. This generates variants of a bundle whose top-level statements are shuffled as far
+//! as their dependencies allow, runs each variant next to the bundle and compares its output
+//! with the bundle's.
+//!
+//! Two statements keep their relative order when one reads a name the other binds (following
+//! the names read by function and class bodies they reference), when both bind the same name,
+//! or when both have effects at import time (calls, imports, attribute stores, control flow).
+//! Statements that use `globals()`, `exec` and the like, or star imports, keep their position
+//! relative to everything. A leading docstring and `__future__` imports stay first. Variants
+//! are shuffled with a seeded generator, so a failing variant can be reproduced from its seed.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result, anyhow};
+use ruff_python_ast::{
+    Expr, ExprContext, Stmt,
+    visitor::source_order::{SourceOrderVisitor, walk_expr, walk_stmt},
+};
+use ruff_text_size::Ranged;
+
+use crate::types::{FxIndexMap, FxIndexSet};
+
+/// Interpreter the bundle and its variants are run with
+const PYTHON: &str = if cfg!(windows) { "python" } else { "python3" };
+
+/// Names whose use makes a statement depend on every binding of the module
+const BARRIER_NAMES: &[&str] = &["globals", "locals", "vars", "dir", "eval", "exec"];
+
+/// A reordered copy of a bundle
+#[derive(Debug, Clone)]
+pub(crate) struct Variant {
+    pub code: String,
+    /// Number of statements that are no longer at their original position
+    pub moved: usize,
+}
+
+/// Result of comparing the variants of a bundle with the bundle itself
+#[derive(Debug, Clone)]
+pub(crate) struct FuzzOutcome {
+    /// One line per variant
+    pub report: String,
+    /// Variants whose output differs, kept on disk for inspection
+    pub mismatched: Vec<PathBuf>,
+}
+
+/// Run the bundle at `bundle_path` and `count` reordered variants of it, seeded from `seed`
+/// upwards, and compare their exit status and standard output
+///
+/// Each variant is written next to the bundle (so paths relative to it resolve the same) and
+/// removed again when its output matches.
+pub(crate) fn verify(bundle_path: &Path, count: usize, seed: u64) -> Result<FuzzOutcome> {
+    let code = fs::read_to_string(bundle_path)
+        .with_context(|| format!("Failed to read bundle {}", bundle_path.display()))?;
+    let expected = run(bundle_path)?;
+
+    let mut report = String::new();
+    let mut mismatched = Vec::new();
+    for index in 0..count {
+        let variant_seed = seed.wrapping_add(index as u64);
+        let variant = reorder(&code, variant_seed)?;
+        let variant_path = bundle_path.with_extension(format!("fuzz-{variant_seed}.py"));
+        fs::write(&variant_path, &variant.code).with_context(|| {
+            format!("Failed to write bundle variant {}", variant_path.display())
+        })?;
+
+        if run(&variant_path)? == expected {
+            fs::remove_file(&variant_path).with_context(|| {
+                format!("Failed to remove bundle variant {}", variant_path.display())
+            })?;
+            let _ = writeln!(
+                report,
+                "variant {index} (seed {variant_seed}): {} statements moved, same output",
+                variant.moved
+            );
+        } else {
+            let _ = writeln!(
+                report,
+                "variant {index} (seed {variant_seed}): {} statements moved, output differs, kept \
+                 at {}",
+                variant.moved,
+                variant_path.display()
+            );
+            mismatched.push(variant_path);
+        }
+    }
+    Ok(FuzzOutcome { report, mismatched })
+}
+
+/// Exit code and standard output of running `path` with [`PYTHON`]
+fn run(path: &Path) -> Result<(Option<i32>, Vec<u8>)> {
+    let output = Command::new(PYTHON)
+        .arg(path)
+        .output()
+        .with_context(|| format!("Failed to run {PYTHON} {}", path.display()))?;
+    Ok((output.status.code(), output.stdout))
+}
+
+/// Shuffle the top-level statements of `code` within their ordering constraints
+pub(crate) fn reorder(code: &str, seed: u64) -> Result<Variant> {
+    let module = ruff_python_parser::parse_module(code)
+        .map_err(|e| anyhow!("Failed to parse bundle: {e}"))?
+        .into_syntax();
+    let body = &module.body;
+    let Some(first) = body.first() else {
+        return Ok(Variant {
+            code: code.to_owned(),
+            moved: 0,
+        });
+    };
+
+    let pinned = pinned_prefix(body);
+    let facts: Vec<StatementFacts> = body[pinned..].iter().map(StatementFacts::of).collect();
+    let order = shuffled_order(&constraints(&facts), &mut SplitMix64(seed));
+
+    let segments: Vec<&str> = body
+        .iter()
+        .enumerate()
+        .map(|(index, stmt)| segment(code, stmt, body.get(index + 1)))
+        .collect();
+    let mut output = code[..usize::from(first.start())].to_owned();
+    for segment in segments[..pinned]
+        .iter()
+        .chain(order.iter().map(|&index| &segments[pinned + index]))
+    {
+        output.push_str(segment);
+        output.push('\n');
+    }
+
+    Ok(Variant {
+        code: output,
+        moved: order
+            .iter()
+            .enumerate()
+            .filter(|(position, index)| position != *index)
+            .count(),
+    })
+}
+
+/// Number of leading statements that must stay first: a docstring and `__future__` imports
+fn pinned_prefix(body: &[Stmt]) -> usize {
+    let docstring = usize::from(matches!(
+        body.first(),
+        Some(Stmt::Expr(expr)) if expr.value.is_string_literal_expr()
+    ));
+    let is_future_import = |stmt: &&Stmt| {
+        matches!(stmt, Stmt::ImportFrom(import_from)
+            if import_from.module.as_ref().is_some_and(|module| module == "__future__"))
+    };
+    docstring
+        + body[docstring..]
+            .iter()
+            .take_while(is_future_import)
+            .count()
+}
+
+/// Source text of `stmt`, including a trailing comment on its last line
+fn segment<'a>(code: &'a str, stmt: &Stmt, next: Option<&Stmt>) -> &'a str {
+    let start = usize::from(stmt.start());
+    let end = usize::from(stmt.end());
+    let line_end = code[end..]
+        .find('\n')
+        .map_or(code.len(), |offset| end + offset);
+    if next.is_some_and(|next| usize::from(next.start()) < line_end) {
+        &code[start..end]
+    } else {
+        code[start..line_end].trim_end()
+    }
+}
+
+/// For each statement, the earlier statements it must stay after
+fn constraints(facts: &[StatementFacts]) -> Vec<Vec<usize>> {
+    let mut name_reads: FxIndexMap<&str, FxIndexSet<&str>> = FxIndexMap::default();
+    let mut name_globals: FxIndexMap<&str, FxIndexSet<&str>> = FxIndexMap::default();
+    for fact in facts {
+        for bound in &fact.binds {
+            name_reads
+                .entry(bound.as_str())
+                .or_default()
+                .extend(fact.reads.iter().map(String::as_str));
+            name_globals
+                .entry(bound.as_str())
+                .or_default()
+                .extend(fact.global_binds.iter().map(String::as_str));
+        }
+    }
+
+    // Reads and binds including those of the functions and classes a statement references
+    let closed: Vec<(FxIndexSet<&str>, FxIndexSet<&str>)> = facts
+        .iter()
+        .map(|fact| {
+            let mut reads: FxIndexSet<&str> = fact.reads.iter().map(String::as_str).collect();
+            let mut index = 0;
+            while let Some(&name) = reads.get_index(index) {
+                if let Some(referenced) = name_reads.get(name) {
+                    reads.extend(referenced.iter().copied());
+                }
+                index += 1;
+            }
+            let mut binds: FxIndexSet<&str> = fact
+                .binds
+                .iter()
+                .chain(&fact.global_binds)
+                .map(String::as_str)
+                .collect();
+            for name in &reads {
+                if let Some(globals) = name_globals.get(name) {
+                    binds.extend(globals.iter().copied());
+                }
+            }
+            (reads, binds)
+        })
+        .collect();
+
+    (0..facts.len())
+        .map(|later| {
+            (0..later)
+                .filter(|&earlier| {
+                    let (earlier_reads, earlier_binds) = &closed[earlier];
+                    let (later_reads, later_binds) = &closed[later];
+                    facts[earlier].barrier
+                        || facts[later].barrier
+                        || (facts[earlier].effectful && facts[later].effectful)
+                        || !earlier_reads.is_disjoint(later_binds)
+                        || !earlier_binds.is_disjoint(later_reads)
+                        || !earlier_binds.is_disjoint(later_binds)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A random topological order of the statements, picking among the ready ones at each step
+fn shuffled_order(predecessors: &[Vec<usize>], rng: &mut SplitMix64) -> Vec<usize> {
+    let mut remaining: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut successors = vec![Vec::new(); predecessors.len()];
+    for (later, earlier) in predecessors.iter().enumerate() {
+        for &earlier in earlier {
+            successors[earlier].push(later);
+        }
+    }
+
+    let mut ready: Vec<usize> = (0..predecessors.len())
+        .filter(|&index| remaining[index] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(predecessors.len());
+    while !ready.is_empty() {
+        let index = ready.swap_remove(rng.below(ready.len()));
+        order.push(index);
+        for &successor in &successors[index] {
+            remaining[successor] -= 1;
+            if remaining[successor] == 0 {
+                ready.push(successor);
+            }
+        }
+    }
+    order
+}
+
+/// Deterministic pseudo-random numbers (SplitMix64), so variants are reproducible from a seed
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number below `bound` (which must not be zero)
+    const fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// What a top-level statement binds, reads and does when the module runs
+#[derive(Debug, Default)]
+struct StatementFacts {
+    /// Module-level names bound by the statement
+    binds: FxIndexSet<String>,
+    /// Names read anywhere in the statement, including function bodies
+    reads: FxIndexSet<String>,
+    /// Names declared `global` in functions the statement defines
+    global_binds: FxIndexSet<String>,
+    /// Whether running the statement can have effects beyond binding names
+    effectful: bool,
+    /// Whether the statement must keep its position relative to every other statement
+    barrier: bool,
+    /// Nesting of function and class scopes
+    depth: usize,
+    /// Whether the code being visited runs when the statement runs
+    executing: bool,
+}
+
+impl StatementFacts {
+    fn of(stmt: &Stmt) -> Self {
+        let mut facts = Self {
+            executing: true,
+            ..Self::default()
+        };
+        facts.visit_stmt(stmt);
+        facts
+    }
+
+    fn visit_scope_body(&mut self, body: &[Stmt], executing: bool) {
+        let saved = self.executing;
+        self.executing = executing && saved;
+        self.depth += 1;
+        self.visit_body(body);
+        self.depth -= 1;
+        self.executing = saved;
+    }
+}
+
+impl<'a> SourceOrderVisitor<'a> for StatementFacts {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(function) => {
+                if self.depth == 0 {
+                    self.binds.insert(function.name.to_string());
+                }
+                self.effectful |= self.executing && !function.decorator_list.is_empty();
+                for decorator in &function.decorator_list {
+                    self.visit_decorator(decorator);
+                }
+                self.visit_parameters(&function.parameters);
+                if let Some(returns) = &function.returns {
+                    self.visit_expr(returns);
+                }
+                self.visit_scope_body(&function.body, false);
+            }
+            Stmt::ClassDef(class) => {
+                if self.depth == 0 {
+                    self.binds.insert(class.name.to_string());
+                }
+                self.effectful |= self.executing
+                    && (class.arguments.is_some() || !class.decorator_list.is_empty());
+                for decorator in &class.decorator_list {
+                    self.visit_decorator(decorator);
+                }
+                if let Some(arguments) = &class.arguments {
+                    self.visit_arguments(arguments);
+                }
+                self.visit_scope_body(&class.body, true);
+            }
+            Stmt::Global(global) => {
+                self.global_binds
+                    .extend(global.names.iter().map(ToString::to_string));
+            }
+            Stmt::Import(import) => {
+                self.effectful |= self.executing;
+                if self.depth == 0 {
+                    self.binds.extend(import.names.iter().map(|alias| {
+                        alias.asname.as_ref().map_or_else(
+                            || alias.name.split('.').next().unwrap_or_default().to_owned(),
+                            ToString::to_string,
+                        )
+                    }));
+                }
+            }
+            Stmt::ImportFrom(import_from) => {
+                self.effectful |= self.executing;
+                if self.depth == 0 {
+                    for alias in &import_from.names {
+                        if alias.name.as_str() == "*" {
+                            self.barrier = true;
+                        }
+                        self.binds
+                            .insert(alias.asname.as_ref().unwrap_or(&alias.name).to_string());
+                    }
+                }
+            }
+            Stmt::AugAssign(aug_assign) => {
+                if let Expr::Name(name) = aug_assign.target.as_ref() {
+                    self.reads.insert(name.id.to_string());
+                }
+                walk_stmt(self, stmt);
+            }
+            Stmt::Assign(_)
+            | Stmt::AnnAssign(_)
+            | Stmt::TypeAlias(_)
+            | Stmt::Expr(_)
+            | Stmt::Pass(_)
+            | Stmt::Delete(_) => walk_stmt(self, stmt),
+            _ => {
+                // Control flow, `raise`, `assert`, `with` and the like
+                self.effectful |= self.executing;
+                walk_stmt(self, stmt);
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) => match name.ctx {
+                ExprContext::Load => {
+                    self.barrier |= BARRIER_NAMES.contains(&name.id.as_str());
+                    self.reads.insert(name.id.to_string());
+                }
+                _ if self.depth == 0 => {
+                    self.binds.insert(name.id.to_string());
+                }
+                _ => {}
+            },
+            Expr::Call(_) | Expr::Await(_) | Expr::Yield(_) | Expr::YieldFrom(_) => {
+                self.effectful |= self.executing;
+            }
+            Expr::Attribute(attribute) if !matches!(attribute.ctx, ExprContext::Load) => {
+                self.effectful |= self.executing;
+            }
+            Expr::Subscript(subscript) if !matches!(subscript.ctx, ExprContext::Load) => {
+                self.effectful |= self.executing;
+            }
+            Expr::Lambda(lambda) => {
+                if let Some(parameters) = &lambda.parameters {
+                    self.visit_parameters(parameters);
+                }
+                let saved = self.executing;
+                self.executing = false;
+                self.visit_expr(&lambda.body);
+                self.executing = saved;
+                return;
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLE: &str = "\
+#!/usr/bin/env python3
+\"\"\"Bundle.\"\"\"
+from __future__ import annotations
+LIMIT = 3
+NAME = 'demo'
+SCALE = 2
+def scaled(x):
+    return x * SCALE
+def greet():
+    return NAME
+print(scaled(LIMIT))  # show it
+print(greet())
+";
+
+    fn position(code: &str, line: &str) -> usize {
+        code.find(line)
+            .unwrap_or_else(|| panic!("`{line}` is missing from the variant"))
+    }
+
+    #[test]
+    fn test_reorder_keeps_dependencies_and_pinned_prefix() -> Result<()> {
+        let mut variants = FxIndexSet::default();
+        for seed in 0..20 {
+            let variant = reorder(BUNDLE, seed)?;
+            let code = &variant.code;
+            assert!(code.starts_with(
+                "#!/usr/bin/env python3\n\"\"\"Bundle.\"\"\"\nfrom __future__ import annotations\n"
+            ));
+            assert!(position(code, "SCALE = 2") < position(code, "print(scaled(LIMIT))"));
+            assert!(position(code, "LIMIT = 3") < position(code, "print(scaled(LIMIT))"));
+            assert!(position(code, "def scaled(x)") < position(code, "print(scaled(LIMIT))"));
+            assert!(position(code, "NAME = 'demo'") < position(code, "print(greet())"));
+            assert!(
+                position(code, "print(scaled(LIMIT))  # show it")
+                    < position(code, "print(greet())")
+            );
+            assert_eq!(code.lines().count(), BUNDLE.lines().count());
+            variants.insert(variant.code);
+        }
+        assert!(variants.len() > 1);
+        assert_eq!(reorder(BUNDLE, 7)?.code, reorder(BUNDLE, 7)?.code);
+        Ok(())
+    }
+
+    #[test]
+    fn test_barriers_and_globals_keep_their_position() -> Result<()> {
+        let source = "\
+COUNT = 0
+def bump():
+    global COUNT
+    COUNT += 1
+snapshot = COUNT
+exported = dict(globals())
+LATE = 1
+";
+        for seed in 0..20 {
+            let code = reorder(source, seed)?.code;
+            assert!(position(&code, "def bump()") < position(&code, "snapshot = COUNT"));
+            assert!(position(&code, "snapshot = COUNT") < position(&code, "exported ="));
+            assert!(position(&code, "exported =") < position(&code, "LATE = 1"));
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    collections::BTreeMap,
     ffi::OsStr,
     fmt,
     io::{BufRead, BufReader},
@@ -16,6 +17,27 @@ use ruff_python_stdlib::sys;
 
 use crate::{config::Config, types::FxIndexMap};
 
+/// Standard library modules imported by the interpreter before user code runs
+///
+/// These are loaded by the import system bootstrap, the codec machinery and `site`, so they
+/// are always present in `sys.modules` and must keep referring to the interpreter's copy.
+const STARTUP_STDLIB_MODULES: &[&str] = &[
+    "_collections_abc",
+    "_sitebuiltins",
+    "abc",
+    "codecs",
+    "encodings",
+    "genericpath",
+    "importlib",
+    "io",
+    "ntpath",
+    "os",
+    "posixpath",
+    "site",
+    "stat",
+    "zipimport",
+];
+
 /// Unique identifier for a module in the dependency graph
 /// The entry module ALWAYS has ID 0 - this is a fundamental invariant
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -91,7 +113,8 @@ pub struct ModuleMetadata {
 struct ModuleRegistry {
     next_id: u32,
     by_id: FxIndexMap<ModuleId, ModuleMetadata>,
-    by_name: FxIndexMap<String, ModuleId>,
+    /// Sorted by name, so the modules under a package form a contiguous range
+    by_name: BTreeMap<String, ModuleId>,
     by_path: FxIndexMap<PathBuf, ModuleId>,
 }
 
@@ -100,7 +123,7 @@ impl ModuleRegistry {
         Self {
             next_id: 0, // Start at 0 - entry point gets this
             by_id: FxIndexMap::default(),
-            by_name: FxIndexMap::default(),
+            by_name: BTreeMap::new(),
             by_path: FxIndexMap::default(),
         }
     }
@@ -212,6 +235,17 @@ impl ModuleRegistry {
     fn get_id_by_name(&self, name: &str) -> Option<&ModuleId> {
         self.by_name.get(name)
     }
+
+    /// Modules whose name starts with `prefix`, in name order
+    ///
+    /// Walks only the matching range of the sorted name index instead of every module.
+    fn find_all_by_path_prefix(&self, prefix: &str) -> Vec<(ModuleId, &str)> {
+        self.by_name
+            .range::<str, _>(prefix..)
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .map(|(name, &id)| (id, name.as_str()))
+            .collect()
+    }
 }
 
 /// Resolve a relative import based on module name (standalone utility)
@@ -313,13 +347,25 @@ impl ImportModuleDescriptor {
     }
 }
 
+/// Inputs that determine the module search path
+///
+/// Resolution results are only valid for the search path they were computed with, so the
+/// module cache is partitioned by context.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ResolutionContext {
+    entry_dir: Option<PathBuf>,
+    pythonpath: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct ModuleResolver {
     config: Config,
     /// Module registry for ID allocation - the single source of truth for module identity
     registry: Mutex<ModuleRegistry>,
-    /// Cache of resolved module paths
-    module_cache: RefCell<IndexMap<String, Option<PathBuf>>>,
+    /// Search path contexts seen so far; a context's index is its `resolution_context_id`
+    resolution_contexts: RefCell<IndexSet<ResolutionContext>>,
+    /// Cache of resolved module paths, keyed by `resolution_context_id`
+    module_cache: RefCell<IndexMap<usize, IndexMap<String, Option<PathBuf>>>>,
     /// Cache of module classifications
     classification_cache: RefCell<IndexMap<String, ImportType>>,
     /// Cache of virtual environment packages to avoid repeated filesystem scans
@@ -361,6 +407,7 @@ impl ModuleResolver {
         Self {
             config,
             registry: Mutex::new(ModuleRegistry::new()),
+            resolution_contexts: RefCell::new(IndexSet::new()),
             module_cache: RefCell::new(IndexMap::new()),
             classification_cache: RefCell::new(IndexMap::new()),
             virtualenv_packages_cache: RefCell::new(None),
@@ -469,6 +516,16 @@ impl ModuleResolver {
         registry.get_id_by_name(name).copied()
     }
 
+    /// Get every registered module under `package` (e.g. `pkg.utils` for `pkg`), in name order
+    pub fn find_submodules(&self, package: &str) -> Vec<(ModuleId, String)> {
+        let registry = self.registry.lock().expect("Module registry lock poisoned");
+        registry
+            .find_all_by_path_prefix(&format!("{package}."))
+            .into_iter()
+            .map(|(id, name)| (id, name.to_owned()))
+            .collect()
+    }
+
     /// Get module ID by path (reverse lookup)
     pub fn get_module_id_by_path(&self, path: &Path) -> Option<ModuleId> {
         let canonical_path = self.canonicalize_path(path.to_path_buf());
@@ -523,6 +580,12 @@ impl ModuleResolver {
             }
         }
 
+        // 4. Standard library directory embedded with `--embed-stdlib` comes last, so it never
+        //    shadows first-party modules
+        if let Some(stdlib_dir) = &self.config.embed_stdlib {
+            unique_dirs.insert(self.canonicalize_path(stdlib_dir.clone()));
+        }
+
         unique_dirs.into_iter().collect()
     }
 
@@ -561,6 +624,16 @@ impl ModuleResolver {
         Ok(None)
     }
 
+    /// Find the search directory an absolute module name resolves in
+    ///
+    /// Directories are tried in search path order, so this is the entry whose module wins.
+    pub fn find_search_directory(&self, module_name: &str) -> Option<PathBuf> {
+        let descriptor = ImportModuleDescriptor::from_module_name(module_name);
+        self.get_search_directories()
+            .into_iter()
+            .find(|search_dir| self.resolve_in_directory(search_dir, &descriptor).is_some())
+    }
+
     /// Resolve a module with optional current module context for relative imports
     pub fn resolve_module_path_with_context(
         &self,
@@ -568,7 +641,13 @@ impl ModuleResolver {
         current_module_path: Option<&Path>,
     ) -> Result<Option<PathBuf>> {
         // Check cache first
-        if let Some(cached_path) = self.module_cache.borrow().get(module_name) {
+        let context_id = self.resolution_context_id();
+        if let Some(cached_path) = self
+            .module_cache
+            .borrow()
+            .get(&context_id)
+            .and_then(|cache| cache.get(module_name))
+        {
             return Ok(cached_path.clone());
         }
 
@@ -593,6 +672,8 @@ impl ModuleResolver {
             if let Some(resolved_path) = self.resolve_in_directory(search_dir, &descriptor) {
                 self.module_cache
                     .borrow_mut()
+                    .entry(context_id)
+                    .or_default()
                     .insert(module_name.to_owned(), Some(resolved_path.clone()));
                 return Ok(Some(resolved_path));
             }
@@ -601,10 +682,27 @@ impl ModuleResolver {
         // Not found - cache the negative result
         self.module_cache
             .borrow_mut()
+            .entry(context_id)
+            .or_default()
             .insert(module_name.to_owned(), None);
         Ok(None)
     }
 
+    /// Identify the current search path configuration
+    ///
+    /// Changing the entry directory or `PYTHONPATH` yields a new id, so results cached under a
+    /// different search path are never reused.
+    fn resolution_context_id(&self) -> usize {
+        let context = ResolutionContext {
+            entry_dir: self.entry_dir.clone(),
+            pythonpath: self
+                .pythonpath_override
+                .clone()
+                .or_else(|| std::env::var("PYTHONPATH").ok()),
+        };
+        self.resolution_contexts.borrow_mut().insert_full(context).0
+    }
+
     /// Resolve a relative import given the current module's path
     fn resolve_relative_import(
         &self,
@@ -806,6 +904,29 @@ impl ModuleResolver {
         None
     }
 
+    /// Check whether a standard library module is bundled from the `embed_stdlib` directory
+    ///
+    /// Only modules with Python source there can be embedded; builtin modules and C extensions
+    /// (`sys`, `math`, `_io`) are still imported from the running interpreter. Modules the
+    /// interpreter has already imported during startup are never embedded either: a second copy
+    /// of `abc` or `codecs` would not share state (ABC registries, codec search functions) with
+    /// the one the rest of the runtime uses.
+    fn is_embedded_stdlib_module(&self, module_name: &str) -> bool {
+        let root_module = module_name.split('.').next().unwrap_or(module_name);
+        if STARTUP_STDLIB_MODULES.contains(&root_module) {
+            return false;
+        }
+
+        self.config
+            .embed_stdlib
+            .as_deref()
+            .is_some_and(|stdlib_dir| {
+                let descriptor = ImportModuleDescriptor::from_module_name(module_name);
+                self.resolve_in_directory(stdlib_dir, &descriptor)
+                    .is_some_and(|path| path.is_file())
+            })
+    }
+
     /// Classify an import as first-party, third-party, or standard library
     pub fn classify_import(&self, module_name: &str) -> ImportType {
         // Check cache first
@@ -838,9 +959,14 @@ impl ModuleResolver {
             return import_type;
         }
 
-        // Check if it's a standard library module
+        // Check if it's a standard library module; pure Python ones are first-party when the
+        // standard library is embedded
         if is_stdlib_module(module_name, self.python_version) {
-            let import_type = ImportType::StandardLibrary;
+            let import_type = if self.is_embedded_stdlib_module(module_name) {
+                ImportType::FirstParty
+            } else {
+                ImportType::StandardLibrary
+            };
             self.classification_cache
                 .borrow_mut()
                 .insert(module_name.to_owned(), import_type.clone());
@@ -1643,6 +1769,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_submodules() {
+        let resolver = ModuleResolver::new(Config::default());
+        resolver.register_module("main", Path::new("main.py"));
+        let utils = resolver.register_module("pkg.utils", Path::new("pkg/utils/__init__.py"));
+        let helpers =
+            resolver.register_module("pkg.utils.helpers", Path::new("pkg/utils/helpers.py"));
+        let utilities = resolver.register_module("pkg.utilities", Path::new("pkg/utilities.py"));
+        resolver.register_module("pkg", Path::new("pkg/__init__.py"));
+
+        assert_eq!(
+            resolver.find_submodules("pkg.utils"),
+            vec![(helpers, "pkg.utils.helpers".to_owned())]
+        );
+        assert_eq!(
+            resolver.find_submodules("pkg"),
+            vec![
+                (utilities, "pkg.utilities".to_owned()),
+                (utils, "pkg.utils".to_owned()),
+                (helpers, "pkg.utils.helpers".to_owned()),
+            ]
+        );
+        assert!(resolver.find_submodules("pkg.utils.helpers").is_empty());
+    }
+
+    #[test]
+    fn test_embedded_stdlib_classification() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let stdlib = temp_dir.path().join("python3.12");
+        create_test_file(&stdlib.join("textwrap.py"), "")?;
+        create_test_file(&stdlib.join("json/__init__.py"), "")?;
+        create_test_file(&stdlib.join("json/decoder.py"), "")?;
+        create_test_file(&stdlib.join("os.py"), "")?;
+        create_test_file(&stdlib.join("abc.py"), "")?;
+        create_test_file(&stdlib.join("encodings/__init__.py"), "")?;
+        create_test_file(&stdlib.join("encodings/utf_8.py"), "")?;
+
+        let config = Config {
+            embed_stdlib: Some(stdlib.clone()),
+            ..Default::default()
+        };
+        let resolver = ModuleResolver::new(config);
+
+        // Pure Python modules are bundled, builtins and C extensions stay imports
+        assert_eq!(resolver.classify_import("textwrap"), ImportType::FirstParty);
+        assert_eq!(resolver.classify_import("json"), ImportType::FirstParty);
+        assert_eq!(
+            resolver.classify_import("json.decoder"),
+            ImportType::FirstParty
+        );
+        assert_eq!(resolver.classify_import("sys"), ImportType::StandardLibrary);
+        assert_eq!(
+            resolver.classify_import("math"),
+            ImportType::StandardLibrary
+        );
+        // Modules already imported at interpreter startup keep using the running copy
+        assert_eq!(resolver.classify_import("os"), ImportType::StandardLibrary);
+        assert_eq!(resolver.classify_import("abc"), ImportType::StandardLibrary);
+        assert_eq!(
+            resolver.classify_import("encodings.utf_8"),
+            ImportType::StandardLibrary
+        );
+        assert_eq!(
+            resolver.resolve_module_path("json.decoder")?,
+            Some(stdlib.join("json/decoder.py").canonicalize()?)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_namespace_package() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1806,6 +2002,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_module_cache_is_separated_by_search_path() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_dir = temp_dir.path().join("project");
+        let entry_path = project_dir.join("main.py");
+        create_test_file(&entry_path, "import helper")?;
+        create_test_file(&project_dir.join("helper.py"), "# helper")?;
+
+        let empty_pythonpath = temp_dir.path().join("empty").to_string_lossy().into_owned();
+        let mut resolver =
+            ModuleResolver::new_with_overrides(Config::default(), Some(&empty_pythonpath), None);
+
+        // Not resolvable before the entry directory joins the search path
+        assert!(resolver.resolve_module_path("helper")?.is_none());
+
+        // The cached negative result must not leak into the new search path
+        resolver.set_entry_file(&entry_path, &entry_path);
+        assert_eq!(
+            resolver.resolve_module_path("helper")?,
+            Some(project_dir.join("helper.py").canonicalize()?)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_pythonpath_module_discovery() -> Result<()> {
         // Create temporary directories for testing
@@ -0,0 +1,249 @@
+//! Replacing self-version lookups with the project version
+//!
+//! Packages commonly discover their own version with `importlib.metadata.version("mypkg")`.
+//! A bundle ships without the distribution metadata, so that call raises
+//! `PackageNotFoundError` at runtime. When the bundled project's `pyproject.toml` declares a
+//! static version, calls looking up the project's own distribution are replaced with that
+//! version string before bundling. Lookups of other distributions are left alone.
+
+use std::{cell::Cell, fs, path::Path};
+
+use cow_utils::CowUtils;
+use log::debug;
+use pep508_rs::PackageName;
+use ruff_python_ast::{
+    Expr, ExprStringLiteral, ModModule, Stmt,
+    visitor::transformer::{Transformer, walk_expr},
+};
+
+use crate::{ast_builder::expressions, types::FxIndexSet};
+
+/// Modules providing a `version(distribution_name)` function
+const METADATA_MODULES: &[&str] = &["importlib.metadata", "importlib_metadata"];
+
+/// Name and static version of the project being bundled, from `pyproject.toml`
+#[derive(Debug, Clone)]
+pub(crate) struct ProjectVersion {
+    /// PEP 503 normalized distribution name
+    name: String,
+    version: String,
+}
+
+impl ProjectVersion {
+    /// Read `[project]` name and version from the nearest `pyproject.toml` at or above `dir`
+    ///
+    /// Returns `None` when there is no `pyproject.toml`, or when the version is dynamic.
+    pub(crate) fn discover(dir: &Path) -> Option<Self> {
+        let pyproject = dir
+            .ancestors()
+            .map(|ancestor| ancestor.join("pyproject.toml"))
+            .find(|path| path.is_file())?;
+        let content = fs::read_to_string(&pyproject).ok()?;
+        let table: toml::Table = toml::from_str(&content)
+            .inspect_err(|err| debug!("Failed to parse {}: {err}", pyproject.display()))
+            .ok()?;
+        let project = table.get("project")?.as_table()?;
+        let name = project.get("name")?.as_str()?;
+        let Some(version) = project.get("version").and_then(toml::Value::as_str) else {
+            debug!(
+                "{} has no static project version, leaving version lookups alone",
+                pyproject.display()
+            );
+            return None;
+        };
+        Some(Self {
+            name: normalize(name),
+            version: version.to_owned(),
+        })
+    }
+
+    /// Replace `version("<project>")` calls in `module` with the version string
+    ///
+    /// Returns the number of replaced calls.
+    pub(crate) fn rewrite_module(&self, module: &mut ModModule) -> usize {
+        let mut aliases = MetadataAliases::default();
+        aliases.collect(&module.body);
+        if aliases.is_empty() {
+            return 0;
+        }
+
+        let rewriter = VersionCallRewriter {
+            project: self,
+            aliases: &aliases,
+            replaced: Cell::new(0),
+        };
+        rewriter.visit_body(&mut module.body);
+        rewriter.replaced.get()
+    }
+}
+
+fn normalize(name: &str) -> String {
+    PackageName::new(name.to_owned()).map_or_else(
+        |_| name.cow_to_lowercase().into_owned(),
+        |package_name| package_name.to_string(),
+    )
+}
+
+/// Local names through which a module reaches a metadata `version` function
+#[derive(Debug, Default)]
+struct MetadataAliases {
+    /// Names bound to the `version` function (`from importlib.metadata import version`)
+    functions: FxIndexSet<String>,
+    /// Names bound to a metadata module (`import importlib.metadata as md`)
+    modules: FxIndexSet<String>,
+    /// Whether `importlib.metadata` is reachable as a dotted attribute path
+    dotted: bool,
+}
+
+impl MetadataAliases {
+    fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.modules.is_empty() && !self.dotted
+    }
+
+    /// Collect aliases from imports anywhere in `body`, including nested scopes
+    fn collect(&mut self, body: &[Stmt]) {
+        for stmt in body {
+            match stmt {
+                Stmt::Import(import) => {
+                    for alias in &import.names {
+                        let module = alias.name.as_str();
+                        match &alias.asname {
+                            Some(asname) if METADATA_MODULES.contains(&module) => {
+                                self.modules.insert(asname.to_string());
+                            }
+                            None if module == "importlib_metadata" => {
+                                self.modules.insert(module.to_owned());
+                            }
+                            None if module == "importlib.metadata" => self.dotted = true,
+                            _ => {}
+                        }
+                    }
+                }
+                Stmt::ImportFrom(import_from) if import_from.level == 0 => {
+                    let module = import_from.module.as_deref().unwrap_or_default();
+                    for alias in &import_from.names {
+                        let local = alias.asname.as_ref().unwrap_or(&alias.name).to_string();
+                        if METADATA_MODULES.contains(&module) && alias.name.as_str() == "version" {
+                            self.functions.insert(local);
+                        } else if module == "importlib" && alias.name.as_str() == "metadata" {
+                            self.modules.insert(local);
+                        }
+                    }
+                }
+                Stmt::FunctionDef(func_def) => self.collect(&func_def.body),
+                Stmt::ClassDef(class_def) => self.collect(&class_def.body),
+                Stmt::If(if_stmt) => {
+                    self.collect(&if_stmt.body);
+                    for clause in &if_stmt.elif_else_clauses {
+                        self.collect(&clause.body);
+                    }
+                }
+                Stmt::Try(try_stmt) => {
+                    self.collect(&try_stmt.body);
+                    for handler in &try_stmt.handlers {
+                        let ruff_python_ast::ExceptHandler::ExceptHandler(handler) = handler;
+                        self.collect(&handler.body);
+                    }
+                    self.collect(&try_stmt.orelse);
+                    self.collect(&try_stmt.finalbody);
+                }
+                Stmt::With(with_stmt) => self.collect(&with_stmt.body),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `func` refers to a metadata `version` function
+    fn is_version_function(&self, func: &Expr) -> bool {
+        match func {
+            Expr::Name(name) => self.functions.contains(name.id.as_str()),
+            Expr::Attribute(attr) if attr.attr.as_str() == "version" => match &*attr.value {
+                Expr::Name(module) => self.modules.contains(module.id.as_str()),
+                Expr::Attribute(module) => {
+                    self.dotted
+                        && module.attr.as_str() == "metadata"
+                        && matches!(&*module.value, Expr::Name(root) if root.id.as_str() == "importlib")
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+struct VersionCallRewriter<'a> {
+    project: &'a ProjectVersion,
+    aliases: &'a MetadataAliases,
+    replaced: Cell<usize>,
+}
+
+impl Transformer for VersionCallRewriter<'_> {
+    fn visit_expr(&self, expr: &mut Expr) {
+        if let Expr::Call(call) = expr
+            && call.arguments.keywords.is_empty()
+            && let [Expr::StringLiteral(ExprStringLiteral { value, .. })] = &*call.arguments.args
+            && normalize(value.to_str()) == self.project.name
+            && self.aliases.is_version_function(&call.func)
+        {
+            debug!(
+                "Replacing version lookup of '{}' with '{}'",
+                value.to_str(),
+                self.project.version
+            );
+            *expr = expressions::string_literal(&self.project.version);
+            self.replaced.set(self.replaced.get() + 1);
+            return;
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite_replaces_own_version_lookups_only() {
+        let project = ProjectVersion {
+            name: normalize("My_Pkg"),
+            version: "1.2.3".to_owned(),
+        };
+        let source = "\
+import importlib.metadata
+from importlib import metadata as md
+from importlib.metadata import version as dist_version
+
+__version__ = importlib.metadata.version('my-pkg')
+other = md.version('requests')
+
+def get_version():
+    return Version(dist_version('my.pkg'))
+";
+        let mut module = parse_module(source)
+            .expect("Test code should parse successfully")
+            .into_syntax();
+
+        assert_eq!(project.rewrite_module(&mut module), 2);
+        let is_version = |expr: &Expr| matches!(expr, Expr::StringLiteral(literal) if literal.value.to_str() == "1.2.3");
+        let Stmt::Assign(own) = &module.body[3] else {
+            panic!("expected an assignment");
+        };
+        assert!(is_version(&own.value));
+        let Stmt::Assign(other) = &module.body[4] else {
+            panic!("expected an assignment");
+        };
+        assert!(other.value.is_call_expr());
+        let Stmt::FunctionDef(func_def) = &module.body[5] else {
+            panic!("expected a function definition");
+        };
+        let Stmt::Return(ret) = &func_def.body[0] else {
+            panic!("expected a return statement");
+        };
+        let Some(Expr::Call(call)) = ret.value.as_deref() else {
+            panic!("expected a call");
+        };
+        assert!(is_version(&call.arguments.args[0]));
+    }
+}
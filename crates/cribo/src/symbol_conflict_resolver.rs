@@ -549,6 +549,11 @@ impl SymbolConflictResolver {
     }
 
     /// Detect and resolve symbol conflicts across all modules
+    ///
+    /// Note: the order of the conflicting definitions carries no priority. Every definition,
+    /// including the first, is renamed and each module refers to its own renamed copy, so no
+    /// definition can shadow another one (`isinstance(x, helper)` resolves to whichever
+    /// `helper` its module defines or imports); the order only picks the numeric suffixes.
     pub(crate) fn detect_and_resolve_conflicts(&mut self) -> Vec<SymbolConflict> {
         let conflicts = self.global_symbols.detect_conflicts();
 
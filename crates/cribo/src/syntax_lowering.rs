@@ -0,0 +1,1045 @@
+//! Lowering syntax for an older Python version
+//!
+//! `--output-python-version` produces a bundle for a Python older than the one the sources
+//! were written for. Before code generation the bundled AST is rewritten:
+//!
+//! - assignment expressions (3.8) become assignments hoisted in front of their statement,
+//! - `match` statements (3.10) become `if`/`elif` chains over a temporary subject,
+//! - self-documenting f-string fields (`f"{x=}"`, 3.8) become a literal followed by the field.
+//!
+//! An assignment expression is only hoisted when nothing else in its statement is evaluated
+//! before it, as in `if (m := pattern.match(line)) is not None:`. Syntax the target cannot parse
+//! and that is not lowered (such assignment expressions, positional-only parameters, `except*`,
+//! type parameter lists and `type` statements) is reported as an error rather than producing a
+//! bundle the target cannot parse.
+
+use anyhow::{Result, anyhow};
+use ruff_python_ast::{
+    AtomicNodeIndex, BoolOp, CmpOp, ConversionFlag, ElifElseClause, Expr, ExprContext, FStringPart,
+    InterpolatedStringElement, InterpolatedStringElements, InterpolatedStringLiteralElement,
+    ModModule, Parameters, Pattern, Singleton, Stmt, StmtBreak, StmtDelete, StmtIf, StmtMatch,
+    StmtWhile, UnaryOp,
+    visitor::{
+        Visitor,
+        transformer::{self, Transformer},
+        walk_expr, walk_stmt,
+    },
+};
+use ruff_python_codegen::{Generator, Stylist};
+use ruff_text_size::TextRange;
+
+use crate::ast_builder::{CRIBO_PREFIX, expressions, statements};
+
+/// Minor version that introduced assignment expressions and self-documenting f-strings
+const NAMED_EXPR_MINOR: u8 = 8;
+
+/// Minor version that introduced positional-only parameters
+const POSITIONAL_ONLY_MINOR: u8 = 8;
+
+/// Minor version that introduced `match` statements
+const MATCH_MINOR: u8 = 10;
+
+/// Minor version that introduced `except*`
+const EXCEPT_STAR_MINOR: u8 = 11;
+
+/// Minor version that introduced type parameter lists and `type` statements
+const TYPE_PARAMS_MINOR: u8 = 12;
+
+/// Builtin classes whose single positional sub-pattern matches the subject itself
+const SELF_MATCHING_CLASSES: &[&str] = &[
+    "bool",
+    "bytearray",
+    "bytes",
+    "dict",
+    "float",
+    "frozenset",
+    "int",
+    "list",
+    "set",
+    "str",
+    "tuple",
+];
+
+/// Rewrites syntax the target Python version does not support
+pub(crate) struct SyntaxLowerer<'a> {
+    /// Minor version of the Python 3 release the bundle must run on
+    target_minor: u8,
+    stylist: &'a Stylist<'a>,
+    /// Number of temporary match subjects created so far
+    match_subjects: usize,
+}
+
+impl<'a> SyntaxLowerer<'a> {
+    pub(crate) const fn new(target_minor: u8, stylist: &'a Stylist<'a>) -> Self {
+        Self {
+            target_minor,
+            stylist,
+            match_subjects: 0,
+        }
+    }
+
+    /// Lower the syntax of `module` the target version does not support
+    ///
+    /// Returns an error naming the constructs that could not be lowered.
+    pub(crate) fn lower_module(&mut self, module: &mut ModModule) -> Result<()> {
+        if self.target_minor < NAMED_EXPR_MINOR {
+            DebugFieldLowerer {
+                stylist: self.stylist,
+            }
+            .visit_body(&mut module.body);
+        }
+        self.lower_body(&mut module.body);
+
+        let mut remaining = RemainingSyntax {
+            target_minor: self.target_minor,
+            stylist: self.stylist,
+            found: Vec::new(),
+        };
+        remaining.visit_body(&module.body);
+        if remaining.found.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "Cannot lower the bundle to Python 3.{}:\n  {}",
+            self.target_minor,
+            remaining.found.join("\n  ")
+        ))
+    }
+
+    fn lower_body(&mut self, body: &mut Vec<Stmt>) {
+        let mut lowered = Vec::with_capacity(body.len());
+        for mut stmt in std::mem::take(body) {
+            self.lower_nested_bodies(&mut stmt);
+            match stmt {
+                Stmt::Match(match_stmt) if self.target_minor < MATCH_MINOR => {
+                    for stmt in self.lower_match(match_stmt) {
+                        lowered.extend(self.hoist_named_exprs(stmt));
+                    }
+                }
+                stmt => lowered.extend(self.hoist_named_exprs(stmt)),
+            }
+        }
+        *body = lowered;
+    }
+
+    fn lower_nested_bodies(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::FunctionDef(func_def) => self.lower_body(&mut func_def.body),
+            Stmt::ClassDef(class_def) => self.lower_body(&mut class_def.body),
+            Stmt::If(if_stmt) => {
+                self.lower_body(&mut if_stmt.body);
+                for clause in &mut if_stmt.elif_else_clauses {
+                    self.lower_body(&mut clause.body);
+                }
+            }
+            Stmt::For(for_stmt) => {
+                self.lower_body(&mut for_stmt.body);
+                self.lower_body(&mut for_stmt.orelse);
+            }
+            Stmt::While(while_stmt) => {
+                self.lower_body(&mut while_stmt.body);
+                self.lower_body(&mut while_stmt.orelse);
+            }
+            Stmt::With(with_stmt) => self.lower_body(&mut with_stmt.body),
+            Stmt::Try(try_stmt) => {
+                self.lower_body(&mut try_stmt.body);
+                for handler in &mut try_stmt.handlers {
+                    let ruff_python_ast::ExceptHandler::ExceptHandler(handler) = handler;
+                    self.lower_body(&mut handler.body);
+                }
+                self.lower_body(&mut try_stmt.orelse);
+                self.lower_body(&mut try_stmt.finalbody);
+            }
+            Stmt::Match(match_stmt) => {
+                for case in &mut match_stmt.cases {
+                    self.lower_body(&mut case.body);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Turn a `match` statement into an assignment of its subject and a chain of `if`s
+    ///
+    /// The statement is returned unchanged when one of its patterns cannot be expressed as
+    /// a condition.
+    fn lower_match(&mut self, match_stmt: StmtMatch) -> Vec<Stmt> {
+        let subject = format!("{CRIBO_PREFIX}_match_{}", self.match_subjects);
+        let subject_expr = expressions::name(&subject, ExprContext::Load);
+        let mut tests = Vec::with_capacity(match_stmt.cases.len());
+        for case in &match_stmt.cases {
+            let mut test = PatternTest::default();
+            if !test.compile(&case.pattern, &subject_expr) {
+                return vec![Stmt::Match(match_stmt)];
+            }
+            tests.push(test);
+        }
+        self.match_subjects += 1;
+
+        let has_guard = match_stmt.cases.iter().any(|case| case.guard.is_some());
+        let cases: Vec<_> = tests
+            .into_iter()
+            .zip(match_stmt.cases)
+            .map(|(test, case)| (test, case.guard.map(|guard| *guard), case.body))
+            .collect();
+        let mut lowered = vec![statements::simple_assign(&subject, *match_stmt.subject)];
+        if has_guard {
+            lowered.extend(self.guarded_case_chain(&subject, cases));
+        } else {
+            lowered.extend(case_chain(cases));
+        }
+        lowered
+    }
+
+    /// Cases with guards bind their captures before the guard runs, so a case whose guard
+    /// fails cannot be an `elif`; a flag records whether an earlier case was taken instead
+    fn guarded_case_chain(
+        &self,
+        subject: &str,
+        cases: Vec<(PatternTest, Option<Expr>, Vec<Stmt>)>,
+    ) -> Vec<Stmt> {
+        let found = format!("{subject}_found");
+        let mut chain = vec![statements::simple_assign(
+            &found,
+            expressions::bool_literal(false),
+        )];
+        for (index, (test, guard, body)) in cases.into_iter().enumerate() {
+            let mut taken = vec![statements::simple_assign(
+                &found,
+                expressions::bool_literal(true),
+            )];
+            taken.extend(body);
+            let mut case_body = test.bindings;
+            match guard {
+                Some(guard) => case_body.extend(self.hoist_named_exprs(statements::if_stmt(
+                    guard,
+                    taken,
+                    vec![],
+                ))),
+                None => case_body.extend(taken),
+            }
+
+            let mut conditions = test.conditions;
+            if index > 0 {
+                conditions.insert(
+                    0,
+                    expressions::unary_op(
+                        UnaryOp::Not,
+                        expressions::name(&found, ExprContext::Load),
+                    ),
+                );
+            }
+            match conjunction(conditions) {
+                Some(condition) => chain.push(statements::if_stmt(condition, case_body, vec![])),
+                None => chain.extend(case_body),
+            }
+        }
+        chain
+    }
+
+    /// Hoist the assignment expressions of `stmt` that are evaluated before anything else in it
+    fn hoist_named_exprs(&self, stmt: Stmt) -> Vec<Stmt> {
+        if self.target_minor >= NAMED_EXPR_MINOR {
+            return vec![stmt];
+        }
+        match stmt {
+            Stmt::If(if_stmt) => hoist_from_if(if_stmt),
+            Stmt::While(while_stmt) => hoist_from_while(while_stmt),
+            mut stmt => {
+                let mut hoisted = Vec::new();
+                if let Some(expr) = leading_expression(&mut stmt) {
+                    hoist_leading(expr, &mut hoisted);
+                }
+                hoisted.push(stmt);
+                hoisted
+            }
+        }
+    }
+}
+
+/// Chain of `if`/`elif` for cases without guards
+fn case_chain(cases: Vec<(PatternTest, Option<Expr>, Vec<Stmt>)>) -> Vec<Stmt> {
+    let mut clauses = Vec::with_capacity(cases.len());
+    for (test, _, body) in cases {
+        let condition = conjunction(test.conditions);
+        let mut case_body = test.bindings;
+        case_body.extend(body);
+        if case_body.is_empty() {
+            case_body.push(statements::pass());
+        }
+        // Nothing after an irrefutable case can match
+        let irrefutable = condition.is_none();
+        clauses.push((condition, case_body));
+        if irrefutable {
+            break;
+        }
+    }
+
+    let mut clauses = clauses.into_iter();
+    let Some((first_condition, first_body)) = clauses.next() else {
+        return Vec::new();
+    };
+    let Some(first_condition) = first_condition else {
+        return first_body;
+    };
+    vec![Stmt::If(StmtIf {
+        node_index: AtomicNodeIndex::NONE,
+        test: Box::new(first_condition),
+        body: first_body,
+        elif_else_clauses: clauses
+            .map(|(test, body)| ElifElseClause {
+                node_index: AtomicNodeIndex::NONE,
+                test,
+                body,
+                range: TextRange::default(),
+            })
+            .collect(),
+        range: TextRange::default(),
+    })]
+}
+
+fn conjunction(mut conditions: Vec<Expr>) -> Option<Expr> {
+    match conditions.len() {
+        0 => None,
+        1 => conditions.pop(),
+        _ => Some(expressions::bool_op(BoolOp::And, conditions)),
+    }
+}
+
+/// Conditions and captures that implement a pattern against a subject expression
+#[derive(Default)]
+struct PatternTest {
+    /// Checks that must all hold, in evaluation order
+    conditions: Vec<Expr>,
+    /// Capture assignments, run once the conditions hold
+    bindings: Vec<Stmt>,
+}
+
+impl PatternTest {
+    /// Add the checks and captures of `pattern` against `subject`
+    ///
+    /// Returns `false` for patterns that cannot be lowered (alternatives that capture names).
+    fn compile(&mut self, pattern: &Pattern, subject: &Expr) -> bool {
+        match pattern {
+            Pattern::MatchValue(value_pattern) => self.conditions.push(expressions::compare(
+                subject.clone(),
+                CmpOp::Eq,
+                (*value_pattern.value).clone(),
+            )),
+            Pattern::MatchSingleton(singleton_pattern) => {
+                let value = match singleton_pattern.value {
+                    Singleton::None => expressions::none_literal(),
+                    Singleton::True => expressions::bool_literal(true),
+                    Singleton::False => expressions::bool_literal(false),
+                };
+                self.conditions
+                    .push(expressions::compare(subject.clone(), CmpOp::Is, value));
+            }
+            Pattern::MatchAs(as_pattern) => {
+                if let Some(pattern) = &as_pattern.pattern
+                    && !self.compile(pattern, subject)
+                {
+                    return false;
+                }
+                if let Some(name) = &as_pattern.name {
+                    self.bindings
+                        .push(statements::simple_assign(name.as_str(), subject.clone()));
+                }
+            }
+            Pattern::MatchOr(or_pattern) => {
+                let mut alternatives = Vec::with_capacity(or_pattern.patterns.len());
+                for alternative in &or_pattern.patterns {
+                    let mut test = Self::default();
+                    if !test.compile(alternative, subject) || !test.bindings.is_empty() {
+                        return false;
+                    }
+                    let Some(condition) = conjunction(test.conditions) else {
+                        // An irrefutable alternative makes the whole pattern irrefutable
+                        return true;
+                    };
+                    alternatives.push(condition);
+                }
+                self.conditions
+                    .push(expressions::bool_op(BoolOp::Or, alternatives));
+            }
+            Pattern::MatchSequence(sequence_pattern) => {
+                return self.compile_sequence(&sequence_pattern.patterns, subject);
+            }
+            Pattern::MatchMapping(mapping_pattern) => {
+                return self.compile_mapping(
+                    &mapping_pattern.keys,
+                    &mapping_pattern.patterns,
+                    mapping_pattern.rest.as_ref(),
+                    subject,
+                );
+            }
+            Pattern::MatchClass(class_pattern) => {
+                return self.compile_class(
+                    &class_pattern.cls,
+                    &class_pattern.arguments.patterns,
+                    &class_pattern.arguments.keywords,
+                    subject,
+                );
+            }
+            // Star patterns only occur inside sequence patterns
+            Pattern::MatchStar(_) => return false,
+        }
+        true
+    }
+
+    fn compile_sequence(&mut self, patterns: &[Pattern], subject: &Expr) -> bool {
+        let star = patterns
+            .iter()
+            .position(|pattern| matches!(pattern, Pattern::MatchStar(_)));
+        self.conditions
+            .push(isinstance(subject, abc_class("Sequence")));
+        // Strings are sequences, but never match sequence patterns
+        self.conditions.push(expressions::unary_op(
+            UnaryOp::Not,
+            isinstance(
+                subject,
+                expressions::tuple(
+                    ["str", "bytes", "bytearray"]
+                        .into_iter()
+                        .map(|name| expressions::name(name, ExprContext::Load))
+                        .collect(),
+                ),
+            ),
+        ));
+        let fixed = patterns.len() - usize::from(star.is_some());
+        self.conditions.push(expressions::compare(
+            builtin_call("len", vec![subject.clone()]),
+            if star.is_some() {
+                CmpOp::GtE
+            } else {
+                CmpOp::Eq
+            },
+            expressions::int_literal(fixed as u32),
+        ));
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            let from_end = patterns.len() - index;
+            match (star, pattern) {
+                (Some(star_index), Pattern::MatchStar(star_pattern)) if index == star_index => {
+                    let Some(name) = &star_pattern.name else {
+                        continue;
+                    };
+                    let upper = (from_end > 1).then(|| negative_index(from_end - 1));
+                    let rest = expressions::subscript(
+                        subject.clone(),
+                        expressions::slice(Some(expressions::int_literal(index as u32)), upper),
+                        ExprContext::Load,
+                    );
+                    self.bindings.push(statements::simple_assign(
+                        name.as_str(),
+                        builtin_call("list", vec![rest]),
+                    ));
+                }
+                (Some(star_index), _) if index > star_index => {
+                    let element = expressions::subscript(
+                        subject.clone(),
+                        negative_index(from_end),
+                        ExprContext::Load,
+                    );
+                    if !self.compile(pattern, &element) {
+                        return false;
+                    }
+                }
+                _ => {
+                    let element = expressions::subscript(
+                        subject.clone(),
+                        expressions::int_literal(index as u32),
+                        ExprContext::Load,
+                    );
+                    if !self.compile(pattern, &element) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn compile_mapping(
+        &mut self,
+        keys: &[Expr],
+        patterns: &[Pattern],
+        rest: Option<&ruff_python_ast::Identifier>,
+        subject: &Expr,
+    ) -> bool {
+        self.conditions
+            .push(isinstance(subject, abc_class("Mapping")));
+        for key in keys {
+            self.conditions
+                .push(expressions::in_op(key.clone(), subject.clone()));
+        }
+        for (key, pattern) in keys.iter().zip(patterns) {
+            let value = expressions::subscript(subject.clone(), key.clone(), ExprContext::Load);
+            if !self.compile(pattern, &value) {
+                return false;
+            }
+        }
+        if let Some(rest) = rest {
+            self.bindings.push(statements::simple_assign(
+                rest.as_str(),
+                builtin_call("dict", vec![subject.clone()]),
+            ));
+            if !keys.is_empty() {
+                self.bindings.push(Stmt::Delete(StmtDelete {
+                    node_index: AtomicNodeIndex::NONE,
+                    targets: keys
+                        .iter()
+                        .map(|key| {
+                            expressions::subscript(
+                                expressions::name(rest.as_str(), ExprContext::Load),
+                                key.clone(),
+                                ExprContext::Del,
+                            )
+                        })
+                        .collect(),
+                    range: TextRange::default(),
+                }));
+            }
+        }
+        true
+    }
+
+    fn compile_class(
+        &mut self,
+        cls: &Expr,
+        patterns: &[Pattern],
+        keywords: &[ruff_python_ast::PatternKeyword],
+        subject: &Expr,
+    ) -> bool {
+        self.conditions.push(isinstance(subject, cls.clone()));
+        let self_matching = patterns.len() == 1
+            && matches!(cls, Expr::Name(name) if SELF_MATCHING_CLASSES.contains(&name.id.as_str()));
+        if self_matching {
+            return self.compile(&patterns[0], subject) && self.compile_keywords(keywords, subject);
+        }
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            // Positional sub-patterns match the attributes named by `__match_args__`
+            let attr_name = expressions::subscript(
+                expressions::attribute(cls.clone(), "__match_args__", ExprContext::Load),
+                expressions::int_literal(index as u32),
+                ExprContext::Load,
+            );
+            self.conditions.push(builtin_call(
+                "hasattr",
+                vec![subject.clone(), attr_name.clone()],
+            ));
+            let value = builtin_call("getattr", vec![subject.clone(), attr_name]);
+            if !self.compile(pattern, &value) {
+                return false;
+            }
+        }
+        self.compile_keywords(keywords, subject)
+    }
+
+    fn compile_keywords(
+        &mut self,
+        keywords: &[ruff_python_ast::PatternKeyword],
+        subject: &Expr,
+    ) -> bool {
+        for keyword in keywords {
+            self.conditions.push(builtin_call(
+                "hasattr",
+                vec![
+                    subject.clone(),
+                    expressions::string_literal(keyword.attr.as_str()),
+                ],
+            ));
+            let value =
+                expressions::attribute(subject.clone(), keyword.attr.as_str(), ExprContext::Load);
+            if !self.compile(&keyword.pattern, &value) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn builtin_call(name: &str, args: Vec<Expr>) -> Expr {
+    expressions::call(expressions::name(name, ExprContext::Load), args, vec![])
+}
+
+fn isinstance(subject: &Expr, class: Expr) -> Expr {
+    builtin_call("isinstance", vec![subject.clone(), class])
+}
+
+/// `collections.abc` class, reached through the stdlib proxy
+fn abc_class(name: &str) -> Expr {
+    expressions::dotted_name(
+        &[CRIBO_PREFIX, "collections", "abc", name],
+        ExprContext::Load,
+    )
+}
+
+fn negative_index(from_end: usize) -> Expr {
+    expressions::unary_op(UnaryOp::USub, expressions::int_literal(from_end as u32))
+}
+
+/// Hoist assignment expressions out of an `if`, splitting an `elif` whose test needs them into
+/// an `else` holding the assignments and a nested `if`
+fn hoist_from_if(mut if_stmt: StmtIf) -> Vec<Stmt> {
+    let mut lowered = Vec::new();
+    hoist_leading(&mut if_stmt.test, &mut lowered);
+
+    for index in 0..if_stmt.elif_else_clauses.len() {
+        let mut hoisted = Vec::new();
+        if let Some(test) = &mut if_stmt.elif_else_clauses[index].test {
+            hoist_leading(test, &mut hoisted);
+        }
+        if hoisted.is_empty() {
+            continue;
+        }
+        let mut rest = if_stmt.elif_else_clauses.split_off(index);
+        let clause = rest.remove(0);
+        let nested = StmtIf {
+            node_index: AtomicNodeIndex::NONE,
+            test: Box::new(
+                clause
+                    .test
+                    .unwrap_or_else(|| expressions::bool_literal(true)),
+            ),
+            body: clause.body,
+            elif_else_clauses: rest,
+            range: TextRange::default(),
+        };
+        hoisted.extend(hoist_from_if(nested));
+        if_stmt.elif_else_clauses.push(ElifElseClause {
+            node_index: AtomicNodeIndex::NONE,
+            test: None,
+            body: hoisted,
+            range: TextRange::default(),
+        });
+        break;
+    }
+
+    lowered.push(Stmt::If(if_stmt));
+    lowered
+}
+
+/// Hoist assignment expressions out of a `while` test by re-evaluating them at the top of an
+/// endless loop that breaks once the test fails
+fn hoist_from_while(mut while_stmt: StmtWhile) -> Vec<Stmt> {
+    // The `else` clause moves inside the loop, where its `break`/`continue` would change meaning
+    if has_loop_control(&while_stmt.orelse) {
+        return vec![Stmt::While(while_stmt)];
+    }
+    let mut hoisted = Vec::new();
+    hoist_leading(&mut while_stmt.test, &mut hoisted);
+    if hoisted.is_empty() {
+        return vec![Stmt::While(while_stmt)];
+    }
+
+    let mut exit = std::mem::take(&mut while_stmt.orelse);
+    exit.push(Stmt::Break(StmtBreak {
+        node_index: AtomicNodeIndex::NONE,
+        range: TextRange::default(),
+    }));
+    let test = std::mem::replace(&mut *while_stmt.test, expressions::bool_literal(true));
+    hoisted.push(statements::if_stmt(
+        expressions::unary_op(UnaryOp::Not, test),
+        exit,
+        vec![],
+    ));
+    hoisted.append(&mut while_stmt.body);
+    while_stmt.body = hoisted;
+    vec![Stmt::While(while_stmt)]
+}
+
+/// Whether `body` contains a `break` or `continue` of its enclosing loop
+fn has_loop_control(body: &[Stmt]) -> bool {
+    body.iter().any(|stmt| match stmt {
+        Stmt::Break(_) | Stmt::Continue(_) => true,
+        Stmt::If(if_stmt) => {
+            has_loop_control(&if_stmt.body)
+                || if_stmt
+                    .elif_else_clauses
+                    .iter()
+                    .any(|clause| has_loop_control(&clause.body))
+        }
+        Stmt::With(with_stmt) => has_loop_control(&with_stmt.body),
+        Stmt::Try(try_stmt) => {
+            has_loop_control(&try_stmt.body)
+                || try_stmt.handlers.iter().any(|handler| {
+                    let ruff_python_ast::ExceptHandler::ExceptHandler(handler) = handler;
+                    has_loop_control(&handler.body)
+                })
+                || has_loop_control(&try_stmt.orelse)
+                || has_loop_control(&try_stmt.finalbody)
+        }
+        Stmt::Match(match_stmt) => match_stmt
+            .cases
+            .iter()
+            .any(|case| has_loop_control(&case.body)),
+        // Loops, functions and classes start a new loop context
+        _ => false,
+    })
+}
+
+/// The expression a simple statement evaluates first
+fn leading_expression(stmt: &mut Stmt) -> Option<&mut Expr> {
+    match stmt {
+        Stmt::Expr(expr_stmt) => Some(&mut *expr_stmt.value),
+        Stmt::Assign(assign) => Some(&mut *assign.value),
+        Stmt::AnnAssign(ann_assign) => ann_assign.value.as_deref_mut(),
+        // Other augmented targets are evaluated before the value
+        Stmt::AugAssign(aug_assign) if aug_assign.target.is_name_expr() => {
+            Some(&mut *aug_assign.value)
+        }
+        Stmt::Return(ret) => ret.value.as_deref_mut(),
+        Stmt::Raise(raise) => raise.exc.as_deref_mut(),
+        Stmt::For(for_stmt) => Some(&mut *for_stmt.iter),
+        Stmt::With(with_stmt) => with_stmt
+            .items
+            .first_mut()
+            .map(|item| &mut item.context_expr),
+        _ => None,
+    }
+}
+
+/// Replace the assignment expressions `expr` evaluates before anything else with their
+/// targets, pushing the equivalent assignments to `hoisted`
+fn hoist_leading(expr: &mut Expr, hoisted: &mut Vec<Stmt>) {
+    let leading = match expr {
+        Expr::Named(named) => {
+            hoist_leading(&mut named.value, hoisted);
+            let Expr::Name(target) = &*named.target else {
+                return;
+            };
+            let target = target.id.to_string();
+            let value = std::mem::replace(&mut *named.value, expressions::none_literal());
+            hoisted.push(statements::simple_assign(&target, value));
+            *expr = expressions::name(&target, ExprContext::Load);
+            return;
+        }
+        Expr::Compare(compare) => &mut *compare.left,
+        Expr::BinOp(bin_op) => &mut *bin_op.left,
+        Expr::UnaryOp(unary_op) => &mut *unary_op.operand,
+        Expr::Attribute(attribute) => &mut *attribute.value,
+        Expr::Subscript(subscript) => &mut *subscript.value,
+        Expr::Await(await_expr) => &mut *await_expr.value,
+        Expr::BoolOp(bool_op) => match bool_op.values.first_mut() {
+            Some(first) => first,
+            None => return,
+        },
+        Expr::Tuple(tuple) => match tuple.elts.first_mut() {
+            Some(first) => first,
+            None => return,
+        },
+        Expr::List(list) => match list.elts.first_mut() {
+            Some(first) => first,
+            None => return,
+        },
+        // Looking up a plain function name has no side effects, so its first argument leads
+        Expr::Call(call) if call.func.is_name_expr() => match call.arguments.args.first_mut() {
+            Some(first) if !first.is_starred_expr() => first,
+            _ => return,
+        },
+        Expr::Call(call) => &mut *call.func,
+        _ => return,
+    };
+    hoist_leading(leading, hoisted);
+}
+
+/// Rewrites `f"{x=}"` fields into `f"x={x!r}"`
+struct DebugFieldLowerer<'a> {
+    stylist: &'a Stylist<'a>,
+}
+
+impl DebugFieldLowerer<'_> {
+    fn lower_elements(&self, elements: &mut InterpolatedStringElements) {
+        let has_debug_field = elements.iter().any(|element| {
+            matches!(element, InterpolatedStringElement::Interpolation(interpolation)
+                if interpolation.debug_text.is_some())
+        });
+        if !has_debug_field {
+            return;
+        }
+
+        let mut lowered = Vec::with_capacity(elements.len() + 1);
+        for element in elements.iter() {
+            let InterpolatedStringElement::Interpolation(interpolation) = element else {
+                lowered.push(element.clone());
+                continue;
+            };
+            let mut interpolation = interpolation.clone();
+            if let Some(debug_text) = interpolation.debug_text.take() {
+                let expression = Generator::from(self.stylist).expr(&interpolation.expression);
+                lowered.push(InterpolatedStringElement::Literal(
+                    InterpolatedStringLiteralElement {
+                        node_index: AtomicNodeIndex::NONE,
+                        value: format!("{}{expression}{}", debug_text.leading, debug_text.trailing)
+                            .into_boxed_str(),
+                        range: TextRange::default(),
+                    },
+                ));
+                // Without a conversion or format spec the value is shown with `repr`
+                if interpolation.conversion == ConversionFlag::None
+                    && interpolation.format_spec.is_none()
+                {
+                    interpolation.conversion = ConversionFlag::Repr;
+                }
+            }
+            lowered.push(InterpolatedStringElement::Interpolation(interpolation));
+        }
+        *elements = InterpolatedStringElements::from(lowered);
+    }
+}
+
+impl Transformer for DebugFieldLowerer<'_> {
+    fn visit_expr(&self, expr: &mut Expr) {
+        if let Expr::FString(fstring) = expr {
+            for part in fstring.value.iter_mut() {
+                if let FStringPart::FString(part) = part {
+                    self.lower_elements(&mut part.elements);
+                }
+            }
+        }
+        transformer::walk_expr(self, expr);
+    }
+}
+
+/// Collects syntax that is still unsupported by the target after lowering
+struct RemainingSyntax<'a> {
+    target_minor: u8,
+    stylist: &'a Stylist<'a>,
+    found: Vec<String>,
+}
+
+impl RemainingSyntax<'_> {
+    const fn has_positional_only(&self, parameters: &Parameters) -> bool {
+        self.target_minor < POSITIONAL_ONLY_MINOR && !parameters.posonlyargs.is_empty()
+    }
+}
+
+impl<'a> Visitor<'a> for RemainingSyntax<'_> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Match(match_stmt) if self.target_minor < MATCH_MINOR => {
+                self.found.push(format!(
+                    "`match {}:` (an alternative of a case pattern captures names)",
+                    Generator::from(self.stylist).expr(&match_stmt.subject)
+                ));
+            }
+            Stmt::FunctionDef(func_def) => {
+                if self.has_positional_only(&func_def.parameters) {
+                    self.found.push(format!(
+                        "`def {}` (positional-only parameters)",
+                        func_def.name
+                    ));
+                }
+                if self.target_minor < TYPE_PARAMS_MINOR && func_def.type_params.is_some() {
+                    self.found
+                        .push(format!("`def {}[...]` (type parameters)", func_def.name));
+                }
+            }
+            Stmt::ClassDef(class_def)
+                if self.target_minor < TYPE_PARAMS_MINOR && class_def.type_params.is_some() =>
+            {
+                self.found
+                    .push(format!("`class {}[...]` (type parameters)", class_def.name));
+            }
+            Stmt::TypeAlias(_) if self.target_minor < TYPE_PARAMS_MINOR => {
+                self.found.push(format!(
+                    "`{}` (type alias statement)",
+                    Generator::from(self.stylist).stmt(stmt)
+                ));
+            }
+            Stmt::Try(try_stmt) if self.target_minor < EXCEPT_STAR_MINOR && try_stmt.is_star => {
+                self.found.push("`except*` (exception groups)".to_owned());
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if self.target_minor < NAMED_EXPR_MINOR && expr.is_named_expr() {
+            self.found.push(format!(
+                "`{}` (assignment expressions can only be lowered when evaluated first in their \
+                 statement)",
+                Generator::from(self.stylist).expr(expr)
+            ));
+        }
+        if let Expr::Lambda(lambda) = expr
+            && lambda
+                .parameters
+                .as_deref()
+                .is_some_and(|parameters| self.has_positional_only(parameters))
+        {
+            self.found.push(format!(
+                "`{}` (positional-only parameters)",
+                Generator::from(self.stylist).expr(expr)
+            ));
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    fn lower(source: &str, target_minor: u8) -> Result<String> {
+        let parsed = parse_module(source).expect("Test code should parse successfully");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.into_syntax();
+        SyntaxLowerer::new(target_minor, &stylist).lower_module(&mut module)?;
+        Ok(module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    #[test]
+    fn test_named_exprs_hoisted_before_their_statement() {
+        let source = "\
+if (m := pattern.match(line)) is not None:
+    print(m)
+elif (n := len(line)) > 10:
+    print(n)
+while (chunk := stream.read()):
+    handle(chunk)
+";
+        let lowered = lower(source, 7).expect("lowering should succeed");
+        assert_eq!(
+            lowered,
+            "\
+m = pattern.match(line)
+if m is not None:
+    print(m)
+else:
+    n = len(line)
+    if n > 10:
+        print(n)
+while True:
+    chunk = stream.read()
+    if not chunk:
+        break
+    handle(chunk)"
+        );
+        // Assignment expressions are valid syntax from 3.8 on
+        assert_eq!(
+            lower(source, 8).expect("nothing to lower"),
+            lower(source, 12).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_named_expr_not_evaluated_first_is_reported() {
+        let error = lower("if ready and (value := compute()):\n    use(value)\n", 7)
+            .expect_err("hoisting would change evaluation order");
+        assert!(error.to_string().contains("value := compute()"));
+    }
+
+    #[test]
+    fn test_debug_fields_become_literal_and_repr() {
+        let lowered = lower("print(f'{x=} {y = :>4}')\n", 7).expect("lowering should succeed");
+        assert_eq!(lowered, "print(f'x={x!r} y = {y:>4}')");
+    }
+
+    #[test]
+    fn test_match_lowered_to_if_chain() {
+        let source = "\
+match command:
+    case ['go', direction]:
+        go(direction)
+    case {'action': action, **rest}:
+        run(action, rest)
+    case Point(x=0) | None:
+        origin()
+    case _:
+        unknown()
+";
+        let lowered = lower(source, 9).expect("lowering should succeed");
+        let lines: Vec<&str> = lowered.lines().collect();
+        assert_eq!(lines[0], "_cribo_match_0 = command");
+        assert!(
+            lines[1].starts_with("if isinstance(_cribo_match_0, _cribo.collections.abc.Sequence)")
+        );
+        assert!(lines[1].contains("len(_cribo_match_0) == 2 and _cribo_match_0[0] == 'go'"));
+        assert_eq!(lines[2].trim(), "direction = _cribo_match_0[1]");
+        assert!(lines[4].contains("'action' in _cribo_match_0"));
+        assert_eq!(lines[5].trim(), "action = _cribo_match_0['action']");
+        assert_eq!(lines[6].trim(), "rest = dict(_cribo_match_0)");
+        assert_eq!(lines[7].trim(), "del rest['action']");
+        assert!(lines[9].contains("_cribo_match_0.x == 0"));
+        assert!(lines[9].contains("_cribo_match_0 is None"));
+        assert_eq!(lines[11], "else:");
+        assert!(
+            lower(source, 10)
+                .expect("nothing to lower")
+                .starts_with("match command:")
+        );
+    }
+
+    #[test]
+    fn test_guarded_cases_bind_before_guard() {
+        let source = "\
+match value:
+    case int(n) if n > 0:
+        positive(n)
+    case n:
+        other(n)
+";
+        let lowered = lower(source, 9).expect("lowering should succeed");
+        assert_eq!(
+            lowered,
+            "\
+_cribo_match_0 = value
+_cribo_match_0_found = False
+if isinstance(_cribo_match_0, int):
+    n = _cribo_match_0
+    if n > 0:
+        _cribo_match_0_found = True
+        positive(n)
+if not _cribo_match_0_found:
+    n = _cribo_match_0
+    _cribo_match_0_found = True
+    other(n)"
+        );
+    }
+
+    #[test]
+    fn test_unlowerable_syntax_is_reported() {
+        let source = "\
+def clamp(value, /, low, high):
+    return min(max(value, low), high)
+
+class Box[T]:
+    pass
+
+type Alias = int
+
+try:
+    pass
+except* ValueError:
+    pass
+";
+        let error = lower(source, 7)
+            .expect_err("lowering to 3.7 should fail")
+            .to_string();
+        assert!(error.starts_with("Cannot lower the bundle to Python 3.7:"));
+        assert!(error.contains("`def clamp` (positional-only parameters)"));
+        assert!(error.contains("`class Box[...]` (type parameters)"));
+        assert!(error.contains("`type Alias = int` (type alias statement)"));
+        assert!(error.contains("`except*` (exception groups)"));
+
+        let error = lower(source, 11)
+            .expect_err("lowering to 3.11 should fail")
+            .to_string();
+        assert!(!error.contains("positional-only"));
+        assert!(!error.contains("except*"));
+        assert!(error.contains("`class Box[...]` (type parameters)"));
+
+        assert!(lower(source, 12).is_ok());
+    }
+}
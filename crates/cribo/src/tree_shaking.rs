@@ -228,6 +228,39 @@ impl<'a> TreeShaker<'a> {
         None
     }
 
+    /// Resolve a name read in a module to the module and symbol defining it
+    ///
+    /// Import aliases are followed through re-exports until a module defines the symbol.
+    pub(crate) fn resolve_symbol_reference(
+        &self,
+        module_id: ModuleId,
+        name: &str,
+    ) -> Option<(ModuleId, String)> {
+        let mut current = (module_id, name.to_owned());
+        let mut visited = FxIndexSet::default();
+        while visited.insert(current.clone()) {
+            if self.is_defined_in_module(current.0, &current.1) {
+                return Some(current);
+            }
+            current = self.resolve_import_alias(current.0, &current.1)?;
+        }
+        None
+    }
+
+    /// Resolve `base.attr` read in a module, where `base` refers to a bundled module
+    pub(crate) fn resolve_module_attribute_reference(
+        &self,
+        module_id: ModuleId,
+        base: &str,
+        attr: &str,
+    ) -> Option<(ModuleId, String)> {
+        let source_module_id = self
+            .resolve_module_import_alias(module_id, base)
+            .or_else(|| self.resolve_from_module_import(module_id, base))
+            .or_else(|| self.graph.module_names.get(base).copied())?;
+        self.resolve_symbol_reference(source_module_id, attr)
+    }
+
     // Note: previous custom resolve_relative_module helper removed in favor of centralized resolver
 
     /// Seed side effects for a module that has been reached via imports
@@ -445,6 +478,15 @@ impl<'a> TreeShaker<'a> {
                 self.mark_scoped_imports_as_used(module_id, symbol, worklist);
             }
 
+            // The `KW_ONLY` sentinel of a live dataclass is a field marker, not an unused
+            // annotation: keep its binding and whatever it resolves to
+            if matches!(item.item_type, ItemType::ClassDef { .. })
+                && let Some(sentinel_reads) = module_dep.kw_only_sentinel(symbol)
+            {
+                debug!("Keeping KW_ONLY sentinel of dataclass {module_display}::{symbol}");
+                self.add_vars_to_worklist(sentinel_reads, module_id, worklist, "KW_ONLY sentinel");
+            }
+
             // Add symbol-specific dependencies if tracked
             if let Some(deps) = item.symbol_dependencies.get(symbol) {
                 for dep in deps {
@@ -1154,6 +1196,268 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_abstract_class_kept_only_while_subclassed_by_live_class() {
+        let mut graph = DependencyGraph::new();
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+
+        let entry_id = graph.add_module(
+            ModuleId::ENTRY,
+            "__main__".to_owned(),
+            &std::path::PathBuf::from("main.py"),
+        );
+        let interfaces_id = graph.add_module(
+            ModuleId::new(1),
+            "interfaces".to_owned(),
+            &std::path::PathBuf::from("interfaces.py"),
+        );
+
+        let source = r"
+import abc as a
+from abc import ABC, abstractmethod
+
+class Plugin(ABC):
+    @abstractmethod
+    def run(self): ...
+
+class Exporter(metaclass=a.ABCMeta):
+    @a.abstractmethod
+    def export(self): ...
+
+class Echo(Plugin):
+    def run(self):
+        return 1
+";
+        let ast = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        let interfaces = graph
+            .modules
+            .get_mut(&interfaces_id)
+            .expect("module should exist");
+        crate::graph_builder::GraphBuilder::new(interfaces, 10)
+            .build_from_ast(&ast)
+            .expect("graph should build");
+
+        let entry = graph
+            .modules
+            .get_mut(&entry_id)
+            .expect("entry module should exist");
+        let mut main = function_item("main");
+        main.read_vars = std::iter::once("Echo".to_owned()).collect();
+        entry.add_item(main);
+
+        let mut shaker = TreeShaker::from_graph(&graph, &resolver);
+        shaker.analyze("__main__");
+
+        assert!(shaker.is_symbol_used("interfaces", "Echo"));
+        assert!(shaker.is_symbol_used("interfaces", "Plugin"));
+        assert!(!shaker.is_symbol_used("interfaces", "Exporter"));
+    }
+
+    #[test]
+    fn test_getattr_with_literal_name_keeps_attribute() {
+        let mut graph = DependencyGraph::new();
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+
+        let entry_id = graph.add_module(
+            ModuleId::ENTRY,
+            "__main__".to_owned(),
+            &std::path::PathBuf::from("main.py"),
+        );
+        let plugins_id = graph.add_module(
+            ModuleId::new(1),
+            "plugins".to_owned(),
+            &std::path::PathBuf::from("plugins.py"),
+        );
+
+        let plugins = graph
+            .modules
+            .get_mut(&plugins_id)
+            .expect("module should exist");
+        plugins.add_item(function_item("optional_feature"));
+        plugins.add_item(function_item("other_feature"));
+
+        let source = r#"
+import plugins
+
+handler = getattr(plugins, "optional_feature", None)
+"#;
+        let ast = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        let entry = graph
+            .modules
+            .get_mut(&entry_id)
+            .expect("entry module should exist");
+        crate::graph_builder::GraphBuilder::new(entry, 10)
+            .build_from_ast(&ast)
+            .expect("graph should build");
+
+        let mut shaker = TreeShaker::from_graph(&graph, &resolver);
+        shaker.analyze("__main__");
+
+        assert!(shaker.is_symbol_used("plugins", "optional_feature"));
+        assert!(!shaker.is_symbol_used("plugins", "other_feature"));
+    }
+
+    #[test]
+    fn test_kw_only_sentinel_kept_for_live_dataclass() {
+        let mut graph = DependencyGraph::new();
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+
+        let entry_id = graph.add_module(
+            ModuleId::ENTRY,
+            "__main__".to_owned(),
+            &std::path::PathBuf::from("main.py"),
+        );
+        let compat_id = graph.add_module(
+            ModuleId::new(1),
+            "compat".to_owned(),
+            &std::path::PathBuf::from("compat.py"),
+        );
+        let models_id = graph.add_module(
+            ModuleId::new(2),
+            "models".to_owned(),
+            &std::path::PathBuf::from("models.py"),
+        );
+
+        let compat = graph
+            .modules
+            .get_mut(&compat_id)
+            .expect("module should exist");
+        compat.add_item(function_item("KW_ONLY"));
+        compat.add_item(function_item("unused"));
+
+        let models = graph
+            .modules
+            .get_mut(&models_id)
+            .expect("module should exist");
+        let mut config = function_item("Config");
+        config.item_type = ItemType::ClassDef {
+            name: "Config".to_owned(),
+        };
+        models.add_item(config);
+        models.mark_kw_only_sentinel(
+            "Config".to_owned(),
+            std::iter::once("KW_ONLY".to_owned()).collect(),
+        );
+
+        let entry = graph
+            .modules
+            .get_mut(&entry_id)
+            .expect("entry module should exist");
+        let mut main = function_item("main");
+        main.read_vars = std::iter::once("Config".to_owned()).collect();
+        entry.add_item(main);
+
+        let mut shaker = TreeShaker::from_graph(&graph, &resolver);
+        shaker.analyze("__main__");
+
+        assert!(shaker.is_symbol_used("models", "Config"));
+        assert!(shaker.is_symbol_used("compat", "KW_ONLY"));
+        assert!(!shaker.is_symbol_used("compat", "unused"));
+    }
+
+    #[test]
+    fn test_namedtuple_kept_only_while_instantiated() {
+        let mut graph = DependencyGraph::new();
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+
+        let entry_id = graph.add_module(
+            ModuleId::ENTRY,
+            "__main__".to_owned(),
+            &std::path::PathBuf::from("main.py"),
+        );
+        let shapes_id = graph.add_module(
+            ModuleId::new(1),
+            "shapes".to_owned(),
+            &std::path::PathBuf::from("shapes.py"),
+        );
+
+        let source = r#"
+import typing as t
+from collections import namedtuple
+
+Point = namedtuple("Point", ["x", "y"])
+Segment = t.NamedTuple("Segment", [("start", Point), ("end", Point)])
+"#;
+        let ast = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        let shapes = graph
+            .modules
+            .get_mut(&shapes_id)
+            .expect("module should exist");
+        crate::graph_builder::GraphBuilder::new(shapes, 10)
+            .build_from_ast(&ast)
+            .expect("graph should build");
+
+        let entry = graph
+            .modules
+            .get_mut(&entry_id)
+            .expect("entry module should exist");
+        let mut main = function_item("main");
+        main.read_vars = std::iter::once("Point".to_owned()).collect();
+        entry.add_item(main);
+
+        let mut shaker = TreeShaker::from_graph(&graph, &resolver);
+        shaker.analyze("__main__");
+
+        assert!(shaker.is_symbol_used("shapes", "Point"));
+        assert!(!shaker.is_symbol_used("shapes", "Segment"));
+    }
+
+    #[test]
+    fn test_module_qualified_decorator_kept_with_decorated_function() {
+        let mut graph = DependencyGraph::new();
+        let resolver = ModuleResolver::new(crate::config::Config::default());
+
+        let entry_id = graph.add_module(
+            ModuleId::ENTRY,
+            "__main__".to_owned(),
+            &std::path::PathBuf::from("main.py"),
+        );
+        let decorators_id = graph.add_module(
+            ModuleId::new(1),
+            "decorators".to_owned(),
+            &std::path::PathBuf::from("decorators.py"),
+        );
+
+        let decorators = graph
+            .modules
+            .get_mut(&decorators_id)
+            .expect("module should exist");
+        decorators.add_item(function_item("traced"));
+        decorators.add_item(function_item("unused"));
+
+        let source = r"
+import decorators
+
+@decorators.traced
+def main():
+    return 1
+
+main()
+";
+        let ast = ruff_python_parser::parse_module(source)
+            .expect("test module should parse")
+            .into_syntax();
+        let entry = graph
+            .modules
+            .get_mut(&entry_id)
+            .expect("entry module should exist");
+        crate::graph_builder::GraphBuilder::new(entry, 10)
+            .build_from_ast(&ast)
+            .expect("graph should build");
+
+        let mut shaker = TreeShaker::from_graph(&graph, &resolver);
+        shaker.analyze("__main__");
+
+        assert!(shaker.is_symbol_used("decorators", "traced"));
+        assert!(!shaker.is_symbol_used("decorators", "unused"));
+    }
+
     #[test]
     fn test_basic_tree_shaking() {
         let mut graph = DependencyGraph::new();
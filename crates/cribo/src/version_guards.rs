@@ -0,0 +1,315 @@
+//! Resolving `sys.version_info` guards for an explicit target version
+//!
+//! Code supporting several Python versions picks an implementation when it is imported:
+//!
+//! ```python
+//! if sys.version_info >= (3, 11):
+//!     from tomllib import loads
+//! else:
+//!     from tomli import loads
+//! ```
+//!
+//! When the target version is given explicitly (`--target-version`), such guards are evaluated
+//! right after parsing and replaced with the branch that runs on that version, so the imports
+//! of the other branches are neither discovered nor bundled. Guards whose outcome depends on
+//! more than the major and minor version (e.g. `>= (3, 11, 2)`) are kept as they are.
+
+use std::cmp::Ordering;
+
+use ruff_python_ast::{CmpOp, ElifElseClause, Expr, ModModule, Number, Stmt, StmtIf};
+
+use crate::ast_builder::statements;
+
+/// Replace the `sys.version_info` guards in `module` with the branch taken on Python
+/// 3.`python_version`
+///
+/// Returns the number of conditions that were decided.
+pub(crate) fn resolve_version_guards(module: &mut ModModule, python_version: u8) -> usize {
+    let mut resolved = 0;
+    resolve_in_body(&mut module.body, python_version, &mut resolved);
+    resolved
+}
+
+fn resolve_in_body(body: &mut Vec<Stmt>, python_version: u8, resolved: &mut usize) {
+    let had_statements = !body.is_empty();
+    for stmt in std::mem::take(body) {
+        match stmt {
+            Stmt::If(if_stmt) => match take_branch(if_stmt, python_version, resolved) {
+                Ok(mut branch) => {
+                    resolve_in_body(&mut branch, python_version, resolved);
+                    body.extend(branch);
+                }
+                Err(mut if_stmt) => {
+                    resolve_in_body(&mut if_stmt.body, python_version, resolved);
+                    for clause in &mut if_stmt.elif_else_clauses {
+                        resolve_in_body(&mut clause.body, python_version, resolved);
+                    }
+                    body.push(Stmt::If(if_stmt));
+                }
+            },
+            Stmt::FunctionDef(mut func_def) => {
+                resolve_in_body(&mut func_def.body, python_version, resolved);
+                body.push(Stmt::FunctionDef(func_def));
+            }
+            Stmt::ClassDef(mut class_def) => {
+                resolve_in_body(&mut class_def.body, python_version, resolved);
+                body.push(Stmt::ClassDef(class_def));
+            }
+            Stmt::Try(mut try_stmt) => {
+                resolve_in_body(&mut try_stmt.body, python_version, resolved);
+                for handler in &mut try_stmt.handlers {
+                    let ruff_python_ast::ExceptHandler::ExceptHandler(handler) = handler;
+                    resolve_in_body(&mut handler.body, python_version, resolved);
+                }
+                resolve_in_body(&mut try_stmt.orelse, python_version, resolved);
+                resolve_in_body(&mut try_stmt.finalbody, python_version, resolved);
+                body.push(Stmt::Try(try_stmt));
+            }
+            Stmt::With(mut with_stmt) => {
+                resolve_in_body(&mut with_stmt.body, python_version, resolved);
+                body.push(Stmt::With(with_stmt));
+            }
+            stmt => body.push(stmt),
+        }
+    }
+
+    // A block whose only statement was a guard without a taken branch must stay a block
+    if had_statements && body.is_empty() {
+        body.push(statements::pass());
+    }
+}
+
+/// The body of the branch `if_stmt` takes, or the statement without the branches that can
+/// never be taken when a condition cannot be decided
+fn take_branch(
+    mut if_stmt: StmtIf,
+    python_version: u8,
+    resolved: &mut usize,
+) -> Result<Vec<Stmt>, StmtIf> {
+    loop {
+        match evaluate(&if_stmt.test, python_version) {
+            None => return Err(if_stmt),
+            Some(true) => {
+                *resolved += 1;
+                return Ok(if_stmt.body);
+            }
+            Some(false) => {
+                *resolved += 1;
+                let mut clauses = if_stmt.elif_else_clauses.into_iter();
+                match clauses.next() {
+                    None => return Ok(Vec::new()),
+                    Some(ElifElseClause {
+                        test: None, body, ..
+                    }) => return Ok(body),
+                    Some(ElifElseClause {
+                        node_index,
+                        range,
+                        test: Some(test),
+                        body,
+                    }) => {
+                        if_stmt = StmtIf {
+                            node_index,
+                            range,
+                            test: Box::new(test),
+                            body,
+                            elif_else_clauses: clauses.collect(),
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Value of a `sys.version_info` comparison on Python 3.`python_version`, if it only depends
+/// on the major and minor version
+fn evaluate(test: &Expr, python_version: u8) -> Option<bool> {
+    let Expr::Compare(compare) = test else {
+        return None;
+    };
+    let ([op], [right]) = (&*compare.ops, &*compare.comparators) else {
+        return None;
+    };
+    // `sys.version_info` continues after the minor version, `sys.version_info[:2]` does not
+    let continues = match &*compare.left {
+        Expr::Subscript(subscript) if is_version_info(&subscript.value) => {
+            let Expr::Slice(slice) = &*subscript.slice else {
+                return None;
+            };
+            if slice.lower.is_some()
+                || slice.step.is_some()
+                || slice.upper.as_deref().and_then(int_value) != Some(2)
+            {
+                return None;
+            }
+            false
+        }
+        left if is_version_info(left) => true,
+        _ => return None,
+    };
+    let Expr::Tuple(tuple) = right else {
+        return None;
+    };
+    let other = tuple
+        .elts
+        .iter()
+        .map(int_value)
+        .collect::<Option<Vec<u64>>>()?;
+
+    let ordering = compare_version(&[3, u64::from(python_version)], continues, &other)?;
+    match op {
+        CmpOp::Lt => Some(ordering.is_lt()),
+        CmpOp::LtE => Some(ordering.is_le()),
+        CmpOp::Gt => Some(ordering.is_gt()),
+        CmpOp::GtE => Some(ordering.is_ge()),
+        CmpOp::Eq => Some(ordering.is_eq()),
+        CmpOp::NotEq => Some(ordering.is_ne()),
+        _ => None,
+    }
+}
+
+/// Compare a version tuple starting with `known` to `other` like Python compares tuples
+///
+/// `continues` tells whether the version has more (unknown) items after `known`.
+fn compare_version(known: &[u64], continues: bool, other: &[u64]) -> Option<Ordering> {
+    for (index, item) in other.iter().enumerate() {
+        let Some(version_item) = known.get(index) else {
+            return if continues {
+                None
+            } else {
+                Some(Ordering::Less)
+            };
+        };
+        match version_item.cmp(item) {
+            Ordering::Equal => {}
+            ordering => return Some(ordering),
+        }
+    }
+    Some(if continues || known.len() > other.len() {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    })
+}
+
+fn is_version_info(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Attribute(attr)
+            if attr.attr.as_str() == "version_info"
+                && matches!(&*attr.value, Expr::Name(name) if name.id.as_str() == "sys")
+    )
+}
+
+fn int_value(expr: &Expr) -> Option<u64> {
+    let Expr::NumberLiteral(literal) = expr else {
+        return None;
+    };
+    let Number::Int(value) = &literal.value else {
+        return None;
+    };
+    value.as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_codegen::{Generator, Stylist};
+    use ruff_python_parser::parse_module;
+
+    use super::*;
+
+    fn resolve(source: &str, python_version: u8) -> (usize, Vec<String>) {
+        let parsed = parse_module(source).expect("Test code should parse successfully");
+        let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let mut module = parsed.syntax().clone();
+        let resolved = resolve_version_guards(&mut module, python_version);
+        let code = module
+            .body
+            .iter()
+            .map(|stmt| Generator::from(&stylist).stmt(stmt))
+            .collect();
+        (resolved, code)
+    }
+
+    #[test]
+    fn test_resolve_keeps_branch_taken_on_target_version() {
+        let source = "\
+import sys
+if sys.version_info >= (3, 11):
+    from tomllib import loads
+else:
+    from tomli import loads
+";
+        assert_eq!(
+            resolve(source, 11),
+            (
+                1,
+                vec![
+                    "import sys".to_owned(),
+                    "from tomllib import loads".to_owned()
+                ]
+            )
+        );
+        assert_eq!(
+            resolve(source, 10),
+            (
+                1,
+                vec![
+                    "import sys".to_owned(),
+                    "from tomli import loads".to_owned()
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_resolve_walks_elif_chains_and_nested_blocks() {
+        let source = "\
+def load():
+    if sys.version_info[:2] == (3, 9):
+        return 'legacy'
+    elif sys.version_info < (3, 12):
+        return 'current'
+    else:
+        return 'future'
+if sys.version_info >= (3, 10, 2):
+    PATCHED = True
+if sys.version_info < (3, 8):
+    OLD = True
+";
+        let (resolved, code) = resolve(source, 10);
+        assert_eq!(resolved, 3);
+        assert_eq!(
+            code,
+            [
+                "def load():\n    return 'current'",
+                "if sys.version_info >= (3, 10, 2):\n    PATCHED = True",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_version_like_python_tuples() {
+        assert_eq!(
+            compare_version(&[3, 10], true, &[3, 10]),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_version(&[3, 10], false, &[3, 10]),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare_version(&[3, 10], false, &[3, 10, 1]),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare_version(&[3, 10], true, &[3, 10, 1]), None);
+        assert_eq!(
+            compare_version(&[3, 10], true, &[3, 9, 5]),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_version(&[3, 10], true, &[3]),
+            Some(Ordering::Greater)
+        );
+    }
+}
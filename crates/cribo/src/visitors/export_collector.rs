@@ -2,14 +2,15 @@
 //!
 //! This visitor identifies module exports including __all__ declarations,
 //! re-exports from imports, and implicit exports.
+//!
+//! `__all__` is tracked flow-sensitively through module-level control flow: when branches of an
+//! `if`/`try`/`match` assign different lists, the exports are the union of all branches, so
+//! bundling keeps every name that may be exported at runtime.
 
-use ruff_python_ast::{
-    Expr, ModModule, Stmt,
-    visitor::{Visitor, walk_stmt},
-};
+use ruff_python_ast::{ExceptHandler, Expr, ModModule, Stmt};
 
 use super::utils::extract_string_list_from_expr;
-use crate::analyzers::types::ExportInfo;
+use crate::{analyzers::types::ExportInfo, types::FxIndexSet};
 
 /// Visitor that collects export information from a module
 pub(crate) struct ExportCollector {
@@ -43,7 +44,7 @@ impl ExportCollector {
     /// Analyze a module and return export information
     pub(crate) fn analyze(module: &ModModule) -> ExportInfo {
         let mut collector = Self::new();
-        collector.visit_body(&module.body);
+        collector.collect_body(&module.body);
 
         // Set the final exported names
         if let Some(all_names) = collector.current_all {
@@ -62,10 +63,15 @@ impl ExportCollector {
         }
         result.names
     }
-}
 
-impl<'a> Visitor<'a> for ExportCollector {
-    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+    /// Process module-level statements in execution order
+    fn collect_body(&mut self, body: &[Stmt]) {
+        for stmt in body {
+            self.collect_stmt(stmt);
+        }
+    }
+
+    fn collect_stmt(&mut self, stmt: &Stmt) {
         match stmt {
             Stmt::Assign(assign) => {
                 // Check for __all__ assignment
@@ -84,11 +90,86 @@ impl<'a> Visitor<'a> for ExportCollector {
                     self.has_dynamic_all = true;
                 }
             }
-            // Import from statements and other statements - no processing needed
+            Stmt::If(if_stmt) => {
+                let mut branches = vec![if_stmt.body.as_slice()];
+                branches.extend(
+                    if_stmt
+                        .elif_else_clauses
+                        .iter()
+                        .map(|clause| clause.body.as_slice()),
+                );
+                let has_else = if_stmt
+                    .elif_else_clauses
+                    .last()
+                    .is_some_and(|clause| clause.test.is_none());
+                self.collect_alternatives(&branches, !has_else);
+            }
+            Stmt::Match(match_stmt) => {
+                let branches: Vec<&[Stmt]> = match_stmt
+                    .cases
+                    .iter()
+                    .map(|case| case.body.as_slice())
+                    .collect();
+                self.collect_alternatives(&branches, true);
+            }
+            Stmt::Try(try_stmt) => {
+                // Either the body and `else` run to completion, or a handler runs
+                let before = self.current_all.clone();
+                self.collect_body(&try_stmt.body);
+                self.collect_body(&try_stmt.orelse);
+                let mut outcomes = vec![self.current_all.take()];
+                for handler in &try_stmt.handlers {
+                    let ExceptHandler::ExceptHandler(handler) = handler;
+                    self.current_all.clone_from(&before);
+                    self.collect_body(&handler.body);
+                    outcomes.push(self.current_all.take());
+                }
+                self.current_all = Self::union(outcomes);
+                self.collect_body(&try_stmt.finalbody);
+            }
+            Stmt::For(for_stmt) => {
+                self.collect_alternatives(
+                    &[for_stmt.body.as_slice(), for_stmt.orelse.as_slice()],
+                    true,
+                );
+            }
+            Stmt::While(while_stmt) => {
+                self.collect_alternatives(
+                    &[while_stmt.body.as_slice(), while_stmt.orelse.as_slice()],
+                    true,
+                );
+            }
+            Stmt::With(with_stmt) => self.collect_body(&with_stmt.body),
+            // Function and class bodies do not assign the module's __all__
             _ => {}
         }
+    }
+
+    /// Process mutually exclusive branches and merge their `__all__` values
+    ///
+    /// When `may_skip` is set, execution can also bypass every branch (an `if` without `else`).
+    fn collect_alternatives(&mut self, branches: &[&[Stmt]], may_skip: bool) {
+        let before = self.current_all.clone();
+        let mut outcomes = Vec::with_capacity(branches.len() + 1);
+        for branch in branches {
+            self.current_all.clone_from(&before);
+            self.collect_body(branch);
+            outcomes.push(self.current_all.take());
+        }
+        if may_skip {
+            outcomes.push(before);
+        }
+        self.current_all = Self::union(outcomes);
+    }
 
-        walk_stmt(self, stmt);
+    /// Union of the `__all__` values reachable along different paths, `None` if no path
+    /// assigns `__all__`
+    fn union(outcomes: Vec<Option<Vec<String>>>) -> Option<Vec<String>> {
+        let mut merged: Option<FxIndexSet<String>> = None;
+        for names in outcomes.into_iter().flatten() {
+            merged.get_or_insert_with(FxIndexSet::default).extend(names);
+        }
+        merged.map(|names| names.into_iter().collect())
     }
 }
 
@@ -155,4 +236,60 @@ __all__ = ("foo", "bar")
             Some(vec!["foo".to_owned(), "bar".to_owned()])
         );
     }
+
+    #[test]
+    fn test_conditional_all_takes_union_of_branches() {
+        let code = r#"
+import sys
+
+if sys.version_info >= (3, 11):
+    __all__ = ["run", "TaskGroup"]
+else:
+    __all__ = ["run", "gather"]
+
+def helper():
+    __all__ = ["local"]
+"#;
+        let parsed = parse_module(code).expect("Test code should parse successfully");
+        let module = parsed.into_syntax();
+        let export_info = ExportCollector::analyze(&module);
+
+        assert!(!export_info.is_dynamic);
+        assert_eq!(
+            export_info.exported_names,
+            Some(vec![
+                "run".to_owned(),
+                "TaskGroup".to_owned(),
+                "gather".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_all_in_try_and_partial_if_merges_with_fallthrough() {
+        let code = r#"
+__all__ = ["base"]
+
+try:
+    from _speedups import fast
+    __all__ = ["base", "fast"]
+except ImportError:
+    pass
+
+if FEATURE:
+    __all__ = ["feature"]
+"#;
+        let parsed = parse_module(code).expect("Test code should parse successfully");
+        let module = parsed.into_syntax();
+        let export_info = ExportCollector::analyze(&module);
+
+        assert_eq!(
+            export_info.exported_names,
+            Some(vec![
+                "feature".to_owned(),
+                "base".to_owned(),
+                "fast".to_owned()
+            ])
+        );
+    }
 }
@@ -3,8 +3,8 @@
 //! Also performs semantic analysis to determine import usage patterns.
 
 use ruff_python_ast::{
-    AnyNodeRef, Expr, ExprAttribute, ExprCall, ExprName, ExprStringLiteral, ExprUnaryOp, Stmt,
-    StmtImport, StmtImportFrom, UnaryOp,
+    AnyNodeRef, Expr, ExprAttribute, ExprCall, ExprName, ExprUnaryOp, Stmt, StmtImport,
+    StmtImportFrom, UnaryOp,
     visitor::source_order::{SourceOrderVisitor, TraversalSignal, walk_expr, walk_stmt},
 };
 use ruff_text_size::TextRange;
@@ -13,6 +13,7 @@ use crate::{
     resolver::ModuleId,
     symbol_conflict_resolver::SymbolConflictResolver,
     types::{FxIndexMap, FxIndexSet},
+    visitors::utils::import_module_literal_arguments,
 };
 
 /// Execution context for code - determines when code runs relative to module import
@@ -414,22 +415,12 @@ impl<'a> ImportDiscoveryVisitor<'a> {
     /// Extract literal module name from `importlib.import_module` call
     fn extract_literal_module_name(&self, call: &ExprCall) -> Option<String> {
         // Only handle static string literals
-        if let Some(arg) = call.arguments.args.first()
-            && let Expr::StringLiteral(ExprStringLiteral { value, .. }) = arg
-        {
-            return Some(value.to_str().to_owned());
-        }
-        None
+        import_module_literal_arguments(call).0.map(str::to_owned)
     }
 
     fn extract_package_context(&self, call: &ExprCall) -> Option<String> {
-        // Extract the second argument if it exists (package context for relative imports)
-        if call.arguments.args.len() >= 2
-            && let Expr::StringLiteral(ExprStringLiteral { value, .. }) = &call.arguments.args[1]
-        {
-            return Some(value.to_str().to_owned());
-        }
-        None
+        // The package context for relative imports, as second argument or `package=` keyword
+        import_module_literal_arguments(call).1.map(str::to_owned)
     }
 
     /// Record an import statement
@@ -1003,6 +994,40 @@ def load_module():
         ));
     }
 
+    #[test]
+    fn test_importlib_package_argument_discovery() {
+        let source = r#"
+import importlib
+
+positional = importlib.import_module(".sub", "mypkg")
+keyword = importlib.import_module(".sub", package="mypkg")
+named = importlib.import_module(name="..other", package="mypkg.sub")
+"#;
+        let parsed = parse_module(source).expect("Failed to parse test module");
+        let mut visitor = ImportDiscoveryVisitor::new();
+        for stmt in &parsed.syntax().body {
+            visitor.visit_stmt(stmt);
+        }
+        let imports: Vec<_> = visitor
+            .into_imports()
+            .into_iter()
+            .filter(|import| matches!(import.import_type, ImportType::ImportlibStatic))
+            .map(|import| (import.module_name, import.level, import.package_context))
+            .collect();
+
+        let relative = |name: &str, level: u32, package: &str| {
+            (Some(name.to_owned()), level, Some(package.to_owned()))
+        };
+        assert_eq!(
+            imports,
+            [
+                relative(".sub", 1, "mypkg"),
+                relative(".sub", 1, "mypkg"),
+                relative("..other", 2, "mypkg.sub"),
+            ]
+        );
+    }
+
     #[test]
     fn test_relative_imports() {
         let source = r"
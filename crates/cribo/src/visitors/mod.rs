@@ -17,6 +17,8 @@ pub(crate) use import_discovery::{
     DiscoveredImport, ImportDiscoveryVisitor, ImportLocation, ImportType, ScopeElement,
 };
 pub(crate) use local_var_collector::LocalVarCollector;
-pub(crate) use side_effect_detector::{ExpressionSideEffectDetector, SideEffectDetector};
+pub(crate) use side_effect_detector::{
+    ExpressionSideEffectDetector, SideEffectDetector, namedtuple_factory_of,
+};
 pub(crate) use symbol_usage_visitor::SymbolUsageVisitor;
 pub(crate) use variable_collector::VariableCollector;
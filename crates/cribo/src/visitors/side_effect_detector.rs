@@ -4,7 +4,7 @@
 //! and detecting whether they contain side effects.
 
 use ruff_python_ast::{
-    Expr, ModModule, Stmt, StmtAssign,
+    Expr, ExprCall, ModModule, Stmt, StmtAssign,
     visitor::{Visitor, walk_expr, walk_stmt},
 };
 use rustc_hash::FxHashSet;
@@ -21,6 +21,18 @@ pub(crate) struct SideEffectDetector {
     in_annotation_context: bool,
     /// Python version for stdlib detection
     python_version: u8,
+    /// Spellings of the named tuple factories bound by the module's imports
+    /// (e.g. `namedtuple`, `collections.namedtuple`, `t.NamedTuple`)
+    namedtuple_factories: FxHashSet<String>,
+}
+
+/// Named tuple class factory exported by a module, if any
+pub(crate) fn namedtuple_factory_of(module_name: &str) -> Option<&'static str> {
+    match module_name {
+        "collections" => Some("namedtuple"),
+        "typing" | "typing_extensions" => Some("NamedTuple"),
+        _ => None,
+    }
 }
 
 /// Simple expression visitor for checking side effects in a single expression
@@ -37,6 +49,7 @@ impl SideEffectDetector {
             in_expression_context: false,
             in_annotation_context: false,
             python_version,
+            namedtuple_factories: FxHashSet::default(),
         }
     }
 
@@ -78,6 +91,11 @@ impl SideEffectDetector {
             let module_name = alias.name.as_str();
             let local_name = alias.asname.as_ref().unwrap_or(&alias.name).as_str();
 
+            if let Some(factory) = namedtuple_factory_of(module_name) {
+                self.namedtuple_factories
+                    .insert(format!("{local_name}.{factory}"));
+            }
+
             // Skip imports that don't have side effects
             if !crate::side_effects::import_has_side_effects(module_name, self.python_version) {
                 continue;
@@ -96,6 +114,20 @@ impl SideEffectDetector {
 
     /// Helper to collect names from import-from statements
     fn collect_import_from_names(&mut self, import_from: &ruff_python_ast::StmtImportFrom) {
+        if import_from.level == 0
+            && let Some(factory) = import_from
+                .module
+                .as_ref()
+                .and_then(|module| namedtuple_factory_of(module.as_str()))
+        {
+            for alias in &import_from.names {
+                if alias.name.as_str() == factory {
+                    let local_name = alias.asname.as_ref().unwrap_or(&alias.name);
+                    self.namedtuple_factories.insert(local_name.to_string());
+                }
+            }
+        }
+
         // Skip imports that don't have side effects
         if !crate::side_effects::from_import_has_side_effects(import_from, self.python_version) {
             return;
@@ -117,6 +149,26 @@ impl SideEffectDetector {
         }
     }
 
+    /// Check if a call creates a named tuple class through an imported factory
+    /// (`collections.namedtuple('P', ['x', 'y'])` or `typing.NamedTuple('P', [('x', int)])`)
+    ///
+    /// Creating the class only evaluates its arguments, so such a call is free of side effects
+    /// whenever the arguments are. A `namedtuple` that is not bound by an import of
+    /// `collections` or `typing` may be anything and is treated as a regular call.
+    fn is_namedtuple_factory_call(&self, call: &ExprCall) -> bool {
+        let callee = match call.func.as_ref() {
+            Expr::Name(name) => name.id.to_string(),
+            Expr::Attribute(attr) => {
+                let Expr::Name(base) = attr.value.as_ref() else {
+                    return false;
+                };
+                format!("{}.{}", base.id, attr.attr)
+            }
+            _ => return false,
+        };
+        self.namedtuple_factories.contains(&callee)
+    }
+
     /// Check if an assignment is to __all__
     fn is_all_assignment(&self, assign: &StmtAssign) -> bool {
         if assign.targets.len() != 1 {
@@ -386,6 +438,17 @@ impl<'a> Visitor<'a> for SideEffectDetector {
                     return;
                 }
 
+                // Named tuple factories only evaluate their arguments
+                Expr::Call(call) if self.is_namedtuple_factory_call(call) => {
+                    for arg in &call.arguments.args {
+                        self.visit_expr(arg);
+                    }
+                    for keyword in &call.arguments.keywords {
+                        self.visit_expr(&keyword.value);
+                    }
+                    return;
+                }
+
                 // These expressions have side effects
                 // Lambda expressions are considered to have side effects to match old behavior
                 Expr::Call(_) | Expr::Lambda(_) => {
@@ -525,6 +588,39 @@ foo()  # This is a side effect
         assert!(SideEffectDetector::check_module(&module, 10));
     }
 
+    #[test]
+    fn test_no_side_effects_namedtuple_factories() {
+        let source = r#"
+import collections
+import typing as t
+from collections import namedtuple as nt
+
+Point = collections.namedtuple("Point", ["x", "y"])
+Pair = nt("Pair", "left right", defaults=(None,))
+Employee = t.NamedTuple("Employee", [("name", str), ("id", int)])
+"#;
+        let module = parse_python(source).expect("Failed to parse test Python code");
+        assert!(!SideEffectDetector::check_module(&module, 10));
+
+        // Not bound by an import of `collections`: an arbitrary call
+        let source = r#"
+def namedtuple(name, fields):
+    print(name)
+
+Point = namedtuple("Point", ["x", "y"])
+"#;
+        let module = parse_python(source).expect("Failed to parse test Python code");
+        assert!(SideEffectDetector::check_module(&module, 10));
+
+        let source = r#"
+from collections import namedtuple
+
+Point = namedtuple(make_name(), ["x", "y"])
+"#;
+        let module = parse_python(source).expect("Failed to parse test Python code");
+        assert!(SideEffectDetector::check_module(&module, 10));
+    }
+
     #[test]
     fn test_side_effects_imported_name() {
         let source = r"
@@ -50,6 +50,10 @@ const TYPE_HINT_IDENTIFIERS: &[&str] = &[
 pub(crate) struct SymbolUsageVisitor {
     /// Set of symbol names that are used in the body
     used_names: FxIndexSet<String>,
+    /// Names referenced in annotations, live only if annotations are read at runtime
+    annotation_names: FxIndexSet<String>,
+    /// Whether the body reads `__annotations__` (module-level or `SomeClass.__annotations__`)
+    reads_annotations: bool,
 }
 
 impl SymbolUsageVisitor {
@@ -59,9 +63,15 @@ impl SymbolUsageVisitor {
     }
 
     /// Collect all symbols used in a function body
+    ///
+    /// Names used only in annotations are included when the body reads `__annotations__`,
+    /// since the annotation objects are then inspected at runtime.
     pub(crate) fn collect_used_symbols(body: &[Stmt]) -> FxIndexSet<String> {
         let mut visitor = Self::new();
         visitor.visit_body(body);
+        if visitor.reads_annotations {
+            visitor.used_names.extend(visitor.annotation_names);
+        }
         visitor.used_names
     }
 
@@ -72,10 +82,12 @@ impl SymbolUsageVisitor {
 }
 
 impl<'a> Visitor<'a> for SymbolUsageVisitor {
-    fn visit_annotation(&mut self, _expr: &'a Expr) {
+    fn visit_annotation(&mut self, expr: &'a Expr) {
         // Don't track names in annotations - they're not runtime usage
         // By not calling the default walk, we skip all names in annotations
         // Note: This is only called for function returns, parameters, and AnnAssign
+        // They are remembered separately in case the body reads `__annotations__`
+        AnnotationNames(&mut self.annotation_names).visit_expr(expr);
     }
 
     fn visit_stmt(&mut self, stmt: &'a Stmt) {
@@ -105,8 +117,13 @@ impl<'a> Visitor<'a> for SymbolUsageVisitor {
             Expr::Name(name) => {
                 // Track the name - we're in runtime context
                 // (annotations are handled separately by visit_annotation)
+                self.reads_annotations |= name.id.as_str() == "__annotations__";
                 self.track_name(&name.id);
             }
+            Expr::Attribute(attr) if attr.attr.as_str() == "__annotations__" => {
+                self.reads_annotations = true;
+                visitor::walk_expr(self, expr);
+            }
             // For subscript expressions like List[str], the subscript part is annotation-like
             Expr::Subscript(subscript) if self.could_be_type_hint(&subscript.value) => {
                 // Visit the value part normally
@@ -114,11 +131,17 @@ impl<'a> Visitor<'a> for SymbolUsageVisitor {
                 // Don't visit the slice if this looks like a type hint
                 // (skip the subscript part of type hints like List[str])
             }
-            // typing.cast(T, expr) — treat T as annotation-only
+            // typing.cast(T, expr) — T is a type expression, but unlike an annotation it is
+            // evaluated when the call runs, so all of its names are used (including the
+            // subscripts of generics); only a string forward reference is not evaluated
             Expr::Call(call) if self.is_typing_cast(&call.func) => {
                 // Visit callee (runtime)
                 self.visit_expr(&call.func);
-                // Skip first positional arg (type annotation)
+                if let Some(type_expr) = call.arguments.args.first()
+                    && !type_expr.is_string_literal_expr()
+                {
+                    AnnotationNames(&mut self.used_names).visit_expr(type_expr);
+                }
                 // Visit remaining args/keywords (runtime)
                 for arg in call.arguments.args.iter().skip(1) {
                     self.visit_expr(arg);
@@ -135,6 +158,18 @@ impl<'a> Visitor<'a> for SymbolUsageVisitor {
     }
 }
 
+/// Collects every name referenced in an annotation expression
+struct AnnotationNames<'s>(&'s mut FxIndexSet<String>);
+
+impl<'a> Visitor<'a> for AnnotationNames<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Name(name) = expr {
+            self.0.insert(name.id.to_string());
+        }
+        visitor::walk_expr(self, expr);
+    }
+}
+
 impl SymbolUsageVisitor {
     /// Check if a function call is typing.cast or `typing_extensions.cast`
     ///
@@ -399,13 +434,14 @@ def regular_func(x: MyType, y: int = 42) -> MyReturnType:
     }
 
     #[test]
-    fn test_typing_cast_first_argument_not_counted() {
-        // Test that typing.cast first argument is treated as annotation-only
+    fn test_typing_cast_first_argument_counted_as_type_expression() {
+        // typing.cast evaluates its type argument at runtime, unless it is a string
         let code = r"
 from typing import cast
 import typing
 value = cast(MyType, some_expression)
-value2 = typing.cast(AnotherType, another_expression)
+value2 = typing.cast(List[AnotherType], another_expression)
+value3 = cast('LaterType', third_expression)
 result = str(value) + str(value2)
 ";
         let used = parse_and_collect(code);
@@ -418,9 +454,38 @@ result = str(value) + str(value2)
         assert!(used.contains("value2")); // Runtime usage (variable access)
         assert!(used.contains("str")); // Runtime usage (function call)
         assert!(used.contains("result")); // Runtime usage (variable assignment)
-        // Type annotations should not be counted
-        assert!(!used.contains("MyType")); // Type annotation (first arg to cast) - not runtime usage
-        assert!(!used.contains("AnotherType")); // Type annotation (first arg to cast) - not runtime usage
+        // Type expressions passed to cast are evaluated
+        assert!(used.contains("MyType"));
+        assert!(used.contains("AnotherType")); // Subscript of a generic is evaluated too
+        // String forward references are not
+        assert!(!used.contains("LaterType"));
+        assert!(used.contains("third_expression"));
+    }
+
+    #[test]
+    fn test_annotations_counted_when_annotations_are_read() {
+        let code = r"
+class Config:
+    host: HostName
+    port: Optional[PortNumber] = None
+fields = Config.__annotations__['port']
+
+def handler(request: Request) -> Response:
+    return request
+hints = __annotations__
+";
+        let used = parse_and_collect(code);
+        assert!(used.contains("HostName"));
+        assert!(used.contains("PortNumber"));
+        assert!(used.contains("Request"));
+        assert!(used.contains("Response"));
+
+        let used = parse_and_collect(
+            "x: MyType = 5
+y = x + 1
+",
+        );
+        assert!(!used.contains("MyType"));
     }
 
     #[test]
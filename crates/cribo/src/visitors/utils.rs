@@ -1,6 +1,6 @@
 //! Shared utilities for visitor implementations
 
-use ruff_python_ast::{Expr, ExprList, ExprName, ExprStringLiteral, ExprTuple};
+use ruff_python_ast::{Expr, ExprCall, ExprList, ExprName, ExprStringLiteral, ExprTuple};
 
 /// Result of extracting exports from an expression
 #[derive(Debug)]
@@ -100,6 +100,26 @@ fn collect_names_recursive<'a>(expr: &'a Expr, out: &mut Vec<&'a str>) {
     }
 }
 
+/// String literal `name` and `package` arguments of an `importlib.import_module` call, passed
+/// positionally or by keyword
+pub(crate) fn import_module_literal_arguments(call: &ExprCall) -> (Option<&str>, Option<&str>) {
+    let literal = |position: usize, keyword: &str| {
+        call.arguments
+            .args
+            .get(position)
+            .or_else(|| {
+                call.arguments
+                    .find_keyword(keyword)
+                    .map(|keyword| &keyword.value)
+            })
+            .and_then(|arg| match arg {
+                Expr::StringLiteral(ExprStringLiteral { value, .. }) => Some(value.to_str()),
+                _ => None,
+            })
+    };
+    (literal(0, "name"), literal(1, "package"))
+}
+
 #[cfg(test)]
 mod tests {
     use ruff_python_parser::parse_module;
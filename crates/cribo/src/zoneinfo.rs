@@ -0,0 +1,219 @@
+//! Embedding IANA timezone data into the bundle
+//!
+//! `zoneinfo.ZoneInfo` reads compiled TZif files from the system timezone database or from the
+//! `tzdata` package at runtime. Deployment targets such as slim containers often ship neither,
+//! so `--embed-zoneinfo` copies the requested zones into the bundle as base64 constants and
+//! replaces `zoneinfo.ZoneInfo` with a subclass that serves those keys from the embedded data
+//! and falls back to the regular lookup for every other key.
+
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use ruff_python_ast::Stmt;
+
+/// Directories CPython searches for timezone data when `PYTHONTZPATH` is not set
+const DEFAULT_TZPATH: &[&str] = &[
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+    "/etc/zoneinfo",
+];
+
+/// Magic bytes every compiled TZif file starts with
+const TZIF_MAGIC: &[u8] = b"TZif";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Compiled data of one timezone, keyed by its IANA name (e.g. `America/New_York`)
+#[derive(Debug, Clone)]
+pub(crate) struct EmbeddedZone {
+    pub key: String,
+    pub data: Vec<u8>,
+}
+
+/// Read the TZif files of `keys` from the timezone database on this machine
+pub(crate) fn load_zones(keys: &[String]) -> Result<Vec<EmbeddedZone>> {
+    let search_path = tzpath();
+    keys.iter()
+        .map(|key| {
+            validate_key(key)?;
+            let path = search_path
+                .iter()
+                .map(|dir| dir.join(key))
+                .find(|path| path.is_file())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Timezone '{key}' not found in the timezone database (searched: {})",
+                        display_paths(&search_path)
+                    )
+                })?;
+            let data = fs::read(&path)
+                .with_context(|| format!("Failed to read timezone file {}", path.display()))?;
+            if !data.starts_with(TZIF_MAGIC) {
+                return Err(anyhow!(
+                    "Timezone file {} is not a compiled TZif file",
+                    path.display()
+                ));
+            }
+            Ok(EmbeddedZone {
+                key: key.clone(),
+                data,
+            })
+        })
+        .collect()
+}
+
+/// Statements defining the embedded zones and installing the `ZoneInfo` subclass
+pub(crate) fn preamble(zones: &[EmbeddedZone]) -> Result<Vec<Stmt>> {
+    let mut entries = String::new();
+    for zone in zones {
+        writeln!(
+            entries,
+            "    \"{}\": \"{}\",",
+            zone.key,
+            base64_encode(&zone.data)
+        )
+        .expect("Writing to String never fails");
+    }
+
+    let source = format!(
+        r#"import base64 as _cribo_zoneinfo_base64
+import io as _cribo_zoneinfo_io
+import zoneinfo as _cribo_zoneinfo
+_cribo_embedded_zones = {{
+{entries}}}
+class _CriboZoneInfo(_cribo_zoneinfo.ZoneInfo):
+    _cribo_cache = {{}}
+    def __new__(cls, key):
+        if key not in _cribo_embedded_zones:
+            return super().__new__(cls, key)
+        if key not in cls._cribo_cache:
+            data = _cribo_zoneinfo_base64.b64decode(_cribo_embedded_zones[key])
+            cls._cribo_cache[key] = cls.from_file(_cribo_zoneinfo_io.BytesIO(data), key=key)
+        return cls._cribo_cache[key]
+_cribo_zoneinfo.ZoneInfo = _CriboZoneInfo
+"#
+    );
+
+    let parsed = ruff_python_parser::parse_module(&source)
+        .map_err(|err| anyhow!("Failed to build the embedded timezone preamble: {err}"))?;
+    Ok(parsed.into_syntax().body)
+}
+
+/// Directories searched for timezone data, honoring `PYTHONTZPATH` like CPython does
+fn tzpath() -> Vec<PathBuf> {
+    match env::var_os("PYTHONTZPATH") {
+        Some(value) => env::split_paths(&value)
+            .filter(|path| path.is_absolute())
+            .collect(),
+        None => DEFAULT_TZPATH.iter().map(PathBuf::from).collect(),
+    }
+}
+
+/// Reject keys that would escape the timezone database or break the generated literal
+fn validate_key(key: &str) -> Result<()> {
+    let valid = key.split('/').all(|part| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Invalid timezone key '{key}': expected an IANA name such as 'America/New_York'"
+        ))
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Standard base64 with padding, as decoded by `base64.b64decode`
+fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let group = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3F;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_python_ast::Expr;
+
+    use super::*;
+
+    #[test]
+    fn test_base64_matches_python_encoding() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"TZif"), "VFppZg==");
+        assert_eq!(base64_encode(b"TZif2"), "VFppZjI=");
+        assert_eq!(base64_encode(b"TZif23"), "VFppZjIz");
+    }
+
+    #[test]
+    fn test_zone_keys_are_validated() {
+        assert!(validate_key("America/New_York").is_ok());
+        assert!(validate_key("Etc/GMT+5").is_ok());
+        assert!(validate_key("../etc/passwd").is_err());
+        assert!(validate_key("/etc/localtime").is_err());
+        assert!(validate_key("America/New York").is_err());
+    }
+
+    #[test]
+    fn test_preamble_installs_zoneinfo_subclass() {
+        let zones = [EmbeddedZone {
+            key: "Europe/Paris".to_owned(),
+            data: b"TZif2".to_vec(),
+        }];
+        let body = preamble(&zones).expect("Preamble should parse");
+
+        let zones_dict = body
+            .iter()
+            .find_map(|stmt| match stmt {
+                Stmt::Assign(assign) => assign.value.as_dict_expr(),
+                _ => None,
+            })
+            .expect("Preamble should define the embedded zones");
+        let [item] = zones_dict.items.as_slice() else {
+            panic!("Expected one embedded zone");
+        };
+        let string_value = |expr: Option<&Expr>| {
+            expr.and_then(Expr::as_string_literal_expr)
+                .map(|literal| literal.value.to_str().to_owned())
+        };
+        assert_eq!(
+            string_value(item.key.as_ref()).as_deref(),
+            Some("Europe/Paris")
+        );
+        assert_eq!(string_value(Some(&item.value)).as_deref(), Some("VFppZjI="));
+
+        assert!(body.iter().any(|stmt| matches!(
+            stmt,
+            Stmt::ClassDef(class_def) if class_def.name.as_str() == "_CriboZoneInfo"
+        )));
+    }
+}
@@ -0,0 +1,15 @@
+"""Containers parameterized with Item imported from models.
+
+When Item gets renamed due to collision, the type arguments of the
+generic bases need to keep referring to the models class.
+"""
+
+from models import Item
+
+
+class ItemList(list[Item]):
+    """List of items."""
+
+
+class ItemMap(dict[str, Item]):
+    """Items by name."""
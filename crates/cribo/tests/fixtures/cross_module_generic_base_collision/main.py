@@ -0,0 +1,14 @@
+"""Test renamed classes used as type arguments of generic bases."""
+
+# Import both Item implementations to create collision
+from models import Item
+from other_models import Item as OtherItem
+
+# Import the containers whose bases are list[Item] and dict[str, Item]
+from containers import ItemList, ItemMap
+
+items = ItemList([Item("a")])
+mapping = ItemMap(b=Item("b"))
+print(items[0], mapping["b"])
+print(ItemList.__orig_bases__[0].__args__[0] is Item)
+print(ItemMap.__orig_bases__[0].__args__ == (str, Item))
@@ -0,0 +1,11 @@
+"""Models module with Item."""
+
+
+class Item:
+    """Item stored in the containers."""
+
+    def __init__(self, name):
+        self.name = name
+
+    def __repr__(self):
+        return f"<Item {self.name}>"
@@ -0,0 +1,11 @@
+"""Another models module with Item to create collision."""
+
+
+class Item:
+    """Different Item implementation."""
+
+    def __init__(self, sku):
+        self.sku = sku
+
+    def __repr__(self):
+        return f"<OtherItem {self.sku}>"
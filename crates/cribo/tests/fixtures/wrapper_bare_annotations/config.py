@@ -0,0 +1,6 @@
+# Bare annotations declare a type without binding the name
+DEBUG: bool
+source: str
+
+source = "config"
+print("config loaded")
@@ -0,0 +1,5 @@
+# Test that bare module-level annotations in a wrapper module stay unbound
+import config
+
+print(config.source)
+print(hasattr(config, "DEBUG"))
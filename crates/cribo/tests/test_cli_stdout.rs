@@ -1,8 +1,16 @@
 #![expect(clippy::disallowed_methods)] // insta macros use unwrap internally
 
-use std::{env, process::Command};
+mod common;
+
+use std::{
+    env, fs,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
 
 use insta::{assert_snapshot, with_settings};
+use tempfile::TempDir;
 
 /// Helper function to get the path to a fixture file
 fn get_fixture_path(relative_path: &str) -> String {
@@ -82,6 +90,47 @@ fn get_cli_filters() -> Vec<(&'static str, &'static str)> {
     ]
 }
 
+/// Run bundled code through Python via stdin and return its stdout
+///
+/// The code runs from an empty directory, so it cannot fall back to importing the original
+/// modules.
+fn run_bundled_python(bundled_code: &str) -> String {
+    let work_dir = TempDir::new().expect("Failed to create temp dir");
+    let output = Command::new(common::get_python_executable())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(work_dir.path())
+        .env("PYTHONIOENCODING", "utf-8")
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(bundled_code.as_bytes())?;
+            }
+            child.wait_with_output()
+        })
+        .expect("Failed to execute Python");
+
+    assert!(
+        output.status.success(),
+        "Bundled code failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n")
+}
+
+/// Write `(relative path, contents)` pairs below `root`
+fn write_files(root: &Path, files: &[(&str, &str)]) {
+    for (relative_path, contents) in files {
+        let path = root.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create directory");
+        }
+        fs::write(path, contents).expect("Failed to write file");
+    }
+}
+
 #[test]
 fn test_stdout_flag_help() {
     let (stdout, _, exit_code) = run_cribo(&["--help"]);
@@ -296,3 +345,202 @@ fn test_directory_entry_empty_fails() {
     // Should contain appropriate error message (checks __init__.py first)
     assert!(stderr.contains("does not contain __init__.py or __main__.py"));
 }
+
+#[test]
+fn test_embed_stdlib_keeps_startup_modules() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let stdlib = temp_dir.path().join("stdlib");
+    let project = temp_dir.path().join("project");
+    write_files(
+        &stdlib,
+        &[
+            ("textwrap.py", "marker = \"embedded textwrap\"\n"),
+            ("os.py", "sep = \"embedded os\"\n"),
+        ],
+    );
+    write_files(
+        &project,
+        &[(
+            "main.py",
+            "import os\nimport textwrap\n\nprint(textwrap.marker)\nprint(os.sep == \"embedded \
+             os\")\n",
+        )],
+    );
+
+    let (stdout, stderr, exit_code) = run_cribo(&[
+        "--entry",
+        &project.join("main.py").to_string_lossy(),
+        "--embed-stdlib",
+        &stdlib.to_string_lossy(),
+        "--stdout",
+    ]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+
+    // `textwrap` is bundled from the stdlib directory, `os` is already imported at startup
+    assert!(stdout.contains("embedded textwrap"));
+    assert!(!stdout.contains("embedded os"));
+    assert_eq!(run_bundled_python(&stdout), "embedded textwrap\nFalse\n");
+}
+
+#[test]
+fn test_output_module_name_keeps_main_guard_working() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_files(
+        temp_dir.path(),
+        &[
+            (
+                "library/main.py",
+                "class Widget:\n    pass\n\nprint(__name__, Widget.__module__)\n",
+            ),
+            (
+                "script/main.py",
+                "def main():\n    print(\"main ran\")\n\nif __name__ == \"__main__\":\n    \
+                 main()\n",
+            ),
+        ],
+    );
+
+    // Without a `__main__` guard the bundle reports the requested name
+    let (stdout, stderr, exit_code) = run_cribo(&[
+        "--entry",
+        &temp_dir.path().join("library/main.py").to_string_lossy(),
+        "--output-module-name",
+        "mylib",
+        "--stdout",
+    ]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+    assert_eq!(run_bundled_python(&stdout), "mylib mylib\n");
+
+    // With a `__main__` guard the name is left alone so the guard still runs
+    let (stdout, stderr, exit_code) = run_cribo(&[
+        "--entry",
+        &temp_dir.path().join("script/main.py").to_string_lossy(),
+        "--output-module-name",
+        "mylib",
+        "--stdout",
+    ]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+    assert!(!stdout.contains("__name__ = \"mylib\""));
+    assert_eq!(run_bundled_python(&stdout), "main ran\n");
+}
+
+#[test]
+fn test_prune_unused_modules_keeps_side_effects() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_files(
+        temp_dir.path(),
+        &[
+            (
+                "main.py",
+                "from pruned_helpers import helper\nfrom noisy_setup import \
+                 unused\n\nprint(\"main done\")\n",
+            ),
+            ("pruned_helpers.py", "def helper():\n    return 1\n"),
+            (
+                "noisy_setup.py",
+                "print(\"noisy setup loaded\")\n\ndef unused():\n    return 2\n",
+            ),
+        ],
+    );
+
+    let (stdout, stderr, exit_code) = run_cribo(&[
+        "--entry",
+        &temp_dir.path().join("main.py").to_string_lossy(),
+        "--prune-unused-modules",
+        "--stdout",
+    ]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+
+    // Nothing of `pruned_helpers` is used, `noisy_setup` prints when imported
+    assert!(!stdout.contains("pruned_helpers"));
+    assert!(stdout.contains("noisy_setup"));
+    assert_eq!(
+        run_bundled_python(&stdout),
+        "noisy setup loaded\nmain done\n"
+    );
+}
+
+#[test]
+fn test_no_deduplicate_imports_keeps_repeated_imports() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_files(
+        temp_dir.path(),
+        &[
+            (
+                "main.py",
+                "import vendorlib as vl\nfrom helpers import helper\n\nprint(helper(), vl.VALUE)\n",
+            ),
+            (
+                "helpers.py",
+                "import vendorlib as vl\n\ndef helper():\n    return vl.VALUE\n",
+            ),
+        ],
+    );
+    let entry = temp_dir.path().join("main.py");
+    let entry = entry.to_string_lossy();
+
+    // `vendorlib` is not bundled, so both modules keep importing it at runtime
+    let (stdout, stderr, exit_code) = run_cribo(&["--entry", &entry, "--stdout"]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+    assert_eq!(stdout.matches("import vendorlib as vl").count(), 1);
+
+    let (stdout, stderr, exit_code) =
+        run_cribo(&["--entry", &entry, "--no-deduplicate-imports", "--stdout"]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+    assert_eq!(stdout.matches("import vendorlib as vl").count(), 2);
+}
+
+#[test]
+fn test_parallel_output_matches_sequential() {
+    let entry = get_fixture_path("ast_rewriting_mixed_collisions/main.py");
+    let (sequential, stderr, exit_code) = run_cribo(&["--entry", &entry, "--stdout"]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+
+    for threads in ["0", "4"] {
+        let (parallel, stderr, exit_code) =
+            run_cribo(&["--entry", &entry, "--parallel", threads, "--stdout"]);
+        assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+        assert_eq!(
+            parallel, sequential,
+            "--parallel {threads} changed the bundle"
+        );
+    }
+}
+
+#[test]
+fn test_emit_ast_writes_bundled_module_as_json() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let bundle_path = temp_dir.path().join("bundle.py");
+    let ast_path = temp_dir.path().join("bundle_ast.json");
+    let (_, stderr, exit_code) = run_cribo(&[
+        "--entry",
+        &get_fixture_path("simple_project/main.py"),
+        "--output",
+        &bundle_path.to_string_lossy(),
+        "--emit-ast",
+        &ast_path.to_string_lossy(),
+    ]);
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+
+    // The dump is the module the bundle was generated from: one JSON node per top-level
+    // statement of the bundle
+    let ast_json = fs::read_to_string(&ast_path).expect("Failed to read AST dump");
+    let ast: serde_json::Value = serde_json::from_str(&ast_json).expect("AST dump is not JSON");
+    let body = ast["body"].as_array().expect("AST dump has no body");
+    assert!(body.iter().all(serde_json::Value::is_object));
+    assert!(ast_json.contains("\"_cribo\""));
+
+    let statement_count = Command::new(common::get_python_executable())
+        .args([
+            "-c",
+            "import ast, sys; print(len(ast.parse(open(sys.argv[1], \
+             encoding=\"utf-8\").read()).body))",
+        ])
+        .arg(&bundle_path)
+        .output()
+        .expect("Failed to execute Python");
+    assert_eq!(
+        String::from_utf8_lossy(&statement_count.stdout).trim(),
+        body.len().to_string()
+    );
+}